@@ -0,0 +1,76 @@
+//! Measures the cost of tokenizing the command-line for a typical invocation
+//! and for invocations made of mostly positionals and of mostly flags.
+
+use cliproc::Cli;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+
+/// Creates an invocation of `size` file paths.
+fn positionals(size: usize) -> Vec<String> {
+    std::iter::once(String::from("wc"))
+        .chain((0..size).map(|i| format!("src/file-{}.rs", i)))
+        .collect()
+}
+
+/// Creates an invocation of `size` flags, each with an attached value.
+fn flags(size: usize) -> Vec<String> {
+    std::iter::once(String::from("build"))
+        .chain((0..size).map(|i| format!("--feature-{}=on", i % 16)))
+        .collect()
+}
+
+fn parse_typical(c: &mut Criterion) {
+    let line: Vec<String> = [
+        "cargo",
+        "build",
+        "--release",
+        "-j",
+        "8",
+        "--features=serde,url",
+        "--target",
+        "x86_64-unknown-linux-gnu",
+        "-vv",
+    ]
+    .iter()
+    .map(|w| w.to_string())
+    .collect();
+    c.bench_function("parse_typical", |b| {
+        b.iter_batched(
+            || line.clone(),
+            |line| Cli::new().parse(line.into_iter()),
+            BatchSize::SmallInput,
+        )
+    });
+}
+
+fn parse_positionals(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_positionals");
+    for size in [10, 100, 1000] {
+        let line = positionals(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &line, |b, line| {
+            b.iter_batched(
+                || line.clone(),
+                |line| Cli::new().with_capacity(64).parse(line.into_iter()),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn parse_flags(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_flags");
+    for size in [10, 100, 1000] {
+        let line = flags(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &line, |b, line| {
+            b.iter_batched(
+                || line.clone(),
+                |line| Cli::new().with_capacity(64).parse(line.into_iter()),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse_typical, parse_positionals, parse_flags);
+criterion_main!(benches);
@@ -0,0 +1,88 @@
+//! Measures the latency of spelling suggestions on the error path when the
+//! bank of known subcommands and flags is large.
+
+use cliproc::{cli, proc, stage::Memory, Arg, Cli, Subcommand};
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use std::cell::RefCell;
+
+thread_local! {
+    // the subcommands known to the plugin system under measurement
+    static PLUGINS: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+#[derive(Debug)]
+struct Plugin;
+
+impl Subcommand<()> for Plugin {
+    fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+        PLUGINS.with(|bank| cli.select(&bank.borrow()))?;
+        Ok(Plugin)
+    }
+
+    fn execute(self, _: &()) -> proc::Result {
+        Ok(())
+    }
+}
+
+fn bank(size: usize) -> Vec<String> {
+    (0..size).map(|i| format!("plugin-{}-install", i)).collect()
+}
+
+fn args(words: &[&str]) -> impl Iterator<Item = String> {
+    words
+        .iter()
+        .map(|w| w.to_string())
+        .collect::<Vec<String>>()
+        .into_iter()
+}
+
+fn suggest_subcommand(c: &mut Criterion) {
+    let mut group = c.benchmark_group("suggest_subcommand");
+    for size in [10, 100, 1000] {
+        PLUGINS.with(|plugins| *plugins.borrow_mut() = bank(size));
+        group.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter_batched(
+                || {
+                    Cli::new()
+                        .threshold(3)
+                        .parse(args(&["orbit", "plugin-7-instlal"]))
+                        .save()
+                },
+                |mut cli| {
+                    cli.nest::<Plugin, ()>(Arg::subcommand("plugin"))
+                        .unwrap_err()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn suggest_flag(c: &mut Criterion) {
+    let mut group = c.benchmark_group("suggest_flag");
+    for size in [10, 100, 1000] {
+        let bank = bank(size);
+        group.bench_with_input(BenchmarkId::from_parameter(size), &bank, |b, bank| {
+            b.iter_batched(
+                || {
+                    Cli::new()
+                        .threshold_auto()
+                        .parse(args(&["orbit", "--plugin-7-instlal"]))
+                        .save()
+                },
+                |mut cli| -> cli::Result<()> {
+                    for name in bank {
+                        cli.check(Arg::flag(name))?;
+                    }
+                    cli.empty()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, suggest_subcommand, suggest_flag);
+criterion_main!(benches);
@@ -32,9 +32,6 @@ impl Command for Copy {
         cli.raise_help()?;
         cli.lower_help();
 
-        let version = cli.check(Arg::flag("version"))?;
-        let list = cli.check(Arg::flag("list"))?;
-
         cli.help(Help::with("a short description of this program"))?;
         Ok(Self {
             verbose: cli.check(Arg::flag("verbose"))?,
@@ -46,20 +43,18 @@ impl Command for Copy {
             shells: cli
                 .get_all(Arg::option("shell").switch('s').value("key=value"))?
                 .unwrap_or_default(),
-            src: match list | version {
-                false => cli.require(Arg::positional("src"))?,
-                true => {
-                    let _ = cli.get::<PathBuf>(Arg::positional("src"));
-                    PathBuf::new()
-                }
-            },
-            dest: match list | version {
-                false => cli.require(Arg::positional("dest"))?,
-                true => {
-                    let _ = cli.get::<PathBuf>(Arg::positional("dest"));
-                    PathBuf::new()
-                }
-            },
+            src: cli
+                .require_unless(
+                    Arg::positional("src"),
+                    &[Arg::flag("list"), Arg::flag("version")],
+                )?
+                .unwrap_or_default(),
+            dest: cli
+                .require_unless(
+                    Arg::positional("dest"),
+                    &[Arg::flag("list"), Arg::flag("version")],
+                )?
+                .unwrap_or_default(),
         })
     }
 
@@ -0,0 +1,25 @@
+#![no_main]
+
+use cliproc::{Arg, Cli};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    // arguments are separated by null bytes, similar to how they are stored in memory
+    let args: Vec<String> = data
+        .split(|b| *b == 0)
+        .map(|a| String::from_utf8_lossy(a).into_owned())
+        .collect();
+
+    let cli = Cli::new().parse(args.into_iter());
+    cli.check_invariants().unwrap();
+
+    // processing the command-line must never panic, regardless of the arguments
+    let mut cli = cli.save();
+    let _ = cli.check(Arg::flag("verbose").switch('v'));
+    let _ = cli.get_all::<String>(Arg::option("output").switch('o'));
+    cli.check_invariants().unwrap();
+    let _ = cli.get::<String>(Arg::positional("path"));
+    let _ = cli.remainder();
+    cli.check_invariants().unwrap();
+    let _ = cli.empty();
+});
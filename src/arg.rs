@@ -1,6 +1,67 @@
+use crate::complete::{self, Completer, CompleterRef};
+use crate::value::PathResolution;
 use std::fmt::Debug;
 use std::fmt::Display;
 use std::marker::PhantomData;
+use std::ops::Bound::{self, *};
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// The bounds of a value's range, written as text.
+type ValueRange = (Bound<String>, Bound<String>);
+
+/// Writes the value of the `bound` as text.
+fn bound_text<V: Display>(bound: Bound<&V>) -> Bound<String> {
+    match bound {
+        Included(v) => Included(v.to_string()),
+        Excluded(v) => Excluded(v.to_string()),
+        Unbounded => Unbounded,
+    }
+}
+
+/// The range a value must fall within, compared against the value once it is
+/// parsed as the type of the bounds.
+#[derive(Clone)]
+pub(crate) struct RangeCheck {
+    bounds: ValueRange,
+    contains: Arc<RangeFn>,
+}
+
+/// Checks if a raw value falls within a range, or returns why it cannot be parsed.
+type RangeFn = dyn Fn(&str) -> Result<bool, String> + Send + Sync;
+
+impl RangeCheck {
+    pub fn new<V, R>(range: R) -> Self
+    where
+        V: PartialOrd + FromStr + Display + Clone + Send + Sync + 'static,
+        <V as FromStr>::Err: std::error::Error,
+        R: RangeBounds<V>,
+    {
+        let start = range.start_bound().cloned();
+        let end = range.end_bound().cloned();
+        Self {
+            bounds: (bound_text(start.as_ref()), bound_text(end.as_ref())),
+            contains: Arc::new(move |word: &str| {
+                let value = word.parse::<V>().map_err(|e| e.to_string())?;
+                Ok((start.as_ref(), end.as_ref()).contains(&value))
+            }),
+        }
+    }
+}
+
+impl PartialEq for RangeCheck {
+    fn eq(&self, other: &Self) -> bool {
+        self.bounds == other.bounds
+    }
+}
+
+impl Debug for RangeCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "RangeCheck({:?})", self.bounds)
+    }
+}
 
 /// An argument type that can be switched on/off.
 pub struct Raisable {}
@@ -8,9 +69,17 @@ pub struct Raisable {}
 /// An argument type that can store a value.
 pub struct Valuable {}
 
+/// An argument type that can store a value of the type `T`.
+pub struct Typed<T> {
+    _marker: PhantomData<T>,
+}
+
 /// An argument type that can be invoked to take an action.
 pub struct Callable {}
 
+/// An argument type that can store values under keys that are not known ahead of time.
+pub struct Dynamic {}
+
 /// The typestate pattern for the different arguments that are possible on
 /// the command-line.
 pub trait ArgState {}
@@ -18,6 +87,39 @@ pub trait ArgState {}
 impl ArgState for Raisable {}
 impl ArgState for Callable {}
 impl ArgState for Valuable {}
+impl ArgState for Dynamic {}
+impl<T> ArgState for Typed<T> {}
+
+/// The argument types that can store a value.
+pub trait ValueState: ArgState {}
+
+impl ValueState for Valuable {}
+impl<T> ValueState for Typed<T> {}
+
+/// An argument that can be requested for a value of type `T`.
+///
+/// An argument created with [option_of][Arg::option_of] or [positional_of][Arg::positional_of]
+/// can only be requested for its own type, while any other option or positional
+/// can be requested for any type.
+pub trait ValueArg<T> {
+    #[doc(hidden)]
+    fn into_valuable(self) -> Arg<Valuable>;
+}
+
+impl<T> ValueArg<T> for Arg<Valuable> {
+    fn into_valuable(self) -> Arg<Valuable> {
+        self
+    }
+}
+
+impl<T> ValueArg<T> for Arg<Typed<T>> {
+    fn into_valuable(self) -> Arg<Valuable> {
+        Arg {
+            data: self.data,
+            _marker: PhantomData::<Valuable>,
+        }
+    }
+}
 
 /// A container for data provided on the command-line.
 #[derive(PartialEq)]
@@ -26,6 +128,13 @@ pub struct Arg<S: ArgState> {
     _marker: PhantomData<S>,
 }
 
+impl<S: ArgState> Arg<S> {
+    /// References the underlying argument.
+    pub(crate) fn get_type(&self) -> &ArgType {
+        &self.data
+    }
+}
+
 impl<S: ArgState> From<Arg<S>> for ArgType {
     fn from(value: Arg<S>) -> Self {
         value.data
@@ -41,6 +150,16 @@ impl Arg<Raisable> {
         }
     }
 
+    /// Create a new flag argument that is only recognized by its switch `c`.
+    ///
+    /// The flag does not have a long form, so `-c` is accepted while `--c` is not.
+    pub fn switch_only(c: char) -> Arg<Raisable> {
+        Self {
+            data: ArgType::Flag(Flag::switch_only(c)),
+            _marker: PhantomData::<Raisable>,
+        }
+    }
+
     /// Specify the switch character that is also associated with this flag.
     pub fn switch(self, c: char) -> Self {
         Self {
@@ -48,6 +167,67 @@ impl Arg<Raisable> {
             _marker: PhantomData::<Raisable>,
         }
     }
+
+    /// Specify an alternate name that is also associated with this flag.
+    ///
+    /// Each name refers to the same argument, so supplying the flag by its
+    /// name and by its alias counts as multiple occurrences.
+    pub fn alias<T: AsRef<str>>(self, name: T) -> Self {
+        Self {
+            data: ArgType::Flag(self.data.into_flag().unwrap().alias(name)),
+            _marker: PhantomData::<Raisable>,
+        }
+    }
+
+    /// Specify how the flag is processed by [count][crate::Cli::count].
+    ///
+    /// Flags are [SetTrue][Action::SetTrue] by default.
+    pub fn action(self, action: Action) -> Self {
+        Self {
+            data: ArgType::Flag(self.data.into_flag().unwrap().action(action)),
+            _marker: PhantomData::<Raisable>,
+        }
+    }
+
+    /// Marks the flag as eager, such that raising it stops interpretation to
+    /// display the informational text `text` (ex: `--version`).
+    ///
+    /// Like [Help][crate::Help], an eager flag is reported as an error that exits
+    /// successfully once it is checked. Declare the flag with
+    /// [eager][crate::Cli::eager] for it to take priority over the errors of
+    /// arguments that are requested before it.
+    pub fn eager<T: AsRef<str>>(self, text: T) -> Self {
+        Self {
+            data: ArgType::Flag(self.data.into_flag().unwrap().eager(text)),
+            _marker: PhantomData::<Raisable>,
+        }
+    }
+
+    /// Only accept the flag when the subcommand `name` is chosen (ex: `--release`
+    /// is only valid with `build`).
+    ///
+    /// Call multiple times to accept the flag with multiple subcommands. Raising
+    /// the flag with any other subcommand, or with no subcommand, is reported as
+    /// an error once the subcommand is [nested][crate::Cli::nest].
+    pub fn only_with<T: AsRef<str>>(self, name: T) -> Self {
+        Self {
+            data: ArgType::Flag(self.data.into_flag().unwrap().only_with(name)),
+            _marker: PhantomData::<Raisable>,
+        }
+    }
+
+    /// Marks the flag as experimental, such that it is only accepted when the
+    /// environment variable `var` is set or unstable arguments are enabled on the
+    /// command-line, see [check_unstable][crate::Cli::check_unstable].
+    ///
+    /// Raising the flag otherwise is reported as an error, and the flag is hidden
+    /// from a [HelpSpec][crate::HelpSpec]'s options table.
+    pub fn unstable<T: AsRef<str>>(self, var: T) -> Self {
+        Self {
+            data: ArgType::Flag(self.data.into_flag().unwrap().unstable(var)),
+            _marker: PhantomData::<Raisable>,
+        }
+    }
 }
 
 impl Arg<Valuable> {
@@ -67,6 +247,28 @@ impl Arg<Valuable> {
         }
     }
 
+    /// Create a new option argument whose value is of the type `T`.
+    ///
+    /// The value's type is inferred when the argument is requested, so mismatched
+    /// types are caught at compile-time (ex: `cli.get(Arg::option_of::<u16>("port"))`).
+    pub fn option_of<T: FromStr>(name: impl AsRef<str>) -> Arg<Typed<T>> {
+        Arg {
+            data: ArgType::Optional(Optional::new(name).type_name(std::any::type_name::<T>())),
+            _marker: PhantomData::<Typed<T>>,
+        }
+    }
+
+    /// Create a new positional argument whose value is of the type `T`, see
+    /// [option_of][Arg::option_of].
+    pub fn positional_of<T: FromStr>(name: impl AsRef<str>) -> Arg<Typed<T>> {
+        Arg {
+            data: ArgType::Positional(Positional::new(name).type_name(std::any::type_name::<T>())),
+            _marker: PhantomData::<Typed<T>>,
+        }
+    }
+}
+
+impl<S: ValueState> Arg<S> {
     /// Specify the name of the value that is associated with this argument.
     ///
     /// This function only modifies arguments that were created as options, and
@@ -81,11 +283,203 @@ impl Arg<Valuable> {
         }
     }
 
+    /// Specify the kind of value that is expected for this argument.
+    ///
+    /// The hint does not affect parsing. When the argument has no
+    /// [completer][Arg::completer], the hint provides the candidates for its
+    /// value during shell completion (ex: matching files for a
+    /// [ValueHint::FilePath]).
+    pub fn hint(self, hint: ValueHint) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.hint(hint)),
+                ArgType::Positional(p) => ArgType::Positional(p.hint(hint)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify the `completer` that provides the candidates for this argument's
+    /// value during dynamic shell completion, see [complete][crate::complete].
+    pub fn completer<C: Completer + Send + Sync + 'static>(self, completer: C) -> Self {
+        let completer = CompleterRef::new(completer);
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.completer(completer)),
+                ArgType::Positional(p) => ArgType::Positional(p.completer(completer)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify how the argument is processed by [values][crate::Cli::values].
+    ///
+    /// Options and positionals are [Set][Action::Set] by default.
+    pub fn action(self, action: Action) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.action(action)),
+                ArgType::Positional(p) => ArgType::Positional(p.action(action)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify the range that the value must fall within (ex: `1..=256`).
+    ///
+    /// The raw value is parsed as the type of the bounds and compared against
+    /// them before it is parsed as the requested type. A value that cannot be
+    /// parsed as the type of the bounds is rejected (ex: `0x10` for `1..=8`).
+    pub fn range<V, R>(self, range: R) -> Self
+    where
+        V: PartialOrd + FromStr + Display + Clone + Send + Sync + 'static,
+        <V as FromStr>::Err: std::error::Error,
+        R: RangeBounds<V>,
+    {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.range(range)),
+                ArgType::Positional(p) => ArgType::Positional(p.range(range)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify the `value` that is used when the argument is not supplied on the
+    /// command-line.
+    ///
+    /// The value is parsed and verified the same as a supplied value, so it is
+    /// subject to the same errors.
+    pub fn default<T: AsRef<str>>(self, value: T) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.default(value)),
+                ArgType::Positional(p) => ArgType::Positional(p.default(value)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify the `value` that is used when the argument is not supplied on the
+    /// command-line but `flag` is raised (ex: `--format` defaults to `json` with
+    /// `--machine`).
+    ///
+    /// Conditional defaults are checked in the order they are declared, before
+    /// falling back to the [default][Arg::default]. The `flag` is raised if it is
+    /// supplied on the command-line, regardless of whether it was requested.
+    pub fn default_if<T: AsRef<str>>(self, flag: Arg<Raisable>, value: T) -> Self {
+        let flag = ArgType::from(flag).into_flag().unwrap();
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.default_if(flag, value)),
+                ArgType::Positional(p) => ArgType::Positional(p.default_if(flag, value)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify an example usage of this argument (ex: `--when 2024-01-01`).
+    ///
+    /// The example is displayed when a value fails to be parsed.
+    pub fn example<T: AsRef<str>>(self, example: T) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.example(example)),
+                ArgType::Positional(p) => ArgType::Positional(p.example(example)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Allow the value to reference a file by beginning with `@` (ex: `--message @msg.txt`),
+    /// in which case the value is replaced by the contents of the file before it is parsed.
+    ///
+    /// This is useful for long values that would otherwise need to be quoted on the
+    /// command-line.
+    pub fn allow_file_ref(self) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.file_ref()),
+                ArgType::Positional(p) => ArgType::Positional(p.file_ref()),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Resolve a relative path value against the directory `base` (ex: a project
+    /// root discovered earlier) before it is parsed.
+    ///
+    /// By default, the value is joined onto `base` as it was written. To normalize
+    /// or canonicalize the resulting path, use [resolve_paths][Arg::resolve_paths].
+    pub fn relative_to<P: AsRef<Path>>(self, base: P) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.base(base)),
+                ArgType::Positional(p) => ArgType::Positional(p.base(base)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify how a path value is resolved against the directory set by
+    /// [relative_to][Arg::relative_to], including whether symbolic links are followed.
+    pub fn resolve_paths(self, resolution: PathResolution) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.resolution(resolution)),
+                ArgType::Positional(p) => ArgType::Positional(p.resolution(resolution)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify a regular expression that the raw value must match before it is parsed.
+    ///
+    /// An invalid `pattern` is considered a programmer's error and will result
+    /// in a panic! when the argument is built.
+    #[cfg(feature = "regex")]
+    pub fn matches<T: AsRef<str>>(self, pattern: T) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.pattern(pattern)),
+                ArgType::Positional(p) => ArgType::Positional(p.pattern(pattern)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify the URL schemes that are accepted for the raw value before it is parsed.
+    ///
+    /// This also hints the value is a [ValueHint::Url]. Values that cannot be
+    /// parsed as a URL are left to be reported by the value's type.
+    #[cfg(feature = "url")]
+    pub fn url<T: AsRef<str>>(self, schemes: &[T]) -> Self {
+        Self {
+            data: match self.data {
+                ArgType::Optional(o) => ArgType::Optional(o.schemes(schemes)),
+                ArgType::Positional(p) => ArgType::Positional(p.schemes(schemes)),
+                ArgType::Flag(f) => ArgType::Flag(f),
+            },
+            _marker: self._marker,
+        }
+    }
+
     /// Specify the switch character that is associated with this argument.
     ///
     /// This function only modifies arguments that were created as options, and
     /// silently leaves any other arguments unmodified.
-    pub fn switch(self, c: char) -> Arg<Valuable> {
+    pub fn switch(self, c: char) -> Self {
         Self {
             data: match self.data.is_option() {
                 true => ArgType::Optional(self.data.into_option().unwrap().switch(c)),
@@ -94,6 +488,147 @@ impl Arg<Valuable> {
             _marker: self._marker,
         }
     }
+
+    /// Specify an alternate name that is also associated with this argument.
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn alias<T: AsRef<str>>(self, name: T) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().alias(name)),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Only accept a value that is attached to the option with `=` (ex: `--color=never`),
+    /// so the following argument is never taken as its value.
+    ///
+    /// Combine with [default_missing][Arg::default_missing] to allow the option to
+    /// be supplied without a value.
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn attached_only(self) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().attached_only()),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Require the value to be attached to the option with `=` (ex: `--key=value`),
+    /// reporting the separated form (ex: `--key value`) as an error that suggests
+    /// attaching the value.
+    ///
+    /// Like [attached_only][Arg::attached_only], the following argument is never
+    /// taken as the option's value.
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn require_equals(self) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().equals()),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Accept a value that begins with a dash (ex: `--filter --name-only`), so the
+    /// argument following the option is taken as its value even if it looks like
+    /// a flag.
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn allow_hyphen_values(self) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().hyphen_values()),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Only accept the option when it is supplied before the subcommand (ex:
+    /// `git -C dir1 -C dir2 status`).
+    ///
+    /// Instances of the option written after the subcommand's name are left for
+    /// the subcommand to handle, so a subcommand can reuse the same flag.
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn before_subcommand(self) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().leading()),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Only accept the option when the subcommand `name` is chosen (ex: `--target`
+    /// is only valid with `build`).
+    ///
+    /// Call multiple times to accept the option with multiple subcommands.
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn only_with<T: AsRef<str>>(self, name: T) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().only_with(name)),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Marks the option as experimental, such that it is only accepted when the
+    /// environment variable `var` is set or unstable arguments are enabled on the
+    /// command-line, see [check_unstable][crate::Cli::check_unstable].
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn unstable<T: AsRef<str>>(self, var: T) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().unstable(var)),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
+
+    /// Specify the `value` that is used when the option is supplied without one
+    /// (ex: `--color` is the same as `--color=auto`).
+    ///
+    /// This makes the option's value optional, so the option has three states:
+    /// - absent: no value exists, so `None` (or the default of [get_or][crate::Cli::get_or]) is returned
+    /// - present without a value: the missing `value` is used
+    /// - present with a value: the supplied value is used
+    ///
+    /// Unless the option is [attached_only][Arg::attached_only], a following
+    /// argument that does not look like a flag is still taken as its value.
+    ///
+    /// This function only modifies arguments that were created as options, and
+    /// silently leaves any other arguments unmodified.
+    pub fn default_missing<T: AsRef<str>>(self, value: T) -> Self {
+        Self {
+            data: match self.data.is_option() {
+                true => ArgType::Optional(self.data.into_option().unwrap().missing(value)),
+                false => self.data,
+            },
+            _marker: self._marker,
+        }
+    }
 }
 
 impl Arg<Callable> {
@@ -106,13 +641,79 @@ impl Arg<Callable> {
     }
 }
 
+impl Arg<Dynamic> {
+    /// Create a new argument that stores `<key>=<value>` pairs behind the prefix `name`.
+    ///
+    /// A prefix of a single character is a switch that may have the key attached
+    /// directly to it (ex: `-Dkey=value` or `-D key=value`). Any other prefix is
+    /// a flag (ex: `--set key=value` or `--set=key=value`).
+    pub fn prefix<T: AsRef<str>>(name: T) -> Arg<Dynamic> {
+        let mut chars = name.as_ref().chars();
+        let option = match (chars.next(), chars.next()) {
+            (Some(c), None) => Flag::switch_only(c),
+            _ => Flag::new(name.as_ref()),
+        };
+        Self {
+            data: ArgType::Optional(Optional {
+                option: option,
+                value: Positional::new("key=value"),
+                attached_only: false,
+                equals: false,
+                hyphen_values: false,
+                leading: false,
+                missing: None,
+            }),
+            _marker: PhantomData::<Dynamic>,
+        }
+    }
+}
+
+/// The behavior to perform when an argument is processed.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Action {
+    /// Raise a flag at most once, see [check][crate::Cli::check].
+    SetTrue,
+    /// Count the number of times a flag is raised, see [check_all][crate::Cli::check_all].
+    Count,
+    /// Take at most one value, see [get][crate::Cli::get].
+    Set,
+    /// Take every value, see [get_all][crate::Cli::get_all].
+    Append,
+}
+
+/// The kind of value that is expected for an argument.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ValueHint {
+    /// No particular kind of value is expected.
+    #[default]
+    Unknown,
+    /// Any path on the file system.
+    AnyPath,
+    /// A path to a file.
+    FilePath,
+    /// A path to a directory.
+    DirPath,
+    /// A path to an executable file.
+    ExecutablePath,
+    /// The name of a command found on the system.
+    CommandName,
+    /// The name of a network host.
+    Hostname,
+    /// The name of a user on the system.
+    Username,
+    /// A uniform resource locator.
+    Url,
+    /// An email address.
+    EmailAddress,
+}
+
 mod symbol {
-    pub const FLAG: &str = "--";
+    pub const SWITCH: char = '-';
     pub const POS_BRACKET_L: &str = "<";
     pub const POS_BRACKER_R: &str = ">";
 }
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone)]
 pub enum ArgType {
     Flag(Flag),
     Positional(Positional),
@@ -128,6 +729,15 @@ impl ArgType {
         }
     }
 
+    /// References the part of the argument that holds a value, if one exists.
+    pub fn as_value(&self) -> Option<&Positional> {
+        match self {
+            ArgType::Flag(_) => None,
+            ArgType::Optional(o) => Some(o.get_positional()),
+            ArgType::Positional(p) => Some(p),
+        }
+    }
+
     pub fn as_option(&self) -> Option<&Optional> {
         match self {
             ArgType::Flag(_) => None,
@@ -136,6 +746,27 @@ impl ArgType {
         }
     }
 
+    /// Sets the `symbol` the argument's flag is displayed with, if it has one.
+    pub(crate) fn set_symbol(&mut self, symbol: char) -> () {
+        match self {
+            Self::Flag(f) => f.set_symbol(symbol),
+            Self::Optional(o) => o.option.set_symbol(symbol),
+            Self::Positional(_) => (),
+        }
+    }
+
+    /// Checks if `other` is the same kind of argument with the same name.
+    pub(crate) fn is_same(&self, other: &ArgType) -> bool {
+        match (self, other) {
+            (ArgType::Flag(a), ArgType::Flag(b)) => a.get_name() == b.get_name(),
+            (ArgType::Positional(a), ArgType::Positional(b)) => a.get_name() == b.get_name(),
+            (ArgType::Optional(a), ArgType::Optional(b)) => {
+                a.get_flag().get_name() == b.get_flag().get_name()
+            }
+            _ => false,
+        }
+    }
+
     fn is_option(&self) -> bool {
         match self {
             Self::Optional(_) => true,
@@ -184,17 +815,241 @@ impl Debug for ArgType {
     }
 }
 
+/// A regular expression that a raw value must match, compiled once when the
+/// argument is built.
+#[derive(Debug, Clone)]
+pub struct Pattern {
+    text: String,
+    #[cfg(feature = "regex")]
+    re: regex::Regex,
+}
+
+impl Pattern {
+    /// Compiles the regular expression written as `text`.
+    ///
+    /// An invalid `text` is considered a programmer's error and will result in
+    /// a panic!.
+    pub fn new<T: AsRef<str>>(text: T) -> Self {
+        Self {
+            text: text.as_ref().to_string(),
+            #[cfg(feature = "regex")]
+            re: match regex::Regex::new(text.as_ref()) {
+                Ok(re) => re,
+                Err(e) => panic!("invalid regular expression \"{}\": {}", text.as_ref(), e),
+            },
+        }
+    }
+
+    /// Checks if the `value` matches the regular expression.
+    #[cfg(feature = "regex")]
+    pub fn is_match(&self, value: &str) -> bool {
+        self.re.is_match(value)
+    }
+
+    pub fn as_str(&self) -> &str {
+        self.text.as_ref()
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
 #[derive(Debug, PartialEq, Clone)]
 pub struct Positional {
     name: String,
+    hint: ValueHint,
+    pattern: Option<Pattern>,
+    schemes: Option<Vec<String>>,
+    example: Option<String>,
+    range: Option<RangeCheck>,
+    action: Action,
+    completer: Option<CompleterRef>,
+    type_name: Option<&'static str>,
+    file_ref: bool,
+    base: Option<PathBuf>,
+    resolution: PathResolution,
+    default: Option<String>,
+    defaults_if: Vec<(Flag, String)>,
 }
 
 impl Positional {
     pub fn new<T: AsRef<str>>(s: T) -> Self {
         Self {
             name: s.as_ref().to_string(),
+            hint: ValueHint::default(),
+            pattern: None,
+            schemes: None,
+            example: None,
+            range: None,
+            action: Action::Set,
+            completer: None,
+            type_name: None,
+            file_ref: false,
+            base: None,
+            resolution: PathResolution::Join,
+            default: None,
+            defaults_if: Vec::new(),
+        }
+    }
+
+    pub fn hint(mut self, hint: ValueHint) -> Self {
+        self.hint = hint;
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        self.name.as_ref()
+    }
+
+    pub fn get_hint(&self) -> ValueHint {
+        self.hint
+    }
+
+    pub fn pattern<T: AsRef<str>>(mut self, pattern: T) -> Self {
+        self.pattern = Some(Pattern::new(pattern));
+        self
+    }
+
+    pub fn get_pattern(&self) -> Option<&Pattern> {
+        self.pattern.as_ref()
+    }
+
+    pub fn schemes<T: AsRef<str>>(mut self, schemes: &[T]) -> Self {
+        self.schemes = Some(schemes.iter().map(|s| s.as_ref().to_string()).collect());
+        self.hint = ValueHint::Url;
+        self
+    }
+
+    pub fn get_schemes(&self) -> Option<&Vec<String>> {
+        self.schemes.as_ref()
+    }
+
+    pub fn example<T: AsRef<str>>(mut self, example: T) -> Self {
+        self.example = Some(example.as_ref().to_string());
+        self
+    }
+
+    pub fn get_example(&self) -> Option<&str> {
+        self.example.as_deref()
+    }
+
+    pub fn file_ref(mut self) -> Self {
+        self.file_ref = true;
+        self
+    }
+
+    pub fn allows_file_ref(&self) -> bool {
+        self.file_ref
+    }
+
+    pub fn base<P: AsRef<Path>>(mut self, base: P) -> Self {
+        self.base = Some(base.as_ref().to_path_buf());
+        self.hint = match self.hint {
+            ValueHint::Unknown => ValueHint::AnyPath,
+            h => h,
+        };
+        self
+    }
+
+    pub fn get_base(&self) -> Option<&Path> {
+        self.base.as_deref()
+    }
+
+    pub fn resolution(mut self, resolution: PathResolution) -> Self {
+        self.resolution = resolution;
+        self
+    }
+
+    pub fn get_resolution(&self) -> PathResolution {
+        self.resolution
+    }
+
+    pub fn default<T: AsRef<str>>(mut self, value: T) -> Self {
+        self.default = Some(value.as_ref().to_string());
+        self
+    }
+
+    pub fn get_default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn default_if<T: AsRef<str>>(mut self, flag: Flag, value: T) -> Self {
+        self.defaults_if.push((flag, value.as_ref().to_string()));
+        self
+    }
+
+    /// Returns the defaults that apply when their flag is raised, in the order
+    /// they were declared.
+    pub fn get_defaults_if(&self) -> &Vec<(Flag, String)> {
+        &self.defaults_if
+    }
+
+    pub fn action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    pub fn get_action(&self) -> Action {
+        self.action
+    }
+
+    pub fn range<V, R>(mut self, range: R) -> Self
+    where
+        V: PartialOrd + FromStr + Display + Clone + Send + Sync + 'static,
+        <V as FromStr>::Err: std::error::Error,
+        R: RangeBounds<V>,
+    {
+        self.range = Some(RangeCheck::new(range));
+        self
+    }
+
+    pub fn get_range(&self) -> Option<&ValueRange> {
+        self.range.as_ref().map(|r| &r.bounds)
+    }
+
+    pub(crate) fn completer(mut self, completer: CompleterRef) -> Self {
+        self.completer = Some(completer);
+        self
+    }
+
+    pub(crate) fn type_name(mut self, name: &'static str) -> Self {
+        self.type_name = Some(name);
+        self
+    }
+
+    /// References the name of the value's type, as reported by [std::any::type_name],
+    /// if the argument was created with its type (ex: `u16`).
+    pub fn get_type_name(&self) -> Option<&str> {
+        self.type_name
+    }
+
+    /// Lists the candidates for the value that so far is written as `current`.
+    ///
+    /// Without a completer, the candidates are derived from the value's hint.
+    pub(crate) fn complete(&self, current: &str) -> Vec<String> {
+        let base = self.base.as_deref();
+        match (&self.completer, self.hint) {
+            (Some(c), _) => c.complete(current),
+            (None, ValueHint::DirPath) => complete::paths(current, true, base),
+            (None, ValueHint::AnyPath | ValueHint::FilePath | ValueHint::ExecutablePath) => {
+                complete::paths(current, false, base)
+            }
+            (None, ValueHint::Hostname) => complete::hosts(),
+            (None, _) => Vec::new(),
         }
     }
+
+    /// Checks if the `word` falls within the value's range once it is parsed as
+    /// the type of the bounds.
+    ///
+    /// Returns `None` if there is no range, or the error message if `word` cannot
+    /// be parsed as the type of the bounds.
+    pub fn in_range(&self, word: &str) -> Option<Result<bool, String>> {
+        self.range.as_ref().map(|r| (r.contains)(word))
+    }
 }
 
 impl Display for Positional {
@@ -213,6 +1068,13 @@ impl Display for Positional {
 pub struct Flag {
     name: String,
     switch: Option<char>,
+    aliases: Vec<String>,
+    long: bool,
+    eager: Option<String>,
+    action: Action,
+    subcommands: Vec<String>,
+    unstable: Option<String>,
+    symbol: char,
 }
 
 impl Flag {
@@ -220,6 +1082,28 @@ impl Flag {
         Self {
             name: s.as_ref().to_string(),
             switch: None,
+            aliases: Vec::new(),
+            long: true,
+            eager: None,
+            action: Action::SetTrue,
+            subcommands: Vec::new(),
+            unstable: None,
+            symbol: symbol::SWITCH,
+        }
+    }
+
+    /// Creates a flag that can only be referenced by its switch `c`.
+    pub fn switch_only(c: char) -> Self {
+        Self {
+            name: c.to_string(),
+            switch: Some(c),
+            aliases: Vec::new(),
+            long: false,
+            eager: None,
+            action: Action::SetTrue,
+            subcommands: Vec::new(),
+            unstable: None,
+            symbol: symbol::SWITCH,
         }
     }
 
@@ -228,6 +1112,65 @@ impl Flag {
         self
     }
 
+    /// Adds an alternate long name for the flag.
+    pub fn alias<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.aliases.push(name.as_ref().to_string());
+        self
+    }
+
+    pub fn get_aliases(&self) -> &Vec<String> {
+        &self.aliases
+    }
+
+    /// Sets the text to display when the flag is raised.
+    pub fn eager<T: AsRef<str>>(mut self, text: T) -> Self {
+        self.eager = Some(text.as_ref().to_string());
+        self
+    }
+
+    /// References the text to display when the flag is raised, if it is eager.
+    pub fn get_eager(&self) -> Option<&str> {
+        self.eager.as_deref()
+    }
+
+    pub fn action(mut self, action: Action) -> Self {
+        self.action = action;
+        self
+    }
+
+    pub fn get_action(&self) -> Action {
+        self.action
+    }
+
+    /// Restricts the flag to only be accepted with the subcommand `name`.
+    pub fn only_with<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.subcommands.push(name.as_ref().to_string());
+        self
+    }
+
+    /// References the subcommands the flag is restricted to, which is empty if
+    /// the flag is accepted with any subcommand.
+    pub fn get_subcommands(&self) -> &Vec<String> {
+        &self.subcommands
+    }
+
+    /// Marks the flag as experimental until the environment variable `var` is set.
+    pub fn unstable<T: AsRef<str>>(mut self, var: T) -> Self {
+        self.unstable = Some(var.as_ref().to_string());
+        self
+    }
+
+    /// References the environment variable that enables the flag, if it is
+    /// experimental.
+    pub fn get_unstable(&self) -> Option<&str> {
+        self.unstable.as_deref()
+    }
+
+    /// Checks if the flag has no long form and is only referenced by its switch.
+    pub fn is_switch_only(&self) -> bool {
+        self.long == false
+    }
+
     pub fn get_name(&self) -> &str {
         self.name.as_ref()
     }
@@ -235,18 +1178,32 @@ impl Flag {
     pub fn get_switch(&self) -> Option<&char> {
         self.switch.as_ref()
     }
+
+    /// Sets the `symbol` the flag is displayed with, see
+    /// [switch_symbol][crate::Cli::switch_symbol].
+    pub(crate) fn set_symbol(&mut self, symbol: char) -> () {
+        self.symbol = symbol;
+    }
 }
 
 impl Display for Flag {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "{}{}", symbol::FLAG, self.get_name())
+        match self.long {
+            true => write!(f, "{0}{0}{1}", self.symbol, self.get_name()),
+            false => write!(f, "{}{}", self.symbol, self.get_name()),
+        }
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct Optional {
     option: Flag,
     value: Positional,
+    attached_only: bool,
+    equals: bool,
+    hyphen_values: bool,
+    leading: bool,
+    missing: Option<String>,
 }
 
 impl Optional {
@@ -254,6 +1211,11 @@ impl Optional {
         Self {
             option: Flag::new(s.as_ref()),
             value: Positional::new(s),
+            attached_only: false,
+            equals: false,
+            hyphen_values: false,
+            leading: false,
+            missing: None,
         }
     }
 
@@ -267,6 +1229,139 @@ impl Optional {
         self
     }
 
+    pub fn alias<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.option = self.option.alias(name);
+        self
+    }
+
+    pub fn hint(mut self, hint: ValueHint) -> Self {
+        self.value = self.value.hint(hint);
+        self
+    }
+
+    pub fn pattern<T: AsRef<str>>(mut self, pattern: T) -> Self {
+        self.value = self.value.pattern(pattern);
+        self
+    }
+
+    pub fn file_ref(mut self) -> Self {
+        self.value = self.value.file_ref();
+        self
+    }
+
+    pub fn base<P: AsRef<Path>>(mut self, base: P) -> Self {
+        self.value = self.value.base(base);
+        self
+    }
+
+    pub fn resolution(mut self, resolution: PathResolution) -> Self {
+        self.value = self.value.resolution(resolution);
+        self
+    }
+
+    pub fn schemes<T: AsRef<str>>(mut self, schemes: &[T]) -> Self {
+        self.value = self.value.schemes(schemes);
+        self
+    }
+
+    pub fn example<T: AsRef<str>>(mut self, example: T) -> Self {
+        self.value = self.value.example(example);
+        self
+    }
+
+    pub fn action(mut self, action: Action) -> Self {
+        self.value = self.value.action(action);
+        self
+    }
+
+    pub fn default<T: AsRef<str>>(mut self, value: T) -> Self {
+        self.value = self.value.default(value);
+        self
+    }
+
+    pub fn default_if<T: AsRef<str>>(mut self, flag: Flag, value: T) -> Self {
+        self.value = self.value.default_if(flag, value);
+        self
+    }
+
+    pub fn range<V, R>(mut self, range: R) -> Self
+    where
+        V: PartialOrd + FromStr + Display + Clone + Send + Sync + 'static,
+        <V as FromStr>::Err: std::error::Error,
+        R: RangeBounds<V>,
+    {
+        self.value = self.value.range(range);
+        self
+    }
+
+    pub(crate) fn completer(mut self, completer: CompleterRef) -> Self {
+        self.value = self.value.completer(completer);
+        self
+    }
+
+    pub(crate) fn type_name(mut self, name: &'static str) -> Self {
+        self.value = self.value.type_name(name);
+        self
+    }
+
+    pub fn attached_only(mut self) -> Self {
+        self.attached_only = true;
+        self
+    }
+
+    pub fn is_attached_only(&self) -> bool {
+        self.attached_only
+    }
+
+    /// Requires the value to be attached with `=`, which implies the option is
+    /// [attached only][Optional::attached_only].
+    pub fn equals(mut self) -> Self {
+        self.attached_only = true;
+        self.equals = true;
+        self
+    }
+
+    pub fn requires_equals(&self) -> bool {
+        self.equals
+    }
+
+    pub fn hyphen_values(mut self) -> Self {
+        self.hyphen_values = true;
+        self
+    }
+
+    pub fn allows_hyphen_values(&self) -> bool {
+        self.hyphen_values
+    }
+
+    pub fn leading(mut self) -> Self {
+        self.leading = true;
+        self
+    }
+
+    pub fn is_leading(&self) -> bool {
+        self.leading
+    }
+
+    pub fn only_with<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.option = self.option.only_with(name);
+        self
+    }
+
+    pub fn unstable<T: AsRef<str>>(mut self, var: T) -> Self {
+        self.option = self.option.unstable(var);
+        self
+    }
+
+    pub fn missing<T: AsRef<str>>(mut self, value: T) -> Self {
+        self.missing = Some(value.as_ref().to_string());
+        self
+    }
+
+    pub fn get_missing(&self) -> Option<&str> {
+        self.missing.as_deref()
+    }
+
     pub fn get_flag(&self) -> &Flag {
         &self.option
     }
@@ -278,7 +1373,12 @@ impl Optional {
 
 impl Display for Optional {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
-        write!(f, "{} {}", self.option, self.value)
+        match (self.attached_only, self.missing.is_some()) {
+            (true, true) => write!(f, "{}[={}]", self.option, self.value),
+            (true, false) => write!(f, "{}={}", self.option, self.value),
+            (false, true) => write!(f, "{} [{}]", self.option, self.value),
+            (false, false) => write!(f, "{} {}", self.option, self.value),
+        }
     }
 }
 
@@ -292,7 +1392,20 @@ mod test {
         assert_eq!(
             ip,
             Positional {
-                name: String::from("ip")
+                name: String::from("ip"),
+                hint: ValueHint::Unknown,
+                pattern: None,
+                schemes: None,
+                example: None,
+                range: None,
+                action: Action::Set,
+                completer: None,
+                type_name: None,
+                file_ref: false,
+                base: None,
+                resolution: PathResolution::Join,
+                default: None,
+                defaults_if: Vec::new(),
             }
         );
 
@@ -300,7 +1413,20 @@ mod test {
         assert_eq!(
             version,
             Positional {
-                name: String::from("version")
+                name: String::from("version"),
+                hint: ValueHint::Unknown,
+                pattern: None,
+                schemes: None,
+                example: None,
+                range: None,
+                action: Action::Set,
+                completer: None,
+                type_name: None,
+                file_ref: false,
+                base: None,
+                resolution: PathResolution::Join,
+                default: None,
+                defaults_if: Vec::new(),
             }
         );
     }
@@ -322,6 +1448,13 @@ mod test {
             Flag {
                 name: String::from("help"),
                 switch: Some('h'),
+                aliases: Vec::new(),
+                long: true,
+                eager: None,
+                action: Action::SetTrue,
+                subcommands: Vec::new(),
+                unstable: None,
+                symbol: '-',
             }
         );
         assert_eq!(help.get_switch(), Some(&'h'));
@@ -333,6 +1466,13 @@ mod test {
             Flag {
                 name: String::from("version"),
                 switch: None,
+                aliases: Vec::new(),
+                long: true,
+                eager: None,
+                action: Action::SetTrue,
+                subcommands: Vec::new(),
+                unstable: None,
+                symbol: '-',
             }
         );
         assert_eq!(version.get_switch(), None);
@@ -346,6 +1486,18 @@ mod test {
 
         let version = Flag::new("version");
         assert_eq!(version.to_string(), "--version");
+
+        let preserve = Flag::switch_only('p');
+        assert_eq!(preserve.to_string(), "-p");
+        assert_eq!(preserve.get_switch(), Some(&'p'));
+        assert_eq!(preserve.is_switch_only(), true);
+
+        let mut help = Flag::new("help");
+        help.set_symbol('/');
+        assert_eq!(help.to_string(), "//help");
+        let mut preserve = Flag::switch_only('p');
+        preserve.set_symbol('/');
+        assert_eq!(preserve.to_string(), "/p");
     }
 
     #[test]
@@ -356,6 +1508,11 @@ mod test {
             Optional {
                 option: Flag::new("code"),
                 value: Positional::new("code"),
+                attached_only: false,
+                equals: false,
+                hyphen_values: false,
+                leading: false,
+                missing: None,
             }
         );
         assert_eq!(code.get_flag().get_switch(), None);
@@ -366,6 +1523,11 @@ mod test {
             Optional {
                 option: Flag::new("color"),
                 value: Positional::new("rgb"),
+                attached_only: false,
+                equals: false,
+                hyphen_values: false,
+                leading: false,
+                missing: None,
             }
         );
         assert_eq!(version.get_flag().get_switch(), None);
@@ -376,6 +1538,11 @@ mod test {
             Optional {
                 option: Flag::new("color").switch('c'),
                 value: Positional::new("rgb"),
+                attached_only: false,
+                equals: false,
+                hyphen_values: false,
+                leading: false,
+                missing: None,
             }
         );
         assert_eq!(version.get_flag().get_switch(), Some(&'c'));
@@ -383,6 +1550,50 @@ mod test {
         assert_eq!(version.get_positional(), &Positional::new("rgb"));
     }
 
+    #[test]
+    fn optional_attached_only() {
+        let color = Optional::new("color").value("when").attached_only();
+        assert_eq!(color.is_attached_only(), true);
+        assert_eq!(color.to_string(), "--color=<when>");
+        let color = color.missing("auto");
+        assert_eq!(color.get_missing(), Some("auto"));
+        assert_eq!(color.to_string(), "--color[=<when>]");
+        let jobs = Optional::new("jobs").value("n").missing("4");
+        assert_eq!(jobs.to_string(), "--jobs [<n>]");
+    }
+
+    #[test]
+    fn value_hint() {
+        let config = ArgType::from(Arg::option("config").hint(ValueHint::FilePath));
+        assert_eq!(
+            config.as_option().unwrap().get_positional().get_hint(),
+            ValueHint::FilePath
+        );
+
+        let dir = Arg::positional("dir").hint(ValueHint::DirPath);
+        let dir = ArgType::from(dir).into_positional().unwrap();
+        assert_eq!(dir.get_hint(), ValueHint::DirPath);
+        assert_eq!(dir.get_name(), "dir");
+
+        assert_eq!(Positional::new("path").get_hint(), ValueHint::Unknown);
+    }
+
+    #[test]
+    fn typed_arg() {
+        let port = Arg::option_of::<u16>("port").switch('p').value("num");
+        let port = ArgType::from(port);
+        let port = port.as_option().unwrap();
+        assert_eq!(port.get_positional().get_type_name(), Some("u16"));
+        assert_eq!(port.get_positional().get_name(), "num");
+
+        let dir = ArgType::from(Arg::positional_of::<String>("dir"));
+        assert_eq!(
+            dir.into_positional().unwrap().get_type_name(),
+            Some("alloc::string::String")
+        );
+        assert_eq!(Positional::new("path").get_type_name(), None);
+    }
+
     #[test]
     fn optional_disp() {
         let code = Optional::new("code");
@@ -1,29 +1,52 @@
-use crate::error::{utils, CapMode, ColorMode};
-use crate::help::Help;
+use crate::complete::Completion;
+use crate::error::{utils, CapMode, ColorMode, Paint};
+use crate::expr::{Expr, Operand};
+use crate::getopt::{Getopt, GetoptError, Matches};
+use crate::help::{Help, HelpFormat};
+use crate::lex;
+#[cfg(feature = "runner")]
+use crate::proc::prompt::Confirm;
+use crate::proc::stash::Stash;
+#[cfg(feature = "runner")]
+use crate::proc::status::Status;
+#[cfg(feature = "runner")]
+use crate::proc::{self, Halt};
+use crate::proc::{Nested, Node};
 use crate::seqalin;
 use crate::seqalin::Cost;
-use crate::{arg::*, Command, Subcommand};
-use colored::Colorize;
+use crate::value::{OutputFormat, Render};
+#[cfg(feature = "runner")]
+use crate::Exit;
+use crate::{arg::*, Command, CommandFrom, Subcommand};
 use stage::*;
+use std::borrow::Borrow;
 use std::collections::HashMap;
-use std::collections::HashSet;
+use std::fmt::Display;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
 use std::ops::RangeBounds;
+#[cfg(feature = "runner")]
 use std::process::ExitCode;
 use std::str::FromStr;
+use std::sync::Arc;
 
-pub use crate::error::{Error, ErrorContext, ErrorKind};
+pub use crate::error::{Error, ErrorContext, ErrorKind, ErrorStyle, Span};
 
 /// The return type for a [Command]'s interpretation process.
 pub type Result<T> = std::result::Result<T, Error>;
 
 mod symbol {
-    // series of characters to denote flags and switches
-    pub const SWITCH: &str = "-";
-    // @note: tokenizing depends on flag having the first character be the switch character
-    pub const FLAG: &str = "--";
+    // character to denote switches, which is written twice to denote flags
+    pub const SWITCH: char = '-';
+    // hidden entry point for dynamic shell completions
+    pub const COMPLETE: &str = "__complete";
+    // prefix of a value that references a file to read the value from
+    pub const FILE_REF: char = '@';
 }
 
+/// The maximum number of spelling suggestions to include in an error.
+const MAX_SUGGESTIONS: usize = 3;
+
 #[derive(Debug, Eq, Hash, PartialEq)]
 enum Tag<T: AsRef<str>> {
     Switch(T),
@@ -39,6 +62,43 @@ impl<T: AsRef<str>> Tag<T> {
     }
 }
 
+/// A [Tag] that can be looked up in the [Store] without allocating its name.
+///
+/// The lookup is consistent with the owned keys of the table since a `Tag<&str>`
+/// hashes and compares the same as a `Tag<String>`.
+trait TagKey {
+    fn key(&self) -> Tag<&str>;
+}
+
+impl<T: AsRef<str>> TagKey for Tag<T> {
+    fn key(&self) -> Tag<&str> {
+        match self {
+            Self::Flag(s) => Tag::Flag(s.as_ref()),
+            Self::Switch(s) => Tag::Switch(s.as_ref()),
+        }
+    }
+}
+
+impl<'a> Borrow<dyn TagKey + 'a> for Tag<String> {
+    fn borrow(&self) -> &(dyn TagKey + 'a) {
+        self
+    }
+}
+
+impl Hash for dyn TagKey + '_ {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state)
+    }
+}
+
+impl PartialEq for dyn TagKey + '_ {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for dyn TagKey + '_ {}
+
 #[derive(Debug, PartialEq)]
 enum Token {
     UnattachedArgument(usize, String),
@@ -48,6 +108,8 @@ enum Token {
     EmptySwitch(usize),
     Ignore(usize, String),
     Terminator(usize),
+    /// A word placed in the token stream that was never written on the command-line.
+    Inserted(String),
 }
 
 impl Token {
@@ -56,33 +118,174 @@ impl Token {
             Self::UnattachedArgument(_, s) => s,
             Self::AttachedArgument(_, s) => s,
             Self::Ignore(_, s) => s,
+            Self::Inserted(s) => s,
             _ => panic!("cannot call take_str on token without string"),
         }
     }
 
-    fn _get_index_ref(&self) -> &usize {
+    /// Returns the index of the command-line argument the token was written in,
+    /// which does not exist for inserted tokens.
+    fn get_index(&self) -> Option<usize> {
+        match self {
+            Self::UnattachedArgument(i, _) => Some(*i),
+            Self::AttachedArgument(i, _) => Some(*i),
+            Self::Flag(i) => Some(*i),
+            Self::EmptySwitch(i) => Some(*i),
+            Self::Switch(i, _) => Some(*i),
+            Self::Terminator(i) => Some(*i),
+            Self::Ignore(i, _) => Some(*i),
+            Self::Inserted(_) => None,
+        }
+    }
+}
+
+/// A read-only view of an argument remaining in the token stream, see
+/// [tokens][Cli::tokens].
+///
+/// Each variant holds the index of the argument on the command-line, where the
+/// program is at index 0.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TokenView<'a> {
+    /// A long flag by its name without the leading `--` (ex: `--verbose`).
+    Flag(usize, &'a str),
+    /// A single switch character; combined switches (ex: `-abc`) are viewed one
+    /// character at a time.
+    Switch(usize, char),
+    /// A switch symbol without a character (`-`).
+    EmptySwitch(usize),
+    /// A value directly attached to a flag or switch with `=` (ex: `--jobs=4`).
+    Value(usize, &'a str),
+    /// An argument not attached to a flag or switch.
+    Positional(usize, &'a str),
+    /// The terminator flag (`--`).
+    Terminator(usize),
+    /// An argument placed after the terminator flag.
+    Ignored(usize, &'a str),
+}
+
+/// A lookup table for the positions of flags and switches in the token stream.
+///
+/// The table iterates in an unspecified order that changes between runs, so
+/// any search across its entries that reports a diagnostic orders the entries
+/// by their first token index, see [verify_determinism][Cli::verify_determinism].
+type Store = HashMap<Tag<String>, Slot>;
+
+/// Records the token position `j` in the lookup table for the flag or switch `tag`.
+///
+/// The table reserves its `capacity` on the first record, so a command-line of
+/// only unattached arguments never allocates it. The name of the tag is only
+/// allocated the first time it is recorded.
+fn record(store: &mut Store, tag: Tag<&str>, j: usize, capacity: usize) -> () {
+    if store.capacity() == 0 {
+        store.reserve(capacity);
+    }
+    match store.get_mut(&tag as &dyn TagKey) {
+        Some(slot) => slot.push(j),
+        None => {
+            let mut slot = Slot::new();
+            slot.push(j);
+            let tag = match tag {
+                Tag::Flag(s) => Tag::Flag(s.to_string()),
+                Tag::Switch(s) => Tag::Switch(s.to_string()),
+            };
+            store.insert(tag, slot);
+        }
+    }
+}
+
+/// The number of token positions a [Slot] holds before it allocates, as most
+/// flags appear only once or twice on the command-line.
+const INLINE_POINTERS: usize = 2;
+
+/// The token positions recorded for a flag or switch, which are held inline
+/// until there are more than [INLINE_POINTERS] of them.
+#[derive(Debug, Clone)]
+enum Pointers {
+    Inline([usize; INLINE_POINTERS], usize),
+    Heap(Vec<usize>),
+}
+
+impl Pointers {
+    fn new() -> Self {
+        Self::Inline([0; INLINE_POINTERS], 0)
+    }
+
+    fn push(&mut self, j: usize) -> () {
+        match self {
+            Self::Inline(buf, len) if *len < INLINE_POINTERS => {
+                buf[*len] = j;
+                *len += 1;
+            }
+            Self::Inline(buf, len) => {
+                let mut heap = Vec::with_capacity(INLINE_POINTERS * 2);
+                heap.extend_from_slice(&buf[..*len]);
+                heap.push(j);
+                *self = Self::Heap(heap);
+            }
+            Self::Heap(heap) => heap.push(j),
+        }
+    }
+
+    fn retain<F: FnMut(&usize) -> bool>(&mut self, mut f: F) -> () {
+        match self {
+            Self::Inline(buf, len) => {
+                let mut kept = 0;
+                for k in 0..*len {
+                    if f(&buf[k]) == true {
+                        buf[kept] = buf[k];
+                        kept += 1;
+                    }
+                }
+                *len = kept;
+            }
+            Self::Heap(heap) => heap.retain(f),
+        }
+    }
+
+    fn as_slice(&self) -> &[usize] {
+        match self {
+            Self::Inline(buf, len) => &buf[..*len],
+            Self::Heap(heap) => heap,
+        }
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [usize] {
         match self {
-            Self::UnattachedArgument(i, _) => i,
-            Self::AttachedArgument(i, _) => i,
-            Self::Flag(i) => i,
-            Self::EmptySwitch(i) => i,
-            Self::Switch(i, _) => i,
-            Self::Terminator(i) => i,
-            Self::Ignore(i, _) => i,
+            Self::Inline(buf, len) => &mut buf[..*len],
+            Self::Heap(heap) => heap,
         }
     }
 }
 
+impl PartialEq for Pointers {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+/// Returns the name of the flag written as the raw command-line argument `arg`,
+/// without its `switch` symbols or an attached value.
+fn flag_name(arg: &str, switch: char) -> &str {
+    let name = arg.split('=').next().unwrap();
+    match name
+        .strip_prefix(switch)
+        .and_then(|n| n.strip_prefix(switch))
+    {
+        Some(n) => n,
+        None => &name[name.chars().next().map_or(0, |c| c.len_utf8())..],
+    }
+}
+
 #[derive(Debug, PartialEq)]
 struct Slot {
-    pointers: Vec<usize>,
+    pointers: Pointers,
     visited: bool,
 }
 
 impl Slot {
     fn new() -> Self {
         Self {
-            pointers: Vec::new(),
+            pointers: Pointers::new(),
             visited: false,
         }
     }
@@ -99,12 +302,12 @@ impl Slot {
         self.visited = true;
     }
 
-    fn get_indices(&self) -> &Vec<usize> {
-        &self.pointers
+    fn get_indices(&self) -> &[usize] {
+        self.pointers.as_slice()
     }
 
     fn first(&self) -> Option<&usize> {
-        self.pointers.first()
+        self.pointers.as_slice().first()
     }
 }
 
@@ -123,7 +326,7 @@ impl MemoryState {
     pub fn proceed(&mut self, mut next: MemoryState) {
         // panic if we are already advanced past the next state
         if self > &mut next {
-            panic!("{}: argument discovery is in an invalid order: invalid state transition from {:?} to {:?}", "structural hazard".red().bold().underline(), self, next)
+            panic!("{}: argument discovery is in an invalid order: invalid state transition from {:?} to {:?}", Paint::new(ColorMode::Normal.is_enabled()).bold_red("structural hazard"), self, next)
         }
         // println!("{:?} -> {:?}", self, next);
         *self = next;
@@ -134,6 +337,19 @@ impl MemoryState {
     }
 }
 
+impl Display for MemoryState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Start => write!(f, "start"),
+            Self::ProcessingFlags => write!(f, "flags"),
+            Self::ProcessingOptionals => write!(f, "options"),
+            Self::ProcessingPositionals => write!(f, "positionals"),
+            Self::ProcessingSubcommands => write!(f, "subcommands"),
+            Self::End => write!(f, "the emptiness check"),
+        }
+    }
+}
+
 pub mod stage {
     /// The typestate pattern for the different stages in processing data from
     /// the command-line.
@@ -163,53 +379,605 @@ impl<S: ProcessorState> Cli<S> {
     /// Perform a state transition for the command-line processor.
     fn transition<T: ProcessorState>(self) -> Cli<T> {
         Cli::<T> {
+            args: self.args,
+            raw_args: self.raw_args,
+            program: self.program,
+            path: self.path,
             tokens: self.tokens,
             store: self.store,
             known_args: self.known_args,
+            consumed: self.consumed,
+            scope: self.scope,
+            taken: self.taken,
+            declared: self.declared,
+            restricted: self.restricted,
+            unstable: self.unstable,
+            stash: self.stash,
+            completion: self.completion,
+            exceeded: self.exceeded,
+            verbosity: self.verbosity,
             asking_for_help: self.asking_for_help,
+            eager: self.eager,
             help: self.help,
+            outer_helps: self.outer_helps,
             state: self.state,
             options: self.options,
             _marker: PhantomData::<T>,
         }
     }
+
+    /// Verifies the token stream and the lookup table of flag and switch positions
+    /// are consistent with each other and with the raw command-line arguments.
+    ///
+    /// This function is intended for testing the command-line processor against
+    /// arbitrary inputs, such as with fuzzing or property-based testing. It errors
+    /// with a description of the first violated invariant.
+    pub fn check_invariants(&self) -> std::result::Result<(), String> {
+        // the first terminator ends the processing of all arguments after it
+        let end = self
+            .args
+            .iter()
+            .position(|a| a.split('=').next().unwrap() == self.flag_prefix());
+        let mut prev: Option<usize> = None;
+        for (j, t) in self.tokens.iter().enumerate() {
+            let t = match t {
+                Some(t) => t,
+                None => continue,
+            };
+            // inserted subcommands do not originate from the command-line
+            if let Token::UnattachedArgument(_, _) | Token::Inserted(_) = t {
+                if self.consumed.contains(&j) == true {
+                    return Err(format!(
+                        "token {} was consumed but remains in the stream",
+                        j
+                    ));
+                }
+                continue;
+            }
+            let i = t.get_index().unwrap();
+            let arg = match self.args.get(i) {
+                Some(a) => a,
+                None => return Err(format!("token {} refers to missing argument {}", j, i)),
+            };
+            if prev.is_some_and(|p| p > i) == true {
+                return Err(format!("token {} is out of order for argument {}", j, i));
+            }
+            prev = Some(i);
+            // identify the lookup table entry that should refer to this token
+            let tag = match t {
+                Token::Flag(_) => Some(Tag::Flag(flag_name(arg, self.options.switch).to_string())),
+                Token::Switch(_, c) => Some(Tag::Switch(c.to_string())),
+                Token::EmptySwitch(_) => Some(Tag::Switch(String::new())),
+                _ => None,
+            };
+            let terminated = end.is_some_and(|e| i > e);
+            match (t, terminated) {
+                (Token::Ignore(_, s), true) if s == arg => (),
+                (Token::Ignore(_, _), _) => {
+                    return Err(format!("token {} is an ignored argument out of place", j))
+                }
+                (Token::AttachedArgument(_, s), _) => {
+                    if arg.ends_with(&format!("={}", s)) == false {
+                        return Err(format!("token {} is not attached to argument {}", j, i));
+                    }
+                }
+                (_, true) => return Err(format!("token {} is not ignored after a terminator", j)),
+                (Token::Terminator(_), false) if Some(i) != end => {
+                    return Err(format!("token {} is not the first terminator", j))
+                }
+                (_, false) if Some(i) == end && matches!(t, Token::Terminator(_)) == false => {
+                    return Err(format!("token {} is not a terminator", j))
+                }
+                _ => (),
+            }
+            if let Some(tag) = tag {
+                match self.store.get(&tag) {
+                    Some(slot) if slot.get_indices().contains(&j) == true => (),
+                    _ => return Err(format!("token {} is missing from the lookup table", j)),
+                }
+            }
+        }
+        // every lookup table entry refers to a matching flag or switch
+        for (tag, slot) in self.store.iter() {
+            for j in slot.get_indices() {
+                match (tag, self.tokens.get(*j)) {
+                    (_, None) => return Err(format!("lookup table refers to missing token {}", j)),
+                    (_, Some(None)) => (),
+                    (Tag::Flag(_), Some(Some(Token::Flag(_)))) => (),
+                    (Tag::Switch(c), Some(Some(Token::Switch(_, d)))) if c == &d.to_string() => (),
+                    (Tag::Switch(c), Some(Some(Token::EmptySwitch(_)))) if c.is_empty() == true => {
+                    }
+                    _ => return Err(format!("lookup table entry does not match token {}", j)),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Tokenizes the raw command-line arguments into the token stream and the
+    /// lookup table of flag and switch positions.
+    fn tokenize(&mut self) -> () {
+        let mut tokens = Vec::<Option<Token>>::with_capacity(self.options.capacity);
+        // the lookup table is allocated once the first flag or switch is seen
+        let mut store = Store::default();
+        let capacity = self.options.capacity;
+        if self.exceeded.is_some() == true {
+            self.tokens = tokens;
+            self.store = store;
+            return;
+        }
+        let mut terminated = false;
+        // in posix-strict mode, the first unattached argument ends flag processing
+        let mut operands = false;
+        // the next unattached argument is the value of a declared option
+        let mut pending = false;
+        let mut args = self.args.iter().cloned().enumerate();
+        while let Some((i, mut arg)) = args.next() {
+            // ignore all input after detecting the terminator
+            if terminated == true {
+                tokens.push(Some(Token::Ignore(i, arg)));
+            // treat all input as arguments after the first operand
+            } else if operands == true {
+                tokens.push(Some(Token::UnattachedArgument(i, arg)));
+            // handle an option
+            } else if arg.starts_with(self.options.switch) == true || self.is_positive(&arg) == true
+            {
+                // try to separate from '=' sign
+                let mut value: Option<String> = None;
+                let mut option: Option<String> = None;
+                {
+                    if let Some((opt, val)) = arg.split_once('=') {
+                        option = Some(opt.to_string());
+                        value = Some(val.to_string());
+                    }
+                }
+                // update arg to be the value split by '='
+                if let Some(opt) = option {
+                    arg = opt;
+                }
+                // handle long flag signal
+                if arg.starts_with(&self.flag_prefix()) == true {
+                    arg.replace_range(0..self.flag_prefix().len(), "");
+                    // caught the terminator (purely "--")
+                    if arg.is_empty() == true {
+                        tokens.push(Some(Token::Terminator(i)));
+                        terminated = true;
+                    // caught a 'long option' flag
+                    } else {
+                        pending = value.is_none() && self.is_posix_option(Some(&arg), None);
+                        record(&mut store, Tag::Flag(&arg), tokens.len(), capacity);
+                        tokens.push(Some(Token::Flag(i)));
+                    }
+                // handle a whole flag name behind a single symbol
+                } else if self.options.paired.is_some() == true && arg.chars().count() > 2 {
+                    let name = flag_name(&arg, self.options.switch);
+                    pending = value.is_none() && self.is_posix_option(Some(name), None);
+                    record(&mut store, Tag::Flag(name), tokens.len(), capacity);
+                    tokens.push(Some(Token::Flag(i)));
+                // handle short flag signal
+                } else {
+                    // encode each switch on the stack to look it up in the table
+                    let mut buf = [0; 4];
+                    // skip the initial switch character/symbol (1 char)
+                    let mut arg = arg.chars().skip(1);
+                    // check if the switch is empty by evaulating the first possible switch position
+                    if let Some(c) = arg.next() {
+                        record(
+                            &mut store,
+                            Tag::Switch(c.encode_utf8(&mut buf)),
+                            tokens.len(),
+                            capacity,
+                        );
+                        tokens.push(Some(Token::Switch(i, c)));
+                    } else {
+                        record(&mut store, Tag::Switch(""), tokens.len(), capacity);
+                        tokens.push(Some(Token::EmptySwitch(i)));
+                    }
+                    // continuously split switches into individual components
+                    while let Some(c) = arg.next() {
+                        record(
+                            &mut store,
+                            Tag::Switch(c.encode_utf8(&mut buf)),
+                            tokens.len(),
+                            capacity,
+                        );
+                        tokens.push(Some(Token::Switch(i, c)));
+                    }
+                    // only the last switch in a group can take the next argument
+                    pending = value.is_none()
+                        && match tokens.last() {
+                            Some(Some(Token::Switch(_, c))) => self.is_posix_option(None, Some(*c)),
+                            _ => false,
+                        };
+                }
+                // caught an argument directly attached to an option
+                if let Some(val) = value {
+                    tokens.push(Some(Token::AttachedArgument(i, val)));
+                }
+            // caught an argument
+            } else {
+                // an option's value and a subcommand's name do not end flag processing
+                if pending == false
+                    && self.options.posix_subcommands.iter().any(|s| s == &arg) == false
+                {
+                    operands = self.options.posix_strict;
+                }
+                pending = false;
+                tokens.push(Some(Token::UnattachedArgument(i, arg)));
+            }
+            // the remaining arguments are not split once the limit is crossed
+            if self
+                .options
+                .max_tokens
+                .is_some_and(|max| tokens.len() > max)
+                == true
+            {
+                break;
+            }
+        }
+        // a command-line with too many tokens is not processed
+        if let Some(max) = self.options.max_tokens {
+            if tokens.len() > max {
+                self.exceeded = Some(Exceeded {
+                    subject: String::from("the command-line"),
+                    unit: "tokens",
+                    cur: tokens.len(),
+                    max: max,
+                });
+                tokens.clear();
+                store.clear();
+            }
+        }
+        self.tokens = tokens;
+        self.store = store;
+        self.eager = self.find_eager();
+    }
+
+    /// Finds the text of the declared eager flag that is raised first on the
+    /// command-line, see [eager][Cli::eager].
+    fn find_eager(&self) -> Option<String> {
+        self.options
+            .eager_flags
+            .iter()
+            .filter_map(|f| {
+                let mut tags: Vec<Tag<String>> = Vec::new();
+                if f.is_switch_only() == false {
+                    tags.push(Tag::Flag(f.get_name().to_string()));
+                }
+                f.get_aliases()
+                    .iter()
+                    .for_each(|a| tags.push(Tag::Flag(a.to_string())));
+                if let Some(c) = f.get_switch() {
+                    tags.push(Tag::Switch(c.to_string()));
+                }
+                tags.iter()
+                    .filter_map(|t| self.store.get(t).and_then(|slot| slot.first()))
+                    .min()
+                    .map(|j| (*j, f.get_eager().unwrap()))
+            })
+            .min_by_key(|(j, _)| *j)
+            .map(|(_, text)| text.to_string())
+    }
+
+    /// Checks if the flag written with the `name` or `switch` belongs to an option
+    /// declared with [posix_option][Cli::posix_option].
+    fn is_posix_option(&self, name: Option<&str>, switch: Option<char>) -> bool {
+        self.options.posix_options.iter().any(|f| {
+            name.is_some_and(|n| f.get_name() == n || f.get_aliases().iter().any(|a| a == n))
+                || switch.is_some_and(|c| f.get_switch() == Some(&c))
+        })
+    }
+
+    /// Returns the symbols that begin a long flag, which are also the terminator
+    /// when written alone, see [switch_symbol][Cli::switch_symbol].
+    fn flag_prefix(&self) -> String {
+        format!("{0}{0}", self.options.switch)
+    }
+
+    /// Checks if the raw command-line argument `arg` is a flag or switch written
+    /// with the positive symbol, see [paired_symbol][Cli::paired_symbol].
+    fn is_positive(&self, arg: &str) -> bool {
+        self.options
+            .paired
+            .is_some_and(|p| arg.starts_with(p) == true && arg.len() > p.len_utf8())
+    }
+
+    /// Finds the first raw command-line argument that exceeds its length limit,
+    /// see [max_arg_len][Cli::max_arg_len] and [max_value_len][Cli::max_value_len].
+    fn measure_args(&self) -> Option<Exceeded> {
+        // the program's name is not counted as an argument
+        self.raw_args
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find_map(|(i, arg)| {
+                if let Some(max) = self.options.max_arg_len {
+                    if arg.len() > max {
+                        return Some(Exceeded {
+                            subject: format!("argument {}", i),
+                            unit: "bytes",
+                            cur: arg.len(),
+                            max: max,
+                        });
+                    }
+                }
+                let flag =
+                    arg.starts_with(self.options.switch) == true || self.is_positive(arg) == true;
+                match (self.options.max_value_len, flag) {
+                    (Some(max), true) => match arg.split_once('=') {
+                        Some((_, value)) if value.len() > max => Some(Exceeded {
+                            subject: format!("the value attached to argument {}", i),
+                            unit: "bytes",
+                            cur: value.len(),
+                            max: max,
+                        }),
+                        _ => None,
+                    },
+                    _ => None,
+                }
+            })
+    }
+}
+
+/// When to color the output, as requested on the command-line through the common
+/// `--color <when>` option.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum ColorChoice {
+    /// The output is always colored.
+    Always,
+    /// The output is never colored.
+    Never,
+    /// The output is colored based on the environment, such as the `NO_COLOR`
+    /// variable and whether `stderr` is a terminal.
+    #[default]
+    Auto,
+}
+
+impl ColorChoice {
+    /// The values accepted on the command-line.
+    const VALUES: [&'static str; 3] = ["always", "never", "auto"];
+
+    /// Determines if output should be colored under this choice.
+    ///
+    /// This agrees with whether the [Cli] colors its own errors.
+    pub fn is_enabled(&self) -> bool {
+        ColorMode::from(*self).is_enabled()
+    }
+}
+
+impl From<ColorChoice> for ColorMode {
+    fn from(value: ColorChoice) -> Self {
+        match value {
+            ColorChoice::Always => ColorMode::On,
+            ColorChoice::Never => ColorMode::Off,
+            ColorChoice::Auto => ColorMode::Normal,
+        }
+    }
+}
+
+impl From<&ColorMode> for ColorChoice {
+    fn from(value: &ColorMode) -> Self {
+        match value {
+            ColorMode::On => ColorChoice::Always,
+            ColorMode::Off => ColorChoice::Never,
+            ColorMode::Normal => ColorChoice::Auto,
+        }
+    }
+}
+
+impl FromStr for ColorChoice {
+    type Err = ColorChoiceError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            "auto" => Ok(Self::Auto),
+            _ => Err(ColorChoiceError),
+        }
+    }
+}
+
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Always => write!(f, "always"),
+            Self::Never => write!(f, "never"),
+            Self::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// The error for a [ColorChoice] that is not one of the accepted values.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ColorChoiceError;
+
+impl Display for ColorChoiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected one of \"always\", \"never\", or \"auto\"")
+    }
+}
+
+impl std::error::Error for ColorChoiceError {}
+
+/// The amount of output the command-line processor writes while running a
+/// command in [go][Cli::go].
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Default)]
+pub enum Verbosity {
+    /// Only errors are written, while status output is suppressed.
+    Quiet,
+    /// Errors are written as a single message.
+    #[default]
+    Normal,
+    /// Additional output is requested, but errors are still written as a single message.
+    Verbose,
+    /// Errors returned from executing the command are written along with the
+    /// chain of errors that caused them.
+    Debug,
+}
+
+/// When help detected on the command-line is displayed compared to the other
+/// errors found during interpretation.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HelpPolicy {
+    /// Help is displayed as soon as it is detected, taking priority over any
+    /// other errors (ex: a missing required argument).
+    #[default]
+    Always,
+    /// Help is displayed only once the rest of the command-line is interpreted
+    /// without errors, when it is checked to be [empty][Cli::empty].
+    OnlyIfValid,
+    /// Help is never displayed by the command-line processor, leaving it to the
+    /// caller to act on the result of [help][Cli::help].
+    Never,
+}
+
+/// A resource limit on the command-line that was exceeded during parsing, see
+/// [max_tokens][Cli::max_tokens].
+#[derive(Debug, PartialEq, Clone)]
+struct Exceeded {
+    subject: String,
+    unit: &'static str,
+    cur: usize,
+    max: usize,
+}
+
+/// A function that rewrites a single argument into any number of arguments
+/// before the command-line is tokenized.
+#[derive(Clone)]
+struct Preprocessor(Arc<dyn Fn(String) -> Vec<String> + Send + Sync>);
+
+impl PartialEq for Preprocessor {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for Preprocessor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Preprocessor")
+    }
 }
 
 #[derive(Debug, PartialEq, Clone)]
 struct CliOptions {
-    pub prioritize_help: bool,
+    pub help_policy: HelpPolicy,
     pub cap_mode: CapMode,
     pub threshold: Cost,
+    pub threshold_auto: bool,
     pub capacity: usize,
     pub color_mode: ColorMode,
-    pub err_prefix: String,
+    pub catch_hazards: bool,
+    pub strict: bool,
+    pub unordered: bool,
+    pub intermixed: bool,
+    pub posix_strict: bool,
+    pub posix_options: Vec<Flag>,
+    pub posix_subcommands: Vec<String>,
+    pub eager_flags: Vec<Flag>,
+    pub bin_name: Option<String>,
+    pub err_chain: bool,
+    pub err_pointer: bool,
+    pub err_prefix: Option<String>,
     pub err_suffix: String,
+    pub preprocessors: Vec<Preprocessor>,
+    pub bind_grouped: bool,
+    pub max_tokens: Option<usize>,
+    pub max_arg_len: Option<usize>,
+    pub max_value_len: Option<usize>,
+    pub paired: Option<char>,
+    pub deterministic: bool,
+    pub switch: char,
 }
 
 impl CliOptions {
     pub fn new() -> Self {
         Self {
-            prioritize_help: true,
+            help_policy: HelpPolicy::Always,
             cap_mode: CapMode::new(),
             threshold: 0,
+            threshold_auto: false,
             capacity: 0,
             color_mode: ColorMode::new(),
-            err_prefix: String::new(),
+            catch_hazards: false,
+            strict: false,
+            unordered: false,
+            intermixed: false,
+            posix_strict: false,
+            posix_options: Vec::new(),
+            posix_subcommands: Vec::new(),
+            eager_flags: Vec::new(),
+            bin_name: None,
+            err_chain: false,
+            err_pointer: false,
+            err_prefix: Some(String::new()),
             err_suffix: String::new(),
+            preprocessors: Vec::new(),
+            bind_grouped: false,
+            max_tokens: None,
+            max_arg_len: None,
+            max_value_len: None,
+            paired: None,
+            deterministic: false,
+            switch: symbol::SWITCH,
         }
     }
 }
 
+impl CliOptions {
+    /// Formats the error message `msg` to be reported with the configured
+    /// prefix, suffix, and capitalization, placing the `chain` of underlying
+    /// errors after the message.
+    #[cfg(feature = "runner")]
+    fn format_report(&self, msg: String, chain: String) -> String {
+        let prefix = match &self.err_prefix {
+            Some(p) => p.clone(),
+            None => format!(
+                "{}: ",
+                Paint::new(self.color_mode.is_enabled()).bold_red("error")
+            ),
+        };
+        format!(
+            "{}{}{}{}",
+            prefix,
+            utils::format_err_msg(msg, self.cap_mode),
+            chain,
+            self.err_suffix
+        )
+    }
+}
+
 impl Default for CliOptions {
     fn default() -> Self {
         Self {
-            prioritize_help: true,
+            help_policy: HelpPolicy::Always,
             cap_mode: CapMode::default(),
             threshold: 2,
+            threshold_auto: false,
             capacity: 0,
             color_mode: ColorMode::default(),
-            err_prefix: String::from(format!("{}: ", "error".red().bold())),
+            catch_hazards: false,
+            strict: false,
+            unordered: false,
+            intermixed: false,
+            posix_strict: false,
+            posix_options: Vec::new(),
+            posix_subcommands: Vec::new(),
+            eager_flags: Vec::new(),
+            bin_name: None,
+            err_chain: false,
+            err_pointer: false,
+            // the default prefix is styled when the error is reported
+            err_prefix: None,
             err_suffix: String::new(),
+            preprocessors: Vec::new(),
+            bind_grouped: false,
+            max_tokens: None,
+            max_arg_len: None,
+            max_value_len: None,
+            paired: None,
+            deterministic: false,
+            switch: symbol::SWITCH,
         }
     }
 }
@@ -217,14 +985,46 @@ impl Default for CliOptions {
 /// The command-line processor.
 #[derive(Debug, PartialEq)]
 pub struct Cli<S: ProcessorState> {
+    /// The raw command-line arguments (excluding the program name)
+    args: Vec<String>,
+    /// The command-line arguments exactly as they were supplied, see [raw_args][Cli::raw_args]
+    raw_args: Vec<String>,
+    /// The file name of the program as it was invoked
+    program: Option<String>,
+    /// The program name followed by the subcommands entered so far
+    path: Vec<String>,
     /// The order-preserved list of tokens
     tokens: Vec<Option<Token>>,
     /// A lookup table for identifying which positions in the token stream a given option is present
-    store: HashMap<Tag<String>, Slot>,
+    store: Store,
     /// The list of arguments has they are processed by the Cli processor
     known_args: Vec<ArgType>,
+    /// The token positions of unattached arguments that were taken as positionals
+    consumed: Vec<usize>,
+    /// The token position where the current (sub)command's positionals begin
+    scope: usize,
+    /// The locations of the values taken from the token stream in the order they were taken
+    taken: Vec<Span>,
+    /// The arguments declared to be part of the interface, see [declare][Cli::declare]
+    declared: Vec<ArgType>,
+    /// The arguments found that are only valid with certain subcommands, see [only_with][Arg::only_with]
+    restricted: Vec<(String, Vec<String>)>,
+    /// Whether experimental arguments were enabled on the command-line, once checked, see [check_unstable][Cli::check_unstable]
+    unstable: Option<bool>,
+    /// The values passed from interpretation to execution, see [stash][Cli::stash]
+    stash: Stash,
+    /// The candidates for the last word when completing the command-line, see [complete][crate::complete]
+    completion: Option<Completion>,
+    /// The resource limit exceeded by the command-line, which is reported on the first request
+    exceeded: Option<Exceeded>,
+    /// The amount of output to write while running the command
+    verbosity: Verbosity,
     asking_for_help: bool,
+    /// The text of the first eager flag raised on the command-line, see [eager][Cli::eager]
+    eager: Option<String>,
     help: Option<Help>,
+    /// The help of the commands enclosing the current command, see [push_help][Cli::push_help]
+    outer_helps: Vec<Option<Help>>,
     state: MemoryState,
     options: CliOptions,
     _marker: PhantomData<S>,
@@ -233,11 +1033,27 @@ pub struct Cli<S: ProcessorState> {
 impl Default for Cli<Build> {
     fn default() -> Self {
         Self {
+            args: Vec::default(),
+            raw_args: Vec::default(),
+            program: None,
+            path: Vec::default(),
             tokens: Vec::default(),
-            store: HashMap::default(),
+            store: Store::default(),
             known_args: Vec::default(),
+            consumed: Vec::default(),
+            scope: 0,
+            taken: Vec::default(),
+            declared: Vec::default(),
+            restricted: Vec::default(),
+            unstable: None,
+            stash: Stash::default(),
+            completion: None,
+            exceeded: None,
+            verbosity: Verbosity::Normal,
             help: None,
+            outer_helps: Vec::new(),
             asking_for_help: false,
+            eager: None,
             state: MemoryState::Start,
             options: CliOptions::default(),
             _marker: PhantomData,
@@ -250,11 +1066,27 @@ impl Cli<Build> {
     /// minimal options enabled.
     pub fn new() -> Self {
         Self {
+            args: Vec::new(),
+            raw_args: Vec::new(),
+            program: None,
+            path: Vec::new(),
             tokens: Vec::new(),
-            store: HashMap::new(),
+            store: Store::default(),
             known_args: Vec::new(),
+            consumed: Vec::new(),
+            scope: 0,
+            taken: Vec::new(),
+            declared: Vec::new(),
+            restricted: Vec::new(),
+            unstable: None,
+            stash: Stash::new(),
+            completion: None,
+            exceeded: None,
+            verbosity: Verbosity::Normal,
             help: None,
+            outer_helps: Vec::new(),
             asking_for_help: false,
+            eager: None,
             state: MemoryState::Start,
             options: CliOptions::new(),
             _marker: PhantomData,
@@ -263,6 +1095,10 @@ impl Cli<Build> {
 
     /// Sets the initial capacity for the data structures that are used to hold
     /// the processed command-line data.
+    ///
+    /// The capacity is the expected number of tokens and distinct flags. The
+    /// positions of each flag are held without allocating until the flag appears
+    /// more than twice.
     pub fn with_capacity(mut self, cap: usize) -> Self {
         self.options.capacity = cap;
         self
@@ -271,6 +1107,17 @@ impl Cli<Build> {
     /// Sets the maximum threshold value when comparing strings for character similiarity.
     pub fn threshold(mut self, cost: Cost) -> Self {
         self.options.threshold = cost;
+        self.options.threshold_auto = false;
+        self
+    }
+
+    /// Scales the threshold value when comparing strings for character similiarity
+    /// by the length of each candidate word, allowing one edit for every three
+    /// characters.
+    ///
+    /// This setting is overridden by a later call to [threshold][Cli::threshold].
+    pub fn threshold_auto(mut self) -> Self {
+        self.options.threshold_auto = true;
         self
     }
 
@@ -311,119 +1158,330 @@ impl Cli<Build> {
         self
     }
 
-    /// Downplays the [Help] flag to not become a priority error over other errors
+    /// Sets when the [Help] flag is displayed compared to the other errors found
     /// during interpretation.
     ///
-    /// Help is prioritized by default.
-    pub fn deprioritize_help(mut self) -> Self {
-        self.options.prioritize_help = false;
+    /// The policy is [Always][HelpPolicy::Always] by default.
+    pub fn help_policy(mut self, policy: HelpPolicy) -> Self {
+        self.options.help_policy = policy;
         self
     }
 
-    /// Prioritizes the [Help] flag over other errors during interpretation.
+    /// Declares the eager flag `arg` (ex: `--version`), such that raising it takes
+    /// priority over the errors of any other arguments, regardless of when the
+    /// flag is checked, see [eager][Arg::eager].
     ///
-    /// This is enabled by default.
-    pub fn prioritize_help(mut self) -> Self {
-        self.options.prioritize_help = true;
+    /// Only [help][Cli::help] takes priority over a declared eager flag. An eager
+    /// flag that is not declared is only reported once it is checked.
+    ///
+    /// Panics if `arg` is not an eager flag.
+    pub fn eager(mut self, arg: Arg<Raisable>) -> Self {
+        let flag = ArgType::from(arg).into_flag().unwrap();
+        if flag.get_eager().is_none() == true {
+            panic!("flag \"{}\" must be eager to be declared", flag.get_name());
+        }
+        self.options.eager_flags.push(flag);
         self
     }
 
-    /// Sets the text to come before an error message if one is reported during
-    /// processing.
-    pub fn error_prefix<T: AsRef<str>>(mut self, prefix: T) -> Self {
-        self.options.err_prefix = String::from(prefix.as_ref());
-        self
+    /// Downplays the [Help] flag to not become a priority error over other errors
+    /// during interpretation.
+    ///
+    /// This is the same as the [Never][HelpPolicy::Never] help policy.
+    pub fn deprioritize_help(self) -> Self {
+        self.help_policy(HelpPolicy::Never)
     }
 
-    /// Sets the text to come after an error message if one is reported during
-    /// processing.
-    pub fn error_suffix<T: AsRef<str>>(mut self, suffix: T) -> Self {
-        self.options.err_suffix = String::from(suffix.as_ref());
+    /// Prioritizes the [Help] flag over other errors during interpretation.
+    ///
+    /// This is the same as the [Always][HelpPolicy::Always] help policy, which is
+    /// enabled by default.
+    pub fn prioritize_help(self) -> Self {
+        self.help_policy(HelpPolicy::Always)
+    }
+
+    /// Reports a violation of the argument discovery order (a structural hazard)
+    /// as an error instead of panicking.
+    ///
+    /// A structural hazard is a programmer's error, so the error message is meant
+    /// for the developer rather than the end user.
+    pub fn catch_structural_hazards(mut self) -> Self {
+        self.options.catch_hazards = true;
         self
     }
 
-    /// Builds the [Cli] struct by tokenizing the [String] iterator into a
-    /// representable form for further processing.
+    /// Reports an argument that was [declared][Cli::declare] but never requested
+    /// during interpretation as an error when checking for an [empty][Cli::empty]
+    /// command-line.
+    ///
+    /// This catches drift between the documented interface and its implementation.
+    /// Like a structural hazard, an unused argument is a programmer's error, so it is
+    /// only checked in debug builds.
+    pub fn strict(mut self) -> Self {
+        self.options.strict = true;
+        self
+    }
+
+    /// Disables enforcing the argument discovery order, allowing arguments to be
+    /// requested in any order.
+    ///
+    /// Conflicts are resolved by token position: an unattached argument that
+    /// directly follows a flag that has not been requested yet is reserved as that
+    /// flag's value and is skipped when requesting positionals. Therefore, flags
+    /// that do not accept values should still be requested before positionals, and
+    /// options may be requested after positionals.
+    pub fn unordered_discovery(mut self) -> Self {
+        self.options.unordered = true;
+        self
+    }
+
+    /// Allows flags, options, and positionals to be requested in any order within
+    /// a command, while only subcommands must still be requested last.
+    ///
+    /// This relaxes the argument discovery order rather than what can be written
+    /// on the command-line (ex: a command can request its positional `target`
+    /// before its flag `--release`). Unlike
+    /// [unordered_discovery][Cli::unordered_discovery], requesting an argument
+    /// after a subcommand or after checking for an [empty][Cli::empty]
+    /// command-line is still a structural hazard.
+    ///
+    /// Conflicts are resolved by token position the same as in unordered discovery.
+    /// Flags that do not accept values and are requested after positionals should
+    /// be [declared][Cli::declare], so the unattached argument that follows them
+    /// is not reserved as their value.
+    pub fn intermixed(mut self) -> Self {
+        self.options.intermixed = true;
+        self
+    }
+
+    /// Ends the processing of flags at the first unattached argument, the same as
+    /// POSIX `getopt`. Every argument after it is an unattached argument, even when
+    /// it begins with `-` (ex: `-v file -x` has the flag `-v` and the positionals
+    /// `file` and `-x`).
+    ///
+    /// The command-line is split before any arguments are requested, so the
+    /// options that take a separate value and the subcommands must be declared
+    /// beforehand with [posix_option][Cli::posix_option] and
+    /// [posix_subcommand][Cli::posix_subcommand]. A terminator (`--`) after the
+    /// first unattached argument is an unattached argument itself.
+    pub fn posix_strict(mut self) -> Self {
+        self.options.posix_strict = true;
+        self
+    }
+
+    /// Declares the option `arg` to take the argument that follows it as its
+    /// value when none is attached, see [posix_strict][Cli::posix_strict].
+    ///
+    /// The separate value (ex: `out.tar` in `-f out.tar -v`) does not end the
+    /// processing of flags.
+    pub fn posix_option<S: ValueState>(mut self, arg: Arg<S>) -> Self {
+        if let Some(o) = ArgType::from(arg).as_option() {
+            self.options.posix_options.push(o.get_flag().clone());
+        }
+        self
+    }
+
+    /// Declares the subcommand `arg` for [posix_strict][Cli::posix_strict] mode.
+    ///
+    /// The subcommand's name does not end the processing of flags. Instead, its
+    /// own flags are processed until the first unattached argument that follows
+    /// it (ex: `-v build --release target` has the flags `-v` and `--release`).
+    pub fn posix_subcommand(mut self, arg: Arg<Callable>) -> Self {
+        if let Some(p) = ArgType::from(arg).as_value() {
+            self.options
+                .posix_subcommands
+                .push(p.get_name().to_string());
+        }
+        self
+    }
+
+    /// Binds a value attached to a group of switches (ex: `-vl=direct`) to the
+    /// last switch in the group.
+    ///
+    /// By default, such a value is rejected with an error once it is requested by
+    /// an option, as it is unclear which switch was meant to take it.
+    pub fn bind_grouped_values(mut self) -> Self {
+        self.options.bind_grouped = true;
+        self
+    }
+
+    /// Writes switches with the `symbol` in place of `-` (ex: `/v`), where a long
+    /// flag begins with the symbol written twice (ex: `//verbose`) and the symbol
+    /// written twice on its own is the terminator.
+    ///
+    /// Flags are displayed with the symbol in errors, in the help flag's tip, and
+    /// in completions. The arguments interpreted by [getopt][Cli::getopt] and
+    /// [operands][Cli::operands] keep their own syntax.
+    pub fn switch_symbol(mut self, symbol: char) -> Self {
+        self.options.switch = symbol;
+        self
+    }
+
+    /// Accepts flags written with the `positive` symbol in place of the switch
+    /// symbol, so a flag can be turned on and off by the symbol it is written
+    /// with (ex: `+sb` and `-sb`), see [toggle][Cli::toggle].
+    ///
+    /// In this mode, a single symbol begins a whole flag name rather than a group
+    /// of switches (ex: `-sb` is the flag `sb`), while a single character after
+    /// the symbol is a switch (ex: `+x`). Flags beginning with the switch symbol
+    /// written twice (ex: `--`) are unchanged, see [switch_symbol][Cli::switch_symbol].
+    pub fn paired_symbol(mut self, positive: char) -> Self {
+        self.options.paired = Some(positive);
+        self
+    }
+
+    /// Limits the number of tokens the command-line can be split into, where each
+    /// flag, switch, and value is one token.
+    ///
+    /// A command-line that exceeds any resource limit is not processed, and the
+    /// limit is reported as an error once the first argument is requested. The
+    /// arguments after the one that crosses the token limit are not split, so the
+    /// reported count only includes the tokens up to that argument. Limits
+    /// guard programs that are run on behalf of others (such as setuid programs or
+    /// commands invoked by a server) against pathological input.
+    pub fn max_tokens(mut self, max: usize) -> Self {
+        self.options.max_tokens = Some(max);
+        self
+    }
+
+    /// Limits the length of any single argument to `max` bytes, see
+    /// [max_tokens][Cli::max_tokens].
+    pub fn max_arg_len(mut self, max: usize) -> Self {
+        self.options.max_arg_len = Some(max);
+        self
+    }
+
+    /// Limits the length of a value attached to a flag (ex: `--name=value`) to `max`
+    /// bytes, see [max_tokens][Cli::max_tokens].
+    pub fn max_value_len(mut self, max: usize) -> Self {
+        self.options.max_value_len = Some(max);
+        self
+    }
+
+    /// Verifies each diagnostic chosen by searching the flags and switches left
+    /// on the command-line is the same when they are searched in reverse order.
+    ///
+    /// This mode is intended for tests (such as snapshot tests of the reported
+    /// errors), as every search is repeated. A diagnostic that depends on the
+    /// order the flags and switches are searched in is considered a bug in the
+    /// command-line processor and will result in a panic!.
+    pub fn verify_determinism(mut self) -> Self {
+        self.options.deterministic = true;
+        self
+    }
+
+    /// Rewrites each argument supplied after the program's name with `f` before
+    /// the command-line is tokenized.
+    ///
+    /// An argument can be replaced by any number of arguments, including none,
+    /// which allows for app-specific rewrites such as expanding `+toolchain` into
+    /// `--toolchain toolchain` or translating legacy flags. When called multiple
+    /// times, the rewrites are applied in the order they were added.
+    pub fn preprocess<F: Fn(String) -> Vec<String> + Send + Sync + 'static>(
+        mut self,
+        f: F,
+    ) -> Self {
+        self.options.preprocessors.push(Preprocessor(Arc::new(f)));
+        self
+    }
+
+    /// Sets the name of the program to use in place of the name it was invoked
+    /// with (the first argument).
+    pub fn bin_name<T: AsRef<str>>(mut self, name: T) -> Self {
+        self.options.bin_name = Some(String::from(name.as_ref()));
+        self
+    }
+
+    /// Writes the chain of errors that caused an error (its sources) when an error
+    /// is reported during processing.
+    ///
+    /// The chain is otherwise only written when the [Verbosity] is [Debug][Verbosity::Debug].
+    pub fn error_chain(mut self) -> Self {
+        self.options.err_chain = true;
+        self
+    }
+
+    /// Echoes the command-line with the offending argument underlined when an
+    /// error is reported during processing, see [ErrorStyle::point_to].
+    pub fn point_errors(mut self) -> Self {
+        self.options.err_pointer = true;
+        self
+    }
+
+    /// Sets the text to come before an error message if one is reported during
+    /// processing.
+    pub fn error_prefix<T: AsRef<str>>(mut self, prefix: T) -> Self {
+        self.options.err_prefix = Some(String::from(prefix.as_ref()));
+        self
+    }
+
+    /// Sets the text to come after an error message if one is reported during
+    /// processing.
+    pub fn error_suffix<T: AsRef<str>>(mut self, suffix: T) -> Self {
+        self.options.err_suffix = String::from(suffix.as_ref());
+        self
+    }
+
+    /// Builds the [Cli] struct by splitting the `line` into arguments like a
+    /// shell and then tokenizing them, see [lex::split][crate::lex::split].
+    ///
+    /// The first argument is the program's name, the same as in [parse][Cli::parse].
+    /// This is convenient for tests and examples.
+    ///
+    /// ```
+    /// use cliproc::{Arg, Cli};
+    ///
+    /// let mut cli = Cli::new()
+    ///     .parse_str("myapp add 1 '2'")
+    ///     .unwrap()
+    ///     .save();
+    /// assert_eq!(cli.require::<String>(Arg::positional("op")).unwrap(), "add");
+    /// assert_eq!(cli.require::<u8>(Arg::positional("lhs")).unwrap(), 1);
+    /// assert_eq!(cli.require::<u8>(Arg::positional("rhs")).unwrap(), 2);
+    /// ```
+    ///
+    /// This function errors if the `line` has an unclosed quote or ends with an
+    /// unescaped backslash.
+    pub fn parse_str<T: AsRef<str>>(
+        self,
+        line: T,
+    ) -> std::result::Result<Cli<Ready>, lex::SplitError> {
+        Ok(self.parse(lex::split(line)?.into_iter()))
+    }
+
+    /// Builds the [Cli] struct by tokenizing the [String] iterator into a
+    /// representable form for further processing.
     ///
     /// This function transitions the [Cli] state to the [Ready] state.
     pub fn parse<T: Iterator<Item = String>>(mut self, args: T) -> Cli<Ready> {
-        self.options.color_mode.sync();
-        let mut tokens = Vec::<Option<Token>>::with_capacity(self.options.capacity);
-        let mut store = HashMap::with_capacity(self.options.capacity);
-        let mut terminated = false;
-        let mut args = args.skip(1).enumerate();
-        while let Some((i, mut arg)) = args.next() {
-            // ignore all input after detecting the terminator
-            if terminated == true {
-                tokens.push(Some(Token::Ignore(i, arg)));
-            // handle an option
-            } else if arg.starts_with(symbol::SWITCH) == true {
-                // try to separate from '=' sign
-                let mut value: Option<String> = None;
-                let mut option: Option<String> = None;
-                {
-                    if let Some((opt, val)) = arg.split_once('=') {
-                        option = Some(opt.to_string());
-                        value = Some(val.to_string());
-                    }
-                }
-                // update arg to be the value split by '='
-                if let Some(opt) = option {
-                    arg = opt;
-                }
-                // handle long flag signal
-                if arg.starts_with(symbol::FLAG) == true {
-                    arg.replace_range(0..=1, "");
-                    // caught the terminator (purely "--")
-                    if arg.is_empty() == true {
-                        tokens.push(Some(Token::Terminator(i)));
-                        terminated = true;
-                    // caught a 'long option' flag
-                    } else {
-                        store
-                            .entry(Tag::Flag(arg))
-                            .or_insert(Slot::new())
-                            .push(tokens.len());
-                        tokens.push(Some(Token::Flag(i)));
-                    }
-                // handle short flag signal
-                } else {
-                    // skip the initial switch character/symbol (1 char)
-                    let mut arg = arg.chars().skip(1);
-                    // check if the switch is empty by evaulating the first possible switch position
-                    if let Some(c) = arg.next() {
-                        store
-                            .entry(Tag::Switch(c.to_string()))
-                            .or_insert(Slot::new())
-                            .push(tokens.len());
-                        tokens.push(Some(Token::Switch(i, c)));
-                    } else {
-                        store
-                            .entry(Tag::Switch(String::new()))
-                            .or_insert(Slot::new())
-                            .push(tokens.len());
-                        tokens.push(Some(Token::EmptySwitch(i)));
-                    }
-                    // continuously split switches into individual components
-                    while let Some(c) = arg.next() {
-                        store
-                            .entry(Tag::Switch(c.to_string()))
-                            .or_insert(Slot::new())
-                            .push(tokens.len());
-                        tokens.push(Some(Token::Switch(i, c)));
-                    }
-                }
-                // caught an argument directly attached to an option
-                if let Some(val) = value {
-                    tokens.push(Some(Token::AttachedArgument(i, val)));
-                }
-            // caught an argument
-            } else {
-                tokens.push(Some(Token::UnattachedArgument(i, arg)));
+        self.raw_args = args.collect();
+        // oversized arguments are not processed (or copied) any further
+        self.exceeded = self.measure_args();
+        let mut args = self.raw_args.iter().cloned();
+        // the program is identified by its file name rather than the path used to invoke it
+        let program = args
+            .next()
+            .map(|p| match std::path::Path::new(&p).file_name() {
+                Some(name) => name.to_string_lossy().into_owned(),
+                None => p,
+            });
+        if let Some(name) = self.options.bin_name.clone().or(program.clone()) {
+            self.path.push(name);
+        }
+        self.program = program;
+        if self.exceeded.is_none() == true {
+            self.args = args.collect();
+            // the last word is left out of processing when it is being completed
+            if self.args.first().is_some_and(|a| a == symbol::COMPLETE) == true {
+                self.args.remove(0);
+                let current = self.args.pop().unwrap_or_default();
+                self.completion = Some(Completion::new(current, self.options.switch));
+            }
+            for p in &self.options.preprocessors {
+                self.args = self.args.drain(..).flat_map(|a| (p.0)(a)).collect();
             }
         }
-        self.tokens = tokens;
-        self.store = store;
+        self.tokenize();
         // proceed to the next state
         Cli::transition(self)
     }
@@ -437,67 +1495,45 @@ impl Cli<Ready> {
     /// of the [Command] trait.
     ///
     /// 1. `T` interprets the command-line data into its own structural data
-    /// 2. `T` executes its task
+    /// 2. `T` validates its structural data
+    /// 3. `T` executes its task
     ///
     /// This function will handle errors and report them to `stderr` if one
-    /// is encountered. If an error is encountered, the function returns 101 as
-    /// the exit code. If no error is encountered, the function returns 0 as the
-    /// exit code.
+    /// is encountered. If an error is encountered, the function returns the
+    /// code of [Exit::UsageError] or [Exit::RuntimeError] (101). If no error is
+    /// encountered, the function returns 0 as the exit code.
+    #[cfg(feature = "runner")]
     pub fn go<T: Command>(self) -> ExitCode {
-        let mut cli: Cli<Memory> = self.save();
+        self.save().run::<T>()
+    }
 
-        match T::interpret(&mut cli) {
-            // construct the application
-            Ok(program) => {
-                // verify the cli has no additional arguments if this is the top-level command being parsed
-                match cli.empty() {
-                    Ok(_) => {
-                        let cli_opts = cli.options.clone();
-                        std::mem::drop(cli);
-                        match program.execute() {
-                            Ok(_) => ExitCode::from(0),
-                            Err(err) => {
-                                eprintln!(
-                                    "{}{}{}",
-                                    cli_opts.err_prefix,
-                                    utils::format_err_msg(err.to_string(), cli_opts.cap_mode),
-                                    cli_opts.err_suffix
-                                );
-                                ExitCode::from(101)
-                            }
-                        }
-                    }
-                    // report cli error
-                    Err(err) => {
-                        let cli_opts = cli.options;
-                        match err.kind() {
-                            ErrorKind::Help => println!("{}", &err),
-                            _ => eprintln!(
-                                "{}{}{}",
-                                cli_opts.err_prefix,
-                                utils::format_err_msg(err.to_string(), cli_opts.cap_mode),
-                                cli_opts.err_suffix
-                            ),
-                        }
-                        ExitCode::from(err.code())
-                    }
-                }
-            }
-            // report cli error
-            Err(err) => {
-                let cli_opts = cli.options;
-                match err.kind() {
-                    ErrorKind::Help => println!("{}", &err),
-                    _ => eprintln!(
-                        "{}{}{}",
-                        cli_opts.err_prefix,
-                        utils::format_err_msg(err.to_string(), cli_opts.cap_mode),
-                        cli_opts.err_suffix
-                    ),
-                }
-                ExitCode::from(err.code())
-            }
-        }
+    /// Constructs `T` from the command-line without executing it.
+    ///
+    /// This function runs the same steps as [go][Cli::go] up until execution:
+    /// `T` interprets the command-line data, the command-line is verified to have
+    /// no additional arguments, and `T` is validated. This is useful when the
+    /// command's execution is controlled elsewhere, such as in a server process
+    /// or a test harness.
+    ///
+    /// Unlike [go][Cli::go], errors are returned instead of being reported.
+    pub fn build<T: Command>(self) -> Result<T> {
+        self.save().construct::<T>()
+    }
+
+    /// Runs the remaining steps in the command-line processor for `T` with the
+    /// context `context`.
+    ///
+    /// This function behaves the same as [go][Cli::go], except `context` is given
+    /// to `T` while it interprets the command-line and when it executes.
+    #[cfg(feature = "runner")]
+    pub fn go_with<T: CommandFrom<C>, C>(self, context: C) -> ExitCode {
+        self.save().run_with::<T, C>(context)
+    }
+
+    /// Constructs `T` from the command-line with the context `context` without
+    /// executing it, see [build][Cli::build].
+    pub fn build_with<T: CommandFrom<C>, C>(self, context: &C) -> Result<T> {
+        self.save().construct_with::<T, C>(context)
     }
 
     /// Saves the data from the command-line processing to be recalled during
@@ -516,23 +1552,175 @@ impl Cli<Memory> {
         self.tokens.len() == 0
     }
 
+    /// Restores the command-line processor to the state it was in when it was
+    /// first saved, allowing the command-line to be interpreted again.
+    ///
+    /// All consumed arguments are restored and the known arguments, help information,
+    /// and argument discovery order are reset. This is useful for performing a
+    /// pre-scan of the command-line (such as checking for a configuration file)
+    /// before the full interpretation.
+    pub fn rewind(&mut self) -> () {
+        self.tokenize();
+        self.known_args.clear();
+        self.consumed.clear();
+        self.scope = 0;
+        self.taken.clear();
+        self.declared.clear();
+        self.restricted.clear();
+        self.unstable = None;
+        self.stash = Stash::new();
+        if let Some(c) = &mut self.completion {
+            *c = Completion::new(c.current().to_string(), self.options.switch);
+        }
+        self.path.truncate(1);
+        self.asking_for_help = false;
+        self.help = None;
+        self.outer_helps.clear();
+        self.verbosity = Verbosity::Normal;
+        self.state = MemoryState::reset();
+    }
+
+    /// Lists the candidates for the last word on the command-line when the
+    /// command-line is being completed through the hidden `__complete` entry point,
+    /// see [complete][crate::complete].
+    ///
+    /// The candidates are collected as arguments are requested during interpretation.
+    pub fn completions(&self) -> Option<Vec<String>> {
+        self.completion
+            .as_ref()
+            .map(|c| c.candidates().iter().map(|w| w.to_string()).collect())
+    }
+
+    /// Iterates through the arguments remaining in the token stream in the
+    /// order they were supplied on the command-line.
+    ///
+    /// Arguments that were already consumed during interpretation are skipped.
+    /// This is useful for implementing a custom argument scheme on top of the
+    /// tokenizer.
+    pub fn tokens(&self) -> impl Iterator<Item = TokenView<'_>> + '_ {
+        self.tokens.iter().flatten().filter_map(|t| match t {
+            Token::Flag(i) => Some(TokenView::Flag(
+                i + 1,
+                flag_name(&self.args[*i], self.options.switch),
+            )),
+            Token::Switch(i, c) => Some(TokenView::Switch(i + 1, *c)),
+            Token::EmptySwitch(i) => Some(TokenView::EmptySwitch(i + 1)),
+            Token::AttachedArgument(i, s) => Some(TokenView::Value(i + 1, s)),
+            Token::UnattachedArgument(i, s) => Some(TokenView::Positional(i + 1, s)),
+            Token::Terminator(i) => Some(TokenView::Terminator(i + 1)),
+            Token::Ignore(i, s) => Some(TokenView::Ignored(i + 1, s)),
+            // inserted words were never written on the command-line
+            Token::Inserted(_) => None,
+        })
+    }
+
+    /// Declares `arg` to be a part of the command's interface.
+    ///
+    /// When [strict][Cli::strict] is enabled, every declared argument must be
+    /// requested before checking for an [empty][Cli::empty] command-line. A
+    /// declared flag never reserves the unattached argument that follows it as a
+    /// value, see [intermixed][Cli::intermixed].
+    pub fn declare<S: ArgState>(&mut self, arg: Arg<S>) -> () {
+        self.declared.push(ArgType::from(arg));
+    }
+
+    /// Sets aside the `value` to be taken by the command once it is executed,
+    /// see [take][crate::proc::stash::take].
+    ///
+    /// This is useful for passing along data derived during interpretation (such
+    /// as an opened file) without storing it in the command's struct. Only one
+    /// value of each type is kept, so stashing a value replaces any previous
+    /// value of the same type.
+    pub fn stash<T: 'static>(&mut self, value: T) -> () {
+        self.stash.insert(value);
+    }
+
+    /// References the name of the program.
+    ///
+    /// The name is the one set by [bin_name][Cli::bin_name], or otherwise the file
+    /// name of the first argument supplied during parsing.
+    pub fn program_name(&self) -> Option<&str> {
+        self.path.first().map(|s| s.as_str())
+    }
+
+    /// References the program name followed by the names of the subcommands
+    /// entered so far through [select][Cli::select].
+    ///
+    /// The program name is the same as [program_name][Cli::program_name].
+    pub fn command_path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// References the command-line arguments exactly as they were supplied during
+    /// parsing, beginning with the program as it was invoked.
+    ///
+    /// The arguments are not rewritten by any [preprocessors][Cli::preprocess], or
+    /// split into flags and values, which makes them suitable for recording the
+    /// exact invocation (ex: for an audit log) or for running the program again.
+    pub fn raw_args(&self) -> &[String] {
+        &self.raw_args
+    }
+
     /// Sets the [Help] information for the command-line processor.
     ///
     /// Once the help information is updated, this function returns true if help
     /// is detected on the command-line only if help is configured as a priority.
-    pub fn help(&mut self, help: Help) -> Result<bool> {
+    pub fn help(&mut self, mut help: Help) -> Result<bool> {
+        help.set_symbol(self.options.switch);
         self.help = Some(help);
         // check for flag if not already raised
         if self.asking_for_help == false && self.is_help_enabled() == true {
+            if self.help.as_ref().unwrap().has_format_selector() == true {
+                self.select_help_format()?;
+            }
             self.asking_for_help = self.check(self.help.as_ref().unwrap().get_arg())?;
         }
+        // reveal the hidden categories of help only if help was raised
+        if self.asking_for_help == true
+            && self.help.as_ref().unwrap().has_hidden() == true
+            && self.check(self.help.as_ref().unwrap().get_all_arg())? == true
+        {
+            self.help.as_mut().unwrap().show_all();
+        }
         Ok(self.asking_for_help)
     }
 
+    /// Sets the [Help] information for a nested command while remembering the
+    /// help of the command it is nested within.
+    ///
+    /// The help is combined with the current help according to its
+    /// [inheritance][Help::inherit], so errors reference the manual of the most
+    /// deeply nested command. Once the nested command is interpreted, call
+    /// [pop_help][Cli::pop_help] to restore the enclosing command's help.
+    ///
+    /// This function returns true if help is detected on the command-line, the
+    /// same as [help][Cli::help].
+    pub fn push_help(&mut self, help: Help) -> Result<bool> {
+        let help = match &self.help {
+            Some(parent) => help.nest_within(parent),
+            None => help,
+        };
+        self.outer_helps.push(self.help.take());
+        self.help(help)
+    }
+
+    /// Restores the [Help] information that was set before the last call to
+    /// [push_help][Cli::push_help], returning the help that is removed.
+    ///
+    /// If no help was pushed, the current help is removed.
+    pub fn pop_help(&mut self) -> Option<Help> {
+        match self.outer_helps.pop() {
+            Some(outer) => std::mem::replace(&mut self.help, outer),
+            None => self.help.take(),
+        }
+    }
+
     /// Attempts to display the currently available help information if help was
     /// detected on the command-line.
+    ///
+    /// Help is displayed under any [HelpPolicy] except [Never][HelpPolicy::Never].
     pub fn raise_help(&self) -> Result<()> {
-        self.try_to_help()
+        self.try_to_help_if_valid()
     }
 
     /// Clears the status flag indicating if help was detected on the command-line
@@ -552,13 +1740,14 @@ impl Cli<Memory> {
         &mut self,
         subcommand: Arg<Callable>,
     ) -> Result<Option<T>> {
+        self.complete_subcommand();
         self.known_args.push(ArgType::from(subcommand));
         // check but do not remove if an unattached arg exists
         let command_exists = self
             .tokens
             .iter()
             .find(|f| match f {
-                Some(Token::UnattachedArgument(_, _)) => true,
+                Some(Token::UnattachedArgument(_, _)) | Some(Token::Inserted(_)) => true,
                 _ => false,
             })
             .is_some();
@@ -566,14 +1755,102 @@ impl Cli<Memory> {
             // reset the parser state upon entering new subcommand
             self.state = MemoryState::reset();
             let sub = Some(T::interpret(self)?);
-            self.state.proceed(MemoryState::ProcessingSubcommands);
+            self.proceed(MemoryState::ProcessingSubcommands)?;
             Ok(sub)
         } else {
-            self.state.proceed(MemoryState::ProcessingSubcommands);
+            self.proceed(MemoryState::ProcessingSubcommands)?;
+            self.verify_restricted(None)?;
             return Ok(None);
         }
     }
 
+    /// Determines if an `UnattachedArg` exists to be served as a subcommand, or
+    /// otherwise serves the subcommand named `default`.
+    ///
+    /// When no subcommand is supplied, the command-line is processed as if `default`
+    /// was supplied immediately after the current command, so any remaining arguments
+    /// are given to the default subcommand. Help requested for the current command
+    /// is raised before entering the default subcommand.
+    ///
+    /// To allow no subcommand to be supplied, see [nest][Cli::nest].
+    pub fn nest_or<'a, T: Subcommand<U>, U>(
+        &mut self,
+        subcommand: Arg<Callable>,
+        default: &str,
+    ) -> Result<T> {
+        let command_exists = self
+            .tokens
+            .iter()
+            .find(|f| match f {
+                Some(Token::UnattachedArgument(_, _)) | Some(Token::Inserted(_)) => true,
+                _ => false,
+            })
+            .is_some();
+        if command_exists == false {
+            self.try_to_help()?;
+            self.insert_uarg(default.to_string());
+        }
+        Ok(self
+            .nest::<T, U>(subcommand)?
+            .expect("default subcommand must exist"))
+    }
+
+    /// Determines if an `UnattachedArg` exists to be served as a subcommand that
+    /// receives a context derived from its parent's context.
+    ///
+    /// The function `map` is stored alongside the subcommand and is called with
+    /// the parent's context once the [Nested] subcommand is executed. If no
+    /// subcommand exists, it will return none.
+    pub fn nest_with<T, P, C, F>(
+        &mut self,
+        subcommand: Arg<Callable>,
+        map: F,
+    ) -> Result<Option<Nested<T, P, C>>>
+    where
+        T: Subcommand<C>,
+        F: FnOnce(&P) -> C + 'static,
+    {
+        Ok(self
+            .nest::<T, C>(subcommand)?
+            .map(|sub| Nested::new(sub, map)))
+    }
+
+    /// Proceeds to the next state in the argument discovery order.
+    ///
+    /// Errors if the state transition is invalid and structural hazards are caught,
+    /// otherwise an invalid state transition panics. The order is not enforced
+    /// during unordered discovery.
+    fn proceed(&mut self, next: MemoryState) -> Result<()> {
+        if let Some(e) = &self.exceeded {
+            return Err(Error::new(
+                self.help.clone(),
+                ErrorKind::ExceededLimit,
+                ErrorContext::ExceededLimit(e.subject.clone(), e.unit, e.cur, e.max),
+                self.options.cap_mode,
+            ));
+        }
+        if self.options.unordered == true {
+            return Ok(());
+        }
+        // only the subcommands are restricted to being requested last
+        if self.options.intermixed == true
+            && self.state < MemoryState::ProcessingSubcommands
+            && next < MemoryState::ProcessingSubcommands
+        {
+            return Ok(());
+        }
+        if self.options.catch_hazards == true && self.state > next {
+            return Err(Error::new(
+                self.help.clone(),
+                ErrorKind::StructuralHazard,
+                ErrorContext::StructuralHazard(self.state.to_string(), next.to_string()),
+                self.options.cap_mode,
+            ));
+        }
+        self.state.proceed(next);
+        Ok(())
+    }
+
     /// Tries to match the next positional argument against an array of strings in `bank`.
     ///
     /// If fails, it will attempt to offer a spelling suggestion if the name is close depending
@@ -583,25 +1860,37 @@ impl Cli<Memory> {
     /// called immediately in the nested subcommand's [interpret][super::Command::interpret] method, which is
     /// triggered on a successful call to the previous command's call to [nest][Cli::nest].
     pub fn select<T: AsRef<str> + std::cmp::PartialEq>(&mut self, bank: &[T]) -> Result<String> {
+        if let Some(c) = &mut self.completion {
+            if c.is_selecting() == true {
+                c.resolve(bank.iter().map(|w| w.as_ref().to_string()));
+            }
+        }
         // find the unattached arg's index before it is removed from the token stream
-        let i: usize = self
+        let i: Option<usize> = self
             .tokens
             .iter()
             .find_map(|f| match f {
-                Some(Token::UnattachedArgument(i, _)) => Some(*i),
+                Some(Token::UnattachedArgument(i, _)) => Some(Some(*i)),
+                Some(Token::Inserted(_)) => Some(None),
                 _ => None,
             })
             .expect("an unattached argument must exist before calling `match(...)`");
         let command = self
             .next_uarg()
             .expect("`nest(...)` must be called before this function");
+        // positional indices for the subcommand begin after its name
+        self.scope = self.consumed.last().unwrap() + 1;
 
         // perform partial clean to ensure no arguments are remaining behind the command (uncaught options)
-        let ooc_arg = self.capture_bad_flag(i)?;
+        // an inserted subcommand has no arguments written behind it
+        let ooc_arg = match i {
+            Some(i) => self.capture_bad_flag(i)?,
+            None => None,
+        };
 
         if bank.iter().find(|p| p.as_ref() == command).is_some() {
             if let Some((prefix, key, pos)) = ooc_arg {
-                if pos < i {
+                if i.is_some_and(|i| pos < i) {
                     self.try_to_help()?;
                     return Err(Error::new(
                         self.help.clone(),
@@ -611,32 +1900,39 @@ impl Cli<Memory> {
                     ));
                 }
             }
+            self.verify_restricted(Some(&command))?;
+            self.path.push(command.clone());
             Ok(command)
         // try to offer a spelling suggestion otherwise say we've hit an unexpected argument
         } else {
-            // bypass sequence alignment algorithm if threshold == 0
-            if let Some(w) = if self.options.threshold > 0 {
-                seqalin::sel_min_edit_str(&command, &bank, self.options.threshold)
-            } else {
-                None
-            } {
+            let words = self.closest_words(&command, &bank);
+            if words.is_empty() == false {
+                let span = self.span_of_value(&command);
                 Err(Error::new(
                     self.help.clone(),
                     ErrorKind::SuggestSubcommand,
-                    ErrorContext::SuggestWord(command, w.to_string()),
+                    ErrorContext::SuggestWord(
+                        command,
+                        words.into_iter().map(|w| w.to_string()).collect(),
+                    ),
                     self.options.cap_mode,
-                ))
+                )
+                .with_span(span))
             } else {
                 self.try_to_help()?;
+                let span = self.span_of_value(&command);
                 Err(Error::new(
                     self.help.clone(),
                     ErrorKind::UnknownSubcommand,
                     ErrorContext::UnknownSubcommand(
                         self.known_args.pop().expect("requires positional argument"),
                         command,
+                        self.path.clone(),
+                        bank.iter().map(|w| w.as_ref().to_string()).collect(),
                     ),
                     self.options.cap_mode,
-                ))
+                )
+                .with_span(span))
             }
         }
     }
@@ -657,6 +1953,21 @@ impl Cli<Memory> {
         }
     }
 
+    /// Returns the state of `arg` when it is turned on and off by the symbol it is
+    /// written with, see [paired_symbol][Cli::paired_symbol].
+    ///
+    /// The last instance of `arg` decides the state: the result is `true` if it
+    /// was written with the positive symbol (ex: `+sb`) and `false` if it was
+    /// written with `-` (ex: `-sb`). If `arg` is not found, the result is none.
+    ///
+    /// This function errors if a value is associated with an instance of `arg`.
+    pub fn toggle(&mut self, arg: Arg<Raisable>) -> Result<Option<bool>> {
+        match ArgType::from(arg) {
+            ArgType::Flag(fla) => self.toggle_flag(fla),
+            _ => panic!("impossible code condition"),
+        }
+    }
+
     /// Returns the number of instances that `arg` exists.
     ///
     /// - If `arg` is a flag, then it checks for all references of its associated name.
@@ -672,6 +1983,181 @@ impl Cli<Memory> {
         }
     }
 
+    /// Returns the number of times `arg` was raised according to its [Action].
+    ///
+    /// - If the action is [SetTrue][Action::SetTrue], then it behaves like [check][Cli::check] and the result is 0 or 1.
+    /// - If the action is [Count][Action::Count], then it behaves like [check_all][Cli::check_all].
+    ///
+    /// This function errors if any other action is set on the flag.
+    pub fn count<'a>(&mut self, arg: Arg<Raisable>) -> Result<usize> {
+        match ArgType::from(arg) {
+            ArgType::Flag(fla) => match fla.get_action() {
+                Action::SetTrue => Ok(self.check_flag(fla)? as usize),
+                Action::Count => self.check_flag_all(fla),
+                a => Err(self.unsupported_action(ArgType::Flag(fla), a)),
+            },
+            _ => panic!("impossible code condition"),
+        }
+    }
+
+    /// Returns the [Verbosity] requested on the command-line through the common
+    /// `--quiet` (`-q`) and `--verbose` (`-v`) flags.
+    ///
+    /// Each instance of `--verbose` increases the verbosity by one level. The
+    /// verbosity also determines what is written when the command is run with
+    /// [go][Cli::go], so the output is consistent across all commands.
+    ///
+    /// This function errors if both flags are supplied.
+    pub fn check_verbosity<'a>(&mut self) -> Result<Verbosity> {
+        let quiet = || Arg::flag("quiet").switch('q');
+        let verbose = || Arg::flag("verbose").switch('v');
+        let is_quiet = self.check(quiet())?;
+        let verbose_count = self.check_all(verbose())?;
+        self.verbosity = match (is_quiet, verbose_count) {
+            (true, 0) => Verbosity::Quiet,
+            (true, _) => {
+                self.try_to_help()?;
+                let mut other = ArgType::from(verbose());
+                other.set_symbol(self.options.switch);
+                return Err(self.reject(quiet(), format!("cannot be used with \"{}\"", other)));
+            }
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        };
+        Ok(self.verbosity)
+    }
+
+    /// Returns the signed level set by the pair of count flags `up` and `down`
+    /// (ex: `-v` and `-q`), clamped within `bounds`.
+    ///
+    /// Each instance of `up` raises the level by one and each instance of `down`
+    /// lowers it by one, so `-vvq` results in a level of 1. If neither flag is
+    /// found, then the result is 0 (or the nearest bound to 0).
+    ///
+    /// This function errors if a value is associated with an instance of either flag.
+    ///
+    /// Passing empty `bounds` (ex: `2..=-1`) is considered a programmer's error
+    /// and will result in a panic!.
+    pub fn check_level(
+        &mut self,
+        up: Arg<Raisable>,
+        down: Arg<Raisable>,
+        bounds: std::ops::RangeInclusive<isize>,
+    ) -> Result<isize> {
+        if bounds.is_empty() == true {
+            panic!(
+                "level bounds {}..={} must not be empty",
+                bounds.start(),
+                bounds.end()
+            );
+        }
+        let raised = self.check_all(up)? as isize;
+        let lowered = self.check_all(down)? as isize;
+        Ok((raised - lowered).clamp(*bounds.start(), *bounds.end()))
+    }
+
+    /// Returns the [ColorChoice] requested on the command-line through the common
+    /// `--color <when>` option, where `<when>` is `always`, `never`, or `auto`.
+    ///
+    /// The choice replaces the coloring set by the builder (such as
+    /// [allow_color][Cli::allow_color]), so the errors reported by the [Cli] are
+    /// colored the same as the command's own output. If the option is not
+    /// supplied, the builder's coloring is returned.
+    ///
+    /// This function errors if the value is not one of the accepted choices or
+    /// if the option is supplied more than once.
+    pub fn check_color(&mut self) -> Result<ColorChoice> {
+        let choice = self.get(
+            Arg::option_of::<ColorChoice>("color")
+                .value("when")
+                .example("--color never")
+                .completer(|_: &str| ColorChoice::VALUES.map(String::from).to_vec()),
+        )?;
+        if let Some(c) = choice {
+            self.options.color_mode = ColorMode::from(c);
+        }
+        Ok(ColorChoice::from(&self.options.color_mode))
+    }
+
+    /// Returns a [Confirm] for asking the user before an operation that cannot be
+    /// undone, which answers "yes" without asking when the common `--yes` (`-y`)
+    /// flag is raised on the command-line.
+    ///
+    /// This function errors if the flag is supplied more than once.
+    #[cfg(feature = "runner")]
+    pub fn check_yes(&mut self) -> Result<Confirm> {
+        Ok(Confirm::new(self.check(Arg::flag("yes").switch('y'))?))
+    }
+
+    /// Checks if experimental arguments are enabled through the common
+    /// `--enable-unstable` flag, see [unstable][Arg::unstable].
+    ///
+    /// Once checked, errors for an experimental argument also suggest raising
+    /// this flag. Call this function before requesting any experimental arguments.
+    ///
+    /// This function errors if the flag is supplied more than once.
+    pub fn check_unstable(&mut self) -> Result<bool> {
+        let enabled = self.check(Arg::flag("enable-unstable"))?;
+        self.unstable = Some(enabled);
+        Ok(enabled)
+    }
+
+    /// Creates a [Status] that writes a command's status messages according to
+    /// the verbosity and coloring requested on the command-line.
+    ///
+    /// Call [check_verbosity][Cli::check_verbosity] and [check_color][Cli::check_color]
+    /// beforehand for the flags to take effect.
+    #[cfg(feature = "runner")]
+    pub fn status(&self) -> Status {
+        Status::new(self.verbosity, self.options.color_mode.is_enabled())
+    }
+
+    /// Returns the [OutputFormat] requested on the command-line through the common
+    /// `--output <format>` option, accepting only the formats supported by `R`.
+    ///
+    /// If the option is not supplied, the first supported format is returned.
+    ///
+    /// This function errors if the format is not supported by `R` or if the option
+    /// is supplied more than once.
+    pub fn check_output<R: Render>(&mut self) -> Result<OutputFormat> {
+        let formats = R::formats();
+        let names: Vec<String> = formats.iter().map(|f| f.to_string()).collect();
+        let completions = names.clone();
+        let name: Option<String> = self.get(
+            Arg::option("output")
+                .value("format")
+                .completer(move |_: &str| completions.clone()),
+        )?;
+        let name = match name {
+            Some(n) => n,
+            None => return Ok(*formats.first().expect("no supported output formats")),
+        };
+        match OutputFormat::from_name(&name).filter(|f| formats.contains(f)) {
+            Some(format) => Ok(format),
+            None => {
+                self.try_to_help()?;
+                let span = self.span_of_value(&name);
+                let expected = names
+                    .iter()
+                    .map(|n| format!("\"{}\"", n))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::BadType,
+                    ErrorContext::FailedCast(
+                        self.known_args.pop().unwrap(),
+                        name,
+                        format!("supported formats are {}", expected),
+                    ),
+                    self.options.cap_mode,
+                )
+                .with_span(span))
+            }
+        }
+    }
+
     /// Returns the number of instances that `arg` exists, up until an amount equal to `limit`.
     ///
     /// - If `arg` is a flag, then it checks for all references of its associated name.
@@ -719,10 +2205,11 @@ impl Cli<Memory> {
     ///
     /// This function errors if parsing into type `T` fails or if the number of values found
     /// is greater than 1.
-    pub fn get<'a, T: FromStr>(&mut self, arg: Arg<Valuable>) -> Result<Option<T>>
+    pub fn get<'a, T: FromStr>(&mut self, arg: impl ValueArg<T>) -> Result<Option<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.get_option(opt),
             ArgType::Positional(pos) => self.get_positional(pos),
@@ -730,6 +2217,60 @@ impl Cli<Memory> {
         }
     }
 
+    /// Returns a single value associated with `arg`, or computes a value from `default`
+    /// if none exists.
+    ///
+    /// - If `arg` is a positional argument, then it takes the next unnamed argument.
+    /// - If `arg` is an option argument, then it takes the value associated with its name.
+    ///
+    /// The `arg` remains known to the processor (for help and suggestions) even
+    /// when it is not supplied on the command-line. This is useful for arguments
+    /// whose default is derived from the environment, such as the current directory.
+    ///
+    /// This function errors if parsing into type `T` fails, if the number of values found
+    /// is greater than 1, or if computing the `default` fails.
+    pub fn get_or<'a, T: FromStr, F, E>(&mut self, arg: impl ValueArg<T>, default: F) -> Result<T>
+    where
+        <T as FromStr>::Err: std::error::Error,
+        F: FnOnce() -> std::result::Result<T, E>,
+        E: std::error::Error + 'static,
+    {
+        let arg = arg.into_valuable();
+        match self.get(arg)? {
+            Some(value) => Ok(value),
+            None => default().map_err(|e| {
+                Error::new(
+                    self.help.clone(),
+                    ErrorKind::CustomRule,
+                    ErrorContext::CustomRule(Box::new(e)),
+                    self.options.cap_mode,
+                )
+            }),
+        }
+    }
+
+    /// Creates a custom rule error with the reason `message` that is associated
+    /// with the argument `arg`.
+    ///
+    /// This is useful for rejecting a value that was successfully parsed but is
+    /// otherwise invalid, see [Error::custom_for].
+    pub fn reject<S: ArgState, M: AsRef<str>>(&self, arg: Arg<S>, message: M) -> Error {
+        Error::new(
+            None,
+            ErrorKind::CustomRule,
+            ErrorContext::CustomArg(
+                ArgType::from(arg),
+                Box::<dyn std::error::Error>::from(message.as_ref()),
+            ),
+            CapMode::default(),
+        )
+        .attach(
+            self.help.clone(),
+            self.options.cap_mode,
+            self.options.switch,
+        )
+    }
+
     /// Returns all values associated with `arg`, if they exist.
     ///
     /// - If `arg` is a positional argument, then it takes all the following unnamed arguments.
@@ -738,11 +2279,15 @@ impl Cli<Memory> {
     /// If no values exists for `arg`, the result is `None`. If values do exist,
     /// then the resulting vector is guaranteed to have `1 <= len()`.
     ///
+    /// The values are in the order they appear on the command-line, regardless of
+    /// whether an option is written by its name, an alias, or its switch.
+    ///
     /// This function errors if parsing into type `T` fails.
-    pub fn get_all<'a, T: FromStr>(&mut self, arg: Arg<Valuable>) -> Result<Option<Vec<T>>>
+    pub fn get_all<'a, T: FromStr>(&mut self, arg: impl ValueArg<T>) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.get_option_all(opt),
             ArgType::Positional(pos) => self.get_positional_all(pos),
@@ -750,6 +2295,30 @@ impl Cli<Memory> {
         }
     }
 
+    /// Returns all values associated with `arg` along with their positions on the
+    /// command-line, if they exist.
+    ///
+    /// Each position is the index of the argument the value was written in,
+    /// where the program name is at position 0 (see [Span::index]). This is
+    /// useful when the values of different arguments must be ordered relative to
+    /// each other (ex: `--include` and `--exclude` patterns).
+    ///
+    /// This function behaves the same as [get_all][Cli::get_all] otherwise.
+    pub fn get_all_indexed<'a, T: FromStr>(
+        &mut self,
+        arg: impl ValueArg<T>,
+    ) -> Result<Option<Vec<(usize, T)>>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let arg = arg.into_valuable();
+        match ArgType::from(arg) {
+            ArgType::Optional(opt) => self.get_option_all_indexed(opt),
+            ArgType::Positional(pos) => self.get_positional_all_indexed(pos),
+            _ => panic!("impossible code condition"),
+        }
+    }
+
     /// Returns all values associated with `arg` up until an amount equal to `limit`, if they exist.
     ///
     /// - If `arg` is a positional argument, then it takes all remaining unnamed arguments up until `limit`.  
@@ -762,12 +2331,13 @@ impl Cli<Memory> {
     /// values found exceeds the specified `limit`.
     pub fn get_until<'a, T: FromStr>(
         &mut self,
-        arg: Arg<Valuable>,
+        arg: impl ValueArg<T>,
         limit: usize,
     ) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.get_option_until(opt, limit),
             ArgType::Positional(pos) => self.get_positional_until(pos, limit),
@@ -787,12 +2357,13 @@ impl Cli<Memory> {
     /// values found is not contained within `span`.
     pub fn get_between<'a, T: FromStr, R: RangeBounds<usize>>(
         &mut self,
-        arg: Arg<Valuable>,
+        arg: impl ValueArg<T>,
         span: R,
     ) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.get_option_between(opt, span),
             ArgType::Positional(pos) => self.get_positional_between(pos, span),
@@ -807,10 +2378,11 @@ impl Cli<Memory> {
     ///
     /// This function errors if parsing into type `T` fails or if the number of values found
     /// is not exactly equal to 1.
-    pub fn require<'a, T: FromStr>(&mut self, arg: Arg<Valuable>) -> Result<T>
+    pub fn require<'a, T: FromStr>(&mut self, arg: impl ValueArg<T>) -> Result<T>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.require_option(opt),
             ArgType::Positional(pos) => self.require_positional(pos),
@@ -818,6 +2390,137 @@ impl Cli<Memory> {
         }
     }
 
+    /// Returns the values associated with `arg` according to its [Action].
+    ///
+    /// - If the action is [Set][Action::Set], then it behaves like [get][Cli::get] and the result has at most one value.
+    /// - If the action is [Append][Action::Append], then it behaves like [get_all][Cli::get_all].
+    ///
+    /// If no values exist for `arg`, the result is empty. This function errors if
+    /// any other action is set on the option or positional.
+    pub fn values<'a, T: FromStr>(&mut self, arg: impl ValueArg<T>) -> Result<Vec<T>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let arg = arg.into_valuable();
+        let action = match arg.get_type() {
+            ArgType::Optional(o) => o.get_positional().get_action(),
+            ArgType::Positional(p) => p.get_action(),
+            _ => panic!("impossible code condition"),
+        };
+        match action {
+            Action::Set => Ok(self.get(arg)?.into_iter().collect()),
+            Action::Append => Ok(self.get_all(arg)?.unwrap_or_default()),
+            a => Err(self.unsupported_action(ArgType::from(arg), a)),
+        }
+    }
+
+    /// Creates the error for the `action` set on `arg` that cannot be processed
+    /// by the kind of argument, see [count][Cli::count] and [values][Cli::values].
+    fn unsupported_action(&self, mut arg: ArgType, action: Action) -> Error {
+        arg.set_symbol(self.options.switch);
+        Error::new(
+            self.help.clone(),
+            ErrorKind::UnsupportedAction,
+            ErrorContext::UnsupportedAction(arg, action),
+            self.options.cap_mode,
+        )
+    }
+
+    /// Returns every `<key>=<value>` pair supplied behind the prefix of `arg`, in
+    /// the order they appear on the command-line.
+    ///
+    /// This is useful when the keys are not known ahead of time, such as with
+    /// compiler-style defines (`-DNAME=1`). A key supplied without a value
+    /// (ex: `-DNAME`) has an empty value. If the prefix is never supplied, the
+    /// result is empty.
+    ///
+    /// Dynamic arguments are discovered alongside flags, and should be requested
+    /// before any flags so the switches written within a key (ex: `-Dverbose`) are
+    /// not mistaken for other flags.
+    ///
+    /// This function errors if the prefix is missing its key.
+    pub fn get_dynamic<'a>(&mut self, arg: Arg<Dynamic>) -> Result<Vec<(String, String)>> {
+        self.proceed(MemoryState::ProcessingFlags)?;
+        let opt = ArgType::from(arg).into_option().unwrap();
+        let words: Vec<Option<String>> = match opt.get_flag().is_switch_only() {
+            true => {
+                let c = *opt.get_flag().get_switch().unwrap();
+                self.learn(ArgType::Optional(opt));
+                self.pull_prefix(c)
+            }
+            false => {
+                let locs = self.take_locs(opt.get_flag());
+                self.learn(ArgType::Optional(opt));
+                self.pull_flag(locs, true)
+            }
+        };
+        let mut pairs = Vec::with_capacity(words.len());
+        for word in words {
+            match word
+                .as_deref()
+                .map(|w| w.split_once('=').unwrap_or((w, "")))
+            {
+                Some((key, value)) if key.is_empty() == false => {
+                    pairs.push((key.to_string(), value.to_string()))
+                }
+                _ => {
+                    self.try_to_help()?;
+                    return Err(Error::new(
+                        self.help.clone(),
+                        ErrorKind::ExpectingValue,
+                        ErrorContext::FailedArg(self.known_args.pop().unwrap()),
+                        self.options.cap_mode,
+                    ));
+                }
+            }
+        }
+        Ok(pairs)
+    }
+
+    /// Returns the value associated with `arg` if it exists, where `arg` is
+    /// required unless any of the flags in `unless` were raised.
+    ///
+    /// The flags in `unless` are only looked up and are not consumed, so they
+    /// should still be checked during flag discovery.
+    ///
+    /// This function errors if parsing into type `T` fails or if none of the
+    /// flags in `unless` were raised and `arg` is missing.
+    pub fn require_unless<'a, T: FromStr>(
+        &mut self,
+        arg: impl ValueArg<T>,
+        unless: &[Arg<Raisable>],
+    ) -> Result<Option<T>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let arg = arg.into_valuable();
+        let flags: Vec<&Flag> = unless
+            .iter()
+            .map(|f| f.get_type().as_flag().unwrap())
+            .collect();
+        if flags.iter().any(|f| self.is_flag_present(f) == true) {
+            return self.get(arg);
+        }
+        match self.require(arg) {
+            Ok(r) => Ok(Some(r)),
+            Err(e) => match (e.kind(), e.context()) {
+                (
+                    ErrorKind::MissingPositional | ErrorKind::MissingOption,
+                    ErrorContext::FailedArg(a),
+                ) => Err(Error::new(
+                    self.help.clone(),
+                    e.kind(),
+                    ErrorContext::MissingUnless(
+                        a.clone(),
+                        flags.iter().map(|f| f.to_string()).collect(),
+                    ),
+                    self.options.cap_mode,
+                )),
+                _ => Err(e),
+            },
+        }
+    }
+
     /// Returns all values associated with `arg`.
     ///
     /// - If `arg` is a positional argument, then it takes all remaining unnamed arguments.  
@@ -825,11 +2528,13 @@ impl Cli<Memory> {
     ///
     /// This function errors if parsing into type `T` fails or if zero values are found.
     ///
-    /// The resulting vector is guaranteed to have `1 <= len()`.
-    pub fn require_all<'a, T: FromStr>(&mut self, arg: Arg<Valuable>) -> Result<Vec<T>>
+    /// The resulting vector is guaranteed to have `1 <= len()`, with the values in
+    /// the order they appear on the command-line.
+    pub fn require_all<'a, T: FromStr>(&mut self, arg: impl ValueArg<T>) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.require_option_all(opt),
             ArgType::Positional(pos) => self.require_positional_all(pos),
@@ -848,12 +2553,13 @@ impl Cli<Memory> {
     /// The resulting vector is guaranteed to have `1 <= len() <= limit`.
     pub fn require_until<'a, T: FromStr>(
         &mut self,
-        arg: Arg<Valuable>,
+        arg: impl ValueArg<T>,
         limit: usize,
     ) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.require_option_until(opt, limit),
             ArgType::Positional(pos) => self.require_positional_until(pos, limit),
@@ -872,12 +2578,13 @@ impl Cli<Memory> {
     /// The resulting vector is guaranteed to have `1 <= len() <= limit`.
     pub fn require_between<'a, T: FromStr, R: RangeBounds<usize>>(
         &mut self,
-        arg: Arg<Valuable>,
+        arg: impl ValueArg<T>,
         span: R,
     ) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
+        let arg = arg.into_valuable();
         match ArgType::from(arg) {
             ArgType::Optional(opt) => self.require_option_between(opt, span),
             ArgType::Positional(pos) => self.require_positional_between(pos, span),
@@ -885,42 +2592,101 @@ impl Cli<Memory> {
         }
     }
 
+    /// Returns the value of the positional `arg` found at the positional `index`, if one exists.
+    ///
+    /// Unlike [get][Cli::get], the value is selected by its position among
+    /// the (sub)command's positional arguments rather than being the next
+    /// remaining positional, so positionals can be requested in any order.
+    ///
+    /// If no value exists at `index`, the result is `None`.
+    ///
+    /// This function errors if parsing into type `T` fails. Panics if `arg` is not
+    /// a positional argument.
+    pub fn get_at<'a, T: FromStr>(
+        &mut self,
+        arg: impl ValueArg<T>,
+        index: usize,
+    ) -> Result<Option<T>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let arg = arg.into_valuable();
+        match ArgType::from(arg) {
+            ArgType::Positional(pos) => self.get_positional_at(pos, index),
+            _ => panic!("an index can only be used with a positional argument"),
+        }
+    }
+
+    /// Returns the value of the positional `arg` found at the positional `index`.
+    ///
+    /// Unlike [require][Cli::require], the value is selected by its position among
+    /// the (sub)command's positional arguments rather than being the next
+    /// remaining positional, so positionals can be requested in any order.
+    ///
+    /// This function errors if parsing into type `T` fails or if no value exists
+    /// at `index`. Panics if `arg` is not a positional argument.
+    pub fn require_at<'a, T: FromStr>(&mut self, arg: impl ValueArg<T>, index: usize) -> Result<T>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let arg = arg.into_valuable();
+        match ArgType::from(arg) {
+            ArgType::Positional(pos) => self.require_positional_at(pos, index),
+            _ => panic!("an index can only be used with a positional argument"),
+        }
+    }
+
     /// Checks that there are no more unprocessed arguments that were stored in
     /// memory.
     ///
     /// This function errors if there are any unhandled arguments that were never
     /// requested during the [Memory] stage.
     pub fn empty<'a>(&'a mut self) -> Result<()> {
-        self.state.proceed(MemoryState::End);
+        self.verify_empty()?;
+        // help waits for the rest of the command-line to be valid
+        self.try_to_help_if_valid()
+    }
+
+    /// Collects the flags and switches that were never requested, along with
+    /// their attached values, instead of reporting them as unexpected arguments.
+    ///
+    /// Each argument is written as it was supplied on the command-line, except
+    /// combined switches only keep the switches that were never requested (ex:
+    /// `-vx` becomes `-x` once `-v` is checked). This is useful for forwarding
+    /// unrecognized flags to an underlying tool. Arguments after the terminator
+    /// flag (`--`) are left for [remainder][Cli::remainder].
+    ///
+    /// If there are no unknown flags or switches, the result is an empty list.
+    pub fn collect_unknown(&mut self) -> Result<Vec<String>> {
         self.try_to_help()?;
-        // check if map is empty, and return the minimum found index.
-        if let Some((prefix, key, _)) = self.capture_bad_flag(self.tokens.len())? {
-            Err(Error::new(
-                self.help.clone(),
-                ErrorKind::UnexpectedArg,
-                ErrorContext::UnexpectedArg(format!("{}{}", prefix, key)),
-                self.options.cap_mode,
-            ))
-        // find first non-none token
-        } else if let Some(t) = self.tokens.iter().find(|p| p.is_some()) {
-            match t {
-                Some(Token::UnattachedArgument(_, word)) => Err(Error::new(
-                    self.help.clone(),
-                    ErrorKind::UnexpectedArg,
-                    ErrorContext::UnexpectedArg(word.to_string()),
-                    self.options.cap_mode,
-                )),
-                Some(Token::Terminator(_)) => Err(Error::new(
-                    self.help.clone(),
-                    ErrorKind::UnexpectedArg,
-                    ErrorContext::UnexpectedArg(symbol::FLAG.to_string()),
-                    self.options.cap_mode,
-                )),
-                _ => panic!("no other tokens types should be left"),
+        let mut words = Vec::<String>::new();
+        let mut removed = Vec::<usize>::new();
+        let mut prev: Option<usize> = None;
+        for (j, tkn) in self.tokens.iter().enumerate() {
+            match tkn {
+                Some(Token::Terminator(_)) | Some(Token::Ignore(_, _)) => break,
+                Some(Token::Flag(i)) => words.push(self.args[*i].clone()),
+                Some(Token::Switch(i, c)) => match prev == Some(*i) {
+                    true => words.last_mut().unwrap().push(*c),
+                    // keep the symbol the switches were written with
+                    false => words.push(format!("{}{}", self.args[*i].chars().next().unwrap(), c)),
+                },
+                Some(Token::EmptySwitch(_)) => words.push(self.options.switch.to_string()),
+                // keep the value attached to the flag or switch before it
+                Some(Token::AttachedArgument(i, val)) if prev == Some(*i) => {
+                    if let Some(Token::Switch(_, _)) | Some(Token::EmptySwitch(_)) =
+                        self.tokens[j - 1]
+                    {
+                        words.last_mut().unwrap().push_str(&format!("={}", val));
+                    }
+                }
+                _ => continue,
             }
-        } else {
-            Ok(())
+            prev = tkn.as_ref().unwrap().get_index();
+            removed.push(j);
         }
+        self.remove_tokens(&removed);
+        Ok(words)
     }
 
     /// Collects the list of arguments that were ignored due to being placed after
@@ -959,6 +2725,177 @@ impl Cli<Memory> {
             })
             .collect()
     }
+
+    /// Serves the only argument that was ignored due to being placed after a
+    /// terminator flag (`--`), parsed as `T`.
+    ///
+    /// This function errors if zero or multiple arguments follow the terminator,
+    /// or if the argument fails to parse. See [remainder][Cli::remainder].
+    pub fn after_terminator_one<T: FromStr>(&mut self) -> Result<T>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let mut words = self.remainder()?;
+        if words.len() != 1 {
+            self.try_to_help()?;
+            return Err(Error::new(
+                self.help.clone(),
+                match words.len() {
+                    0 => ErrorKind::MissingPositional,
+                    _ => ErrorKind::ExceedingMaxCount,
+                },
+                ErrorContext::TerminatorArity(self.flag_prefix(), words.len()),
+                self.options.cap_mode,
+            ));
+        }
+        let word = words.pop().unwrap();
+        match word.parse::<T>() {
+            Ok(r) => Ok(r),
+            Err(err) => {
+                self.try_to_help()?;
+                Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::BadType,
+                    ErrorContext::FailedCast(ArgType::Flag(Flag::new("")), word, err.to_string()),
+                    self.options.cap_mode,
+                ))
+            }
+        }
+    }
+
+    /// Takes the remaining arguments as a stream of [Operand]s in the order they
+    /// were supplied on the command-line.
+    ///
+    /// The stream begins at the first remaining argument that is a flag, a
+    /// switch, `(`, or `!`, and ends before the terminator flag (`--`). Arguments
+    /// are not split into switches, so words such as `-name` are delivered as
+    /// they were written. This function should be called before requesting any
+    /// other arguments that may appear within the stream.
+    ///
+    /// If there are no operands, the result is an empty list.
+    pub fn operands(&mut self) -> Vec<Operand> {
+        let start = self
+            .tokens
+            .iter()
+            .flatten()
+            .find(|t| match t {
+                Token::Terminator(_) | Token::Ignore(_, _) => true,
+                _ => t
+                    .get_index()
+                    .is_some_and(|i| Operand::is_leading(&self.args[i])),
+            })
+            .and_then(|t| match t {
+                Token::Terminator(_) | Token::Ignore(_, _) => None,
+                _ => t.get_index(),
+            });
+        let start = match start {
+            Some(i) => i,
+            None => return Vec::new(),
+        };
+        // remove every token that belongs to an argument within the stream
+        let mut indices = Vec::<usize>::new();
+        let mut removed = Vec::<usize>::new();
+        for (j, tkn) in self.tokens.iter().enumerate() {
+            match tkn {
+                Some(Token::Terminator(_)) | Some(Token::Ignore(_, _)) => break,
+                Some(t) if t.get_index().is_some_and(|i| i >= start) => {
+                    let i = t.get_index().unwrap();
+                    if indices.last() != Some(&i) {
+                        indices.push(i);
+                    }
+                    removed.push(j);
+                }
+                _ => (),
+            }
+        }
+        self.remove_tokens(&removed);
+        indices
+            .into_iter()
+            .map(|i| Operand::new(&self.args[i]))
+            .collect()
+    }
+
+    /// Takes the remaining arguments as an [Expr] grouped by its operators, see
+    /// [operands][Cli::operands].
+    ///
+    /// If there are no operands, the result is none.
+    ///
+    /// This function errors if the operands do not form a valid expression.
+    pub fn expression(&mut self) -> Result<Option<Expr>> {
+        let operands = self.operands();
+        if operands.is_empty() == true {
+            return Ok(None);
+        }
+        match Expr::parse(&operands) {
+            Ok(expr) => Ok(Some(expr)),
+            Err(err) => {
+                self.try_to_help()?;
+                Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::InvalidExpression,
+                    ErrorContext::InvalidExpression(err.to_string()),
+                    self.options.cap_mode,
+                ))
+            }
+        }
+    }
+
+    /// Takes the remaining arguments and interprets them with the semantics of
+    /// `getopt_long`, as described by the `spec`.
+    ///
+    /// Arguments are delivered as they were written, so the spec alone decides
+    /// how switches are grouped and where an option's argument ends. This function
+    /// should be called instead of requesting any other arguments from the
+    /// command-line.
+    ///
+    /// This function errors on the first option that is unknown, ambiguous, or
+    /// has an invalid argument.
+    pub fn getopt(&mut self, spec: &Getopt) -> Result<Matches> {
+        // collect every remaining token, keeping each argument once
+        let mut words = Vec::<(Option<usize>, String)>::new();
+        let mut removed = Vec::<usize>::new();
+        for (j, tkn) in self.tokens.iter().enumerate() {
+            let t = match tkn {
+                Some(t) => t,
+                None => continue,
+            };
+            let i = t.get_index();
+            if i.is_none() || words.last().is_some_and(|(k, _)| *k == i) == false {
+                words.push(match (t, i) {
+                    (Token::UnattachedArgument(_, s), _) | (Token::Inserted(s), _) => {
+                        (i, s.clone())
+                    }
+                    (_, Some(i)) => (Some(i), self.args[i].clone()),
+                    (_, None) => continue,
+                });
+            }
+            removed.push(j);
+        }
+        let words: Vec<String> = words.into_iter().map(|(_, w)| w).collect();
+        match spec.parse(&words) {
+            Ok(matches) => {
+                self.remove_tokens(&removed);
+                Ok(matches)
+            }
+            Err(err) => {
+                self.try_to_help()?;
+                let kind = match &err {
+                    GetoptError::InvalidOption(_) | GetoptError::UnrecognizedOption(_) => {
+                        ErrorKind::UnexpectedArg
+                    }
+                    GetoptError::AmbiguousOption(_, _) => ErrorKind::SuggestArg,
+                    GetoptError::MissingArgument(_) => ErrorKind::ExpectingValue,
+                    GetoptError::UnexpectedArgument(_) => ErrorKind::UnexpectedValue,
+                };
+                Err(Error::new(
+                    self.help.clone(),
+                    kind,
+                    ErrorContext::Getopt(err.to_string()),
+                    self.options.cap_mode,
+                ))
+            }
+        }
+    }
 }
 
 // Private API
@@ -970,27 +2907,43 @@ impl Cli<Memory> {
     /// not move forward in the token stream.
     fn get_positional<'a, T: FromStr>(&mut self, p: Positional) -> Result<Option<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
-        self.known_args.push(ArgType::Positional(p));
-        self.try_positional()
+        self.proceed(MemoryState::ProcessingPositionals)?;
+        self.learn(ArgType::Positional(p));
+        match self.try_positional()? {
+            Some(value) => Ok(Some(value)),
+            None => self.get_default(),
+        }
     }
 
     fn get_positional_all<'a, T: FromStr>(&mut self, p: Positional) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
-        let mut result = Vec::<T>::new();
-        match self.get_positional(p)? {
-            Some(item) => result.push(item),
-            None => return Ok(None),
+        match self.get_positional_all_indexed(p)? {
+            Some(values) => Ok(Some(values.into_iter().map(|(_, v)| v).collect())),
+            None => Ok(self.get_default()?.map(|v| vec![v])),
         }
-        while let Some(v) = self.try_positional()? {
+    }
+
+    fn get_positional_all_indexed<'a, T: FromStr>(
+        &mut self,
+        p: Positional,
+    ) -> Result<Option<Vec<(usize, T)>>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        self.proceed(MemoryState::ProcessingPositionals)?;
+        self.learn(ArgType::Positional(p));
+        let mut result = Vec::<(usize, T)>::new();
+        while let Some(v) = self.try_positional_indexed()? {
             result.push(v);
         }
-        Ok(Some(result))
+        match result.is_empty() {
+            true => Ok(None),
+            false => Ok(Some(result)),
+        }
     }
 
     fn get_positional_until<'a, T: FromStr>(
@@ -999,9 +2952,9 @@ impl Cli<Memory> {
         limit: usize,
     ) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
+        self.proceed(MemoryState::ProcessingPositionals)?;
         let values = self.get_positional_all::<T>(p)?;
         match values {
             // verify the size of the vector does not exceed `n`
@@ -1024,9 +2977,9 @@ impl Cli<Memory> {
         span: R,
     ) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
+        self.proceed(MemoryState::ProcessingPositionals)?;
         let values = self.get_positional_all::<T>(p)?;
         match values {
             // verify the size of the vector does not exceed `n`
@@ -1053,14 +3006,26 @@ impl Cli<Memory> {
     /// Errors if parsing fails or if no unattached argument is left in the token stream.
     fn require_positional<'a, T: FromStr>(&mut self, p: Positional) -> Result<T>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
+        self.proceed(MemoryState::ProcessingPositionals)?;
         if let Some(value) = self.get_positional(p)? {
             Ok(value)
         } else {
             self.try_to_help()?;
-            self.empty()?;
+            // an unhandled flag may have been the value intended for this positional
+            if let Some((_, _, j)) = self.capture_bad_flag(self.tokens.len())? {
+                let word = self.raw_arg_at(j).unwrap();
+                let span = self.span_at(j);
+                return Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::MissingPositional,
+                    ErrorContext::FlagLikeValue(self.known_args.pop().unwrap(), word),
+                    self.options.cap_mode,
+                )
+                .with_span(span));
+            }
+            self.verify_empty()?;
             Err(Error::new(
                 self.help.clone(),
                 ErrorKind::MissingPositional,
@@ -1077,9 +3042,9 @@ impl Cli<Memory> {
     /// The resulting vector is guaranteed to have `.len() >= 1`.
     fn require_positional_all<'a, T: FromStr>(&mut self, p: Positional) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
+        self.proceed(MemoryState::ProcessingPositionals)?;
         let mut result = Vec::<T>::new();
         result.push(self.require_positional(p)?);
         while let Some(v) = self.try_positional()? {
@@ -1094,9 +3059,9 @@ impl Cli<Memory> {
         limit: usize,
     ) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
+        self.proceed(MemoryState::ProcessingPositionals)?;
         let values = self.require_positional_all(p)?;
         // verify the size of the vector does not exceed `n`
         match values.len() <= limit {
@@ -1120,9 +3085,9 @@ impl Cli<Memory> {
         span: R,
     ) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingPositionals);
+        self.proceed(MemoryState::ProcessingPositionals)?;
         let values = self.require_positional_all::<T>(p)?;
         match span.contains(&values.len()) {
             true => Ok(values),
@@ -1140,40 +3105,86 @@ impl Cli<Memory> {
         }
     }
 
-    /// Queries for a value of `Optional`.
+    /// Serves the `Positional` value at the positional `index` parsed as `T`.
+    ///
+    /// Errors if parsing fails.
+    fn get_positional_at<'a, T: FromStr>(
+        &mut self,
+        p: Positional,
+        index: usize,
+    ) -> Result<Option<T>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        self.proceed(MemoryState::ProcessingPositionals)?;
+        self.learn(ArgType::Positional(p));
+        let word = self.uarg_at(index);
+        self.cast_positional(word)
+    }
+
+    /// Forces the [Positional] at the positional `index` to exist from the token stream.
+    ///
+    /// Errors if parsing fails or if no unattached argument exists at `index`.
+    fn require_positional_at<'a, T: FromStr>(&mut self, p: Positional, index: usize) -> Result<T>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        self.proceed(MemoryState::ProcessingPositionals)?;
+        if let Some(value) = self.get_positional_at(p, index)? {
+            Ok(value)
+        } else {
+            self.try_to_help()?;
+            Err(Error::new(
+                self.help.clone(),
+                ErrorKind::MissingPositional,
+                ErrorContext::FailedArg(self.known_args.pop().unwrap()),
+                self.options.cap_mode,
+            ))
+        }
+    }
+
+    /// Queries for a value of `Optional`.
     ///
     /// Errors if there are multiple values or if parsing fails.
     fn get_option<'a, T: FromStr>(&mut self, o: Optional) -> Result<Option<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
-        // collect information on where the flag can be found
-        let mut locs = self.take_flag_locs(o.get_flag().get_name());
-        if let Some(c) = o.get_flag().get_switch() {
-            locs.extend(self.take_switch_locs(c));
-        }
-        self.known_args.push(ArgType::Optional(o));
+        self.proceed(MemoryState::ProcessingOptionals)?;
+        // collect information on where the flag can be found (including aliases and switch)
+        let locs = self.take_locs(o.get_flag());
+        let locs = self.scope_locs(&o, locs);
+        self.restrict(o.get_flag(), &locs);
+        self.learn(ArgType::Optional(o));
+        self.verify_stable(&locs)?;
+        let grouped = self.find_grouped_value(&locs);
+        // remember any flag-like arguments that may have been intended as values
+        let mut hints = self.flag_like_values(&locs);
         // pull values from where the option flags were found (including switch)
-        let mut values = self.pull_flag(locs, true);
+        let mut values = self.pull_option(locs);
+        self.verify_grouped_value(grouped)?;
         match values.len() {
             1 => {
                 if let Some(word) = values.pop().unwrap() {
+                    let word = self.prepare_value(word)?;
+                    self.verify_value(&word)?;
                     let result = word.parse::<T>();
                     match result {
                         Ok(r) => Ok(Some(r)),
                         Err(err) => {
                             self.try_to_help()?;
+                            let span = self.span_of_value(&word);
                             Err(Error::new(
                                 self.help.clone(),
                                 ErrorKind::BadType,
                                 ErrorContext::FailedCast(
                                     self.known_args.pop().unwrap(),
                                     word,
-                                    Box::new(err),
+                                    err.to_string(),
                                 ),
                                 self.options.cap_mode,
-                            ))
+                            )
+                            .with_span(span))
                         }
                     }
                 } else {
@@ -1181,12 +3192,12 @@ impl Cli<Memory> {
                     Err(Error::new(
                         self.help.clone(),
                         ErrorKind::ExpectingValue,
-                        ErrorContext::FailedArg(self.known_args.pop().unwrap()),
+                        self.missing_value(hints.pop().unwrap()),
                         self.options.cap_mode,
                     ))
                 }
             }
-            0 => Ok(None),
+            0 => self.get_default(),
             _ => {
                 self.try_to_help()?;
                 Err(Error::new(
@@ -1204,39 +3215,66 @@ impl Cli<Memory> {
     /// Errors if a parsing fails from string.
     fn get_option_all<'a, T: FromStr>(&mut self, o: Optional) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
-        // collect information on where the flag can be found
-        let mut locs = self.take_flag_locs(o.get_flag().get_name());
-        if let Some(c) = o.get_flag().get_switch() {
-            locs.extend(self.take_switch_locs(c));
+        match self.get_option_all_indexed(o)? {
+            Some(values) => Ok(Some(values.into_iter().map(|(_, v)| v).collect())),
+            None => Ok(self.get_default()?.map(|v| vec![v])),
         }
-        self.known_args.push(ArgType::Optional(o));
+    }
+
+    /// Queries for all values behind an `Optional` along with the command-line
+    /// positions they were written at.
+    ///
+    /// Errors if a parsing fails from string.
+    fn get_option_all_indexed<'a, T: FromStr>(
+        &mut self,
+        o: Optional,
+    ) -> Result<Option<Vec<(usize, T)>>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        self.proceed(MemoryState::ProcessingOptionals)?;
+        // collect information on where the flag can be found (including aliases and switch)
+        let locs = self.take_locs(o.get_flag());
+        let locs = self.scope_locs(&o, locs);
+        self.restrict(o.get_flag(), &locs);
+        self.learn(ArgType::Optional(o));
+        self.verify_stable(&locs)?;
+        let grouped = self.find_grouped_value(&locs);
+        // remember any flag-like arguments that may have been intended as values
+        let hints = self.flag_like_values(&locs);
+        // find where each value is written before it is removed from the token stream
+        let indices: Vec<usize> = locs.iter().map(|i| self.value_index(*i)).collect();
         // pull values from where the option flags were found (including switch)
-        let values = self.pull_flag(locs, true);
+        let values = self.pull_option(locs);
+        self.verify_grouped_value(grouped)?;
         if values.is_empty() == true {
             return Ok(None);
         }
         // try to convert each value into the type T
-        let mut transform = Vec::<T>::with_capacity(values.len());
-        for val in values {
+        let mut transform = Vec::<(usize, T)>::with_capacity(values.len());
+        for ((val, hint), index) in values.into_iter().zip(hints).zip(indices) {
             if let Some(word) = val {
+                let word = self.prepare_value(word)?;
+                self.verify_value(&word)?;
                 let result = word.parse::<T>();
                 match result {
-                    Ok(r) => transform.push(r),
+                    Ok(r) => transform.push((index, r)),
                     Err(err) => {
                         self.try_to_help()?;
+                        let span = self.span_of_value(&word);
                         return Err(Error::new(
                             self.help.clone(),
                             ErrorKind::BadType,
                             ErrorContext::FailedCast(
                                 self.known_args.pop().unwrap(),
                                 word,
-                                Box::new(err),
+                                err.to_string(),
                             ),
                             self.options.cap_mode,
-                        ));
+                        )
+                        .with_span(span));
                     }
                 }
             } else {
@@ -1244,7 +3282,7 @@ impl Cli<Memory> {
                 return Err(Error::new(
                     self.help.clone(),
                     ErrorKind::ExpectingValue,
-                    ErrorContext::FailedArg(self.known_args.pop().unwrap()),
+                    self.missing_value(hint),
                     self.options.cap_mode,
                 ));
             }
@@ -1261,9 +3299,9 @@ impl Cli<Memory> {
         limit: usize,
     ) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
+        self.proceed(MemoryState::ProcessingOptionals)?;
         let values = self.get_option_all::<T>(o)?;
         match values {
             // verify the size of the vector does not exceed `n`
@@ -1286,9 +3324,9 @@ impl Cli<Memory> {
         span: R,
     ) -> Result<Option<Vec<T>>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
+        self.proceed(MemoryState::ProcessingOptionals)?;
         let values = self.get_option_all::<T>(o)?;
         match values {
             // verify the size of the vector does not exceed `n`
@@ -1313,14 +3351,14 @@ impl Cli<Memory> {
     /// Queries for an expected value of `Optional`.
     fn require_option<'a, T: FromStr>(&mut self, o: Optional) -> Result<T>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
+        self.proceed(MemoryState::ProcessingOptionals)?;
         if let Some(value) = self.get_option(o)? {
             Ok(value)
         } else {
             self.try_to_help()?;
-            self.empty()?;
+            self.verify_empty()?;
             Err(Error::new(
                 self.help.clone(),
                 ErrorKind::MissingOption,
@@ -1332,14 +3370,14 @@ impl Cli<Memory> {
 
     fn require_option_all<'a, T: FromStr>(&mut self, o: Optional) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
+        self.proceed(MemoryState::ProcessingOptionals)?;
         if let Some(value) = self.get_option_all(o)? {
             Ok(value)
         } else {
             self.try_to_help()?;
-            self.empty()?;
+            self.verify_empty()?;
             Err(Error::new(
                 self.help.clone(),
                 ErrorKind::MissingOption,
@@ -1351,9 +3389,9 @@ impl Cli<Memory> {
 
     fn require_option_until<'a, T: FromStr>(&mut self, o: Optional, limit: usize) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
+        self.proceed(MemoryState::ProcessingOptionals)?;
         let values = self.require_option_all(o)?;
         // verify the size of the vector does not exceed `n`
         match values.len() <= limit {
@@ -1377,9 +3415,9 @@ impl Cli<Memory> {
         span: R,
     ) -> Result<Vec<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
     {
-        self.state.proceed(MemoryState::ProcessingOptionals);
+        self.proceed(MemoryState::ProcessingOptionals)?;
         let values = self.require_option_all::<T>(o)?;
         match span.contains(&values.len()) {
             true => Ok(values),
@@ -1401,7 +3439,7 @@ impl Cli<Memory> {
     ///
     /// Errors if the flag has an attached value or was raised multiple times.
     fn check_flag<'a>(&mut self, f: Flag) -> Result<bool> {
-        self.state.proceed(MemoryState::ProcessingFlags);
+        self.proceed(MemoryState::ProcessingFlags)?;
         let occurences = self.check_flag_all(f)?;
         match occurences > 1 {
             true => {
@@ -1418,28 +3456,43 @@ impl Cli<Memory> {
         }
     }
 
+    /// Queries for the state of a flag by the symbol of its last instance.
+    fn toggle_flag(&mut self, f: Flag) -> Result<Option<bool>> {
+        self.proceed(MemoryState::ProcessingFlags)?;
+        let last = self
+            .take_locs(&f)
+            .into_iter()
+            .filter_map(|j| self.tokens[j].as_ref())
+            .filter_map(|t| t.get_index())
+            .max();
+        let state = last.map(|i| self.is_positive(&self.args[i]));
+        self.check_flag_all(f)?;
+        Ok(state)
+    }
+
     /// Queries for the number of times a flag was raised.
     ///
     /// Errors if the flag has an attached value. Returning a zero indicates the flag was never raised.
     fn check_flag_all<'a>(&mut self, f: Flag) -> Result<usize> {
-        self.state.proceed(MemoryState::ProcessingFlags);
-        // collect information on where the flag can be found
-        let mut locs = self.take_flag_locs(f.get_name());
-        // try to find the switch locations
-        if let Some(c) = f.get_switch() {
-            locs.extend(self.take_switch_locs(c));
-        };
-        self.known_args.push(ArgType::Flag(f));
+        self.proceed(MemoryState::ProcessingFlags)?;
+        // collect information on where the flag can be found (including aliases and switch)
+        let locs = self.take_locs(&f);
+        self.restrict(&f, &locs);
+        self.learn(ArgType::Flag(f));
+        self.verify_stable(&locs)?;
         let mut occurences = self.pull_flag(locs, false);
         // verify there are no values attached to this flag
         if let Some(val) = occurences.iter_mut().find(|p| p.is_some()) {
             self.try_to_help()?;
+            let word = val.take().unwrap();
+            let span = self.span_of_value(&word);
             return Err(Error::new(
                 self.help.clone(),
                 ErrorKind::UnexpectedValue,
-                ErrorContext::UnexpectedValue(self.known_args.pop().unwrap(), val.take().unwrap()),
+                ErrorContext::UnexpectedValue(self.known_args.pop().unwrap(), word),
                 self.options.cap_mode,
-            ));
+            )
+            .with_span(span));
         } else {
             let raised = occurences.len() != 0;
             // check if the user is asking for help by raising the help flag
@@ -1457,6 +3510,24 @@ impl Cli<Memory> {
                     self.asking_for_help = true;
                 }
             }
+            // stop interpreting to display the text of an eager flag
+            if raised == true {
+                let text = self
+                    .known_args
+                    .last()
+                    .unwrap()
+                    .as_flag()
+                    .unwrap()
+                    .get_eager();
+                if let Some(text) = text.map(|t| t.to_string()) {
+                    self.known_args.pop();
+                    // an eager flag raised earlier on the command-line is displayed first
+                    if self.eager.is_none() == true {
+                        self.eager = Some(text);
+                    }
+                    self.try_to_help()?;
+                }
+            }
             // return the number of times the flag was raised
             Ok(occurences.len())
         }
@@ -1466,7 +3537,7 @@ impl Cli<Memory> {
     ///
     /// Errors if the flag has an attached value. Returning a zero indicates the flag was never raised.
     fn check_flag_until<'a>(&mut self, f: Flag, limit: usize) -> Result<usize> {
-        self.state.proceed(MemoryState::ProcessingFlags);
+        self.proceed(MemoryState::ProcessingFlags)?;
         let occurences = self.check_flag_all(f)?;
         // verify the size of the vector does not exceed `n`
         match occurences <= limit {
@@ -1481,7 +3552,7 @@ impl Cli<Memory> {
     }
 
     fn check_flag_between<'a, R: RangeBounds<usize>>(&mut self, f: Flag, span: R) -> Result<usize> {
-        self.state.proceed(MemoryState::ProcessingFlags);
+        self.proceed(MemoryState::ProcessingFlags)?;
         let occurences = self.check_flag_all(f)?;
         // verify the size of the vector does not exceed `n`
         match span.contains(&occurences) {
@@ -1501,6 +3572,296 @@ impl Cli<Memory> {
     }
 }
 
+// Crate API
+
+impl Cli<Memory> {
+    /// Runs the remaining steps in the command-line processor for `T`, see [go][Cli::go].
+    #[cfg(feature = "runner")]
+    pub(crate) fn run<T: Command>(mut self) -> ExitCode {
+        let program = self.construct::<T>();
+        let stash = std::mem::take(&mut self.stash);
+        self.conclude(program, |p| proc::stash::provide(stash, || p.execute()))
+    }
+
+    /// Runs the remaining steps in the command-line processor for `T` with its
+    /// `context`, see [go_with][Cli::go_with].
+    #[cfg(feature = "runner")]
+    pub(crate) fn run_with<T: CommandFrom<C>, C>(mut self, context: C) -> ExitCode {
+        let program = self.construct_with::<T, C>(&context);
+        let stash = std::mem::take(&mut self.stash);
+        self.conclude(program, |p| {
+            proc::stash::provide(stash, || p.execute(&context))
+        })
+    }
+
+    /// Executes the constructed `program`, or reports the error that occurred
+    /// while constructing it, and returns the exit code.
+    #[cfg(feature = "runner")]
+    fn conclude<P, F>(self, program: Result<P>, execute: F) -> ExitCode
+    where
+        F: FnOnce(P) -> crate::proc::Result,
+    {
+        // write the candidates for the last word instead of running the command
+        if self.completion.is_some() == true {
+            self.completions().unwrap().iter().for_each(proc::write_out);
+            return ExitCode::from(Exit::Success);
+        }
+        match program {
+            // construct the application
+            Ok(program) => {
+                let cli_opts = self.options.clone();
+                let verbosity = self.verbosity;
+                std::mem::drop(self);
+                match execute(program) {
+                    Ok(_) => ExitCode::from(Exit::Success),
+                    // exit with the requested code and report only its message
+                    Err(err) if err.is::<Halt>() == true => {
+                        let halt = err.downcast_ref::<Halt>().unwrap();
+                        if let Some(msg) = halt.message() {
+                            proc::write_err(cli_opts.format_report(msg.to_string(), String::new()));
+                        }
+                        ExitCode::from(halt.exit())
+                    }
+                    // the output was closed early by its reader (ex: `head`)
+                    Err(err) if proc::is_broken_pipe(err.as_ref()) == true => {
+                        ExitCode::from(Exit::Success)
+                    }
+                    Err(err) => {
+                        // errors are always written, while verbosity only adds the chain of causes
+                        let chain =
+                            match cli_opts.err_chain == true || verbosity >= Verbosity::Debug {
+                                true => utils::format_chain(err.as_ref()),
+                                false => String::new(),
+                            };
+                        proc::write_err(cli_opts.format_report(err.to_string(), chain));
+                        ExitCode::from(Exit::RuntimeError)
+                    }
+                }
+            }
+            // report cli error
+            Err(err) => self.report(err),
+        }
+    }
+
+    /// Interprets and validates `T` as the top-level command, see [build][Cli::build].
+    pub(crate) fn construct<T: Command>(&mut self) -> Result<T> {
+        let program = T::interpret(self);
+        self.verify(program, T::validate)
+    }
+
+    /// Interprets and validates `T` as the top-level command with its `context`,
+    /// see [build_with][Cli::build_with].
+    pub(crate) fn construct_with<T: CommandFrom<C>, C>(&mut self, context: &C) -> Result<T> {
+        let program = T::interpret(self, context);
+        self.verify(program, T::validate)
+    }
+
+    /// Verifies the interpreted top-level `program` consumed the entire command-line
+    /// and is semantically valid.
+    fn verify<P, F>(&mut self, program: Result<P>, validate: F) -> Result<P>
+    where
+        F: FnOnce(P) -> Result<P>,
+    {
+        program
+            .map_err(|e| {
+                e.attach(
+                    self.help.clone(),
+                    self.options.cap_mode,
+                    self.options.switch,
+                )
+            })
+            // verify the cli has no additional arguments if this is the top-level command being parsed
+            .and_then(|program| self.empty().map(|_| program))
+            // verify the command is semantically valid
+            .and_then(|program| {
+                validate(program).map_err(|e| {
+                    e.attach(
+                        self.help.clone(),
+                        self.options.cap_mode,
+                        self.options.switch,
+                    )
+                })
+            })
+    }
+
+    /// Reports the command-line error `err` and returns its exit code.
+    ///
+    /// Help is written to `stdout` while all other errors are written to `stderr`.
+    #[cfg(feature = "runner")]
+    pub(crate) fn report(self, err: Error) -> ExitCode {
+        let cli_opts = self.options;
+        match err.kind() {
+            // help is wrapped at the width of the terminal
+            ErrorKind::Help => {
+                let style = match proc::columns() {
+                    Some(width) => ErrorStyle::new().wrap(width),
+                    None => ErrorStyle::new(),
+                };
+                proc::write_out(err.render(&style))
+            }
+            ErrorKind::Eager => proc::write_out(&err),
+            _ => {
+                // style the message for this processor rather than from global state
+                let mut style = ErrorStyle::new().color(cli_opts.color_mode.is_enabled());
+                if let (true, Some(program)) = (cli_opts.err_pointer, &self.program) {
+                    let line: Vec<&str> = std::iter::once(program.as_str())
+                        .chain(self.args.iter().map(|a| a.as_str()))
+                        .collect();
+                    style = style.point_to(&line);
+                }
+                // the message already includes the custom error's description
+                let chain = match cli_opts.err_chain == true || self.verbosity >= Verbosity::Debug {
+                    true => std::error::Error::source(&err)
+                        .map(|e| utils::format_chain(e))
+                        .unwrap_or_default(),
+                    false => String::new(),
+                };
+                proc::write_err(cli_opts.format_report(err.render(&style), chain))
+            }
+        }
+        ExitCode::from(err.code())
+    }
+
+    /// References the file name of the first argument supplied during parsing,
+    /// regardless of any name set by [bin_name][Cli::bin_name].
+    #[cfg(feature = "runner")]
+    pub(crate) fn invoked_name(&self) -> Option<&str> {
+        self.program.as_deref()
+    }
+
+    /// Checks for the hidden flag `--list-commands` and stops interpretation to
+    /// display the paths of the subcommand `nodes` if it was raised.
+    ///
+    /// The paths are displayed one per line, or as a JSON array along with their
+    /// summaries when raised as `--list-commands=json`.
+    pub(crate) fn list_commands(&mut self, nodes: &[Node]) -> Result<()> {
+        let flag = Flag::new("list-commands");
+        if self.is_flag_present(&flag) == false {
+            return Ok(());
+        }
+        let locs = self.take_locs(&flag);
+        let format = self.pull_flag(locs, false).into_iter().flatten().next();
+        self.try_to_help()?;
+        let text = match format.as_deref() {
+            None => nodes
+                .iter()
+                .map(|n| n.path().join(" "))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            Some("json") => format!(
+                "[{}]",
+                nodes
+                    .iter()
+                    .map(|n| format!(
+                        "{{\"path\":{},\"summary\":{}}}",
+                        utils::to_json_str(&n.path().join(" ")),
+                        n.summary()
+                            .map(utils::to_json_str)
+                            .unwrap_or(String::from("null"))
+                    ))
+                    .collect::<Vec<String>>()
+                    .join(",")
+            ),
+            Some(other) => {
+                return Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::UnexpectedValue,
+                    ErrorContext::UnexpectedValue(ArgType::Flag(flag), other.to_string()),
+                    self.options.cap_mode,
+                ))
+            }
+        };
+        Err(Error::new(
+            self.help.clone(),
+            ErrorKind::Eager,
+            ErrorContext::Eager(text),
+            self.options.cap_mode,
+        ))
+    }
+
+    /// Replaces the failed selection `err` with a suggestion from the `nested`
+    /// subcommand paths when one is closer to the entered word than any
+    /// suggestion at the current level.
+    ///
+    /// A path is compared by joining its names with hyphens in both orders, so
+    /// "install-deps" and "deps-install" both match "deps install".
+    pub(crate) fn suggest_nested(&self, err: Error, nested: &[String]) -> Error {
+        let (word, cost) = match err.context() {
+            ErrorContext::SuggestWord(word, suggestions) => (
+                word.clone(),
+                seqalin::min_edit_str(word, suggestions).unwrap().1,
+            ),
+            ErrorContext::UnknownSubcommand(_, word, _, _) => match self.options.threshold_auto {
+                true => (word.clone(), seqalin::scaled_threshold(word)),
+                false => (word.clone(), self.options.threshold),
+            },
+            _ => return err,
+        };
+        let best = nested
+            .iter()
+            .filter_map(|path| {
+                let names: Vec<&str> = path.split_whitespace().collect();
+                let forms = [
+                    names.join("-"),
+                    names.iter().rev().cloned().collect::<Vec<&str>>().join("-"),
+                ];
+                seqalin::min_edit_str(&word, &forms).map(|(_, c)| (path, c))
+            })
+            .min_by(|x, y| x.1.cmp(&y.1));
+        match best {
+            Some((path, c)) if c < cost => Error::new(
+                self.help.clone(),
+                ErrorKind::SuggestSubcommand,
+                ErrorContext::SuggestWord(
+                    word,
+                    vec![self
+                        .path
+                        .iter()
+                        .chain([path])
+                        .cloned()
+                        .collect::<Vec<String>>()
+                        .join(" ")],
+                ),
+                self.options.cap_mode,
+            ),
+            _ => err,
+        }
+    }
+
+    /// Tries to match the next positional argument for `subcommand` against an
+    /// array of strings in `bank`, see [select][Cli::select].
+    ///
+    /// Errors if there is not a next positional argument.
+    #[cfg(feature = "runner")]
+    pub(crate) fn select_required<T: AsRef<str> + std::cmp::PartialEq>(
+        &mut self,
+        subcommand: Arg<Callable>,
+        bank: &[T],
+    ) -> Result<String> {
+        self.complete_subcommand();
+        self.known_args.push(ArgType::from(subcommand));
+        let command_exists = self
+            .tokens
+            .iter()
+            .find(|f| match f {
+                Some(Token::UnattachedArgument(_, _)) | Some(Token::Inserted(_)) => true,
+                _ => false,
+            })
+            .is_some();
+        if command_exists == false {
+            self.try_to_help()?;
+            self.verify_empty()?;
+            return Err(Error::new(
+                self.help.clone(),
+                ErrorKind::MissingPositional,
+                ErrorContext::FailedArg(self.known_args.pop().unwrap()),
+                self.options.cap_mode,
+            ));
+        }
+        self.select(bank)
+    }
+}
+
 // Internal methods
 
 impl Cli<Memory> {
@@ -1509,44 +3870,252 @@ impl Cli<Memory> {
     /// Assumes the [Positional] argument is already added as the last element to the `known_args` vector.
     fn try_positional<'a, T: FromStr>(&mut self) -> Result<Option<T>>
     where
-        <T as FromStr>::Err: 'static + std::error::Error,
+        <T as FromStr>::Err: std::error::Error,
+    {
+        Ok(self.try_positional_indexed()?.map(|(_, v)| v))
+    }
+
+    /// Takes the next positional value along with the command-line position it
+    /// was written at.
+    fn try_positional_indexed<'a, T: FromStr>(&mut self) -> Result<Option<(usize, T)>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let j = match self.options.unordered == true || self.options.intermixed == true {
+            true => self.find_unreserved_uarg(),
+            false => self.find_uarg(),
+        };
+        let index = j.and_then(|j| self.arg_index(j));
+        let word = j.map(|j| {
+            self.consumed.push(j);
+            self.take_value(j)
+        });
+        Ok(index.zip(self.cast_positional(word)?))
+    }
+
+    /// Parses the unattached argument `word` taken for a positional.
+    ///
+    /// Assumes the [Positional] argument is already added as the last element to the `known_args` vector.
+    fn cast_positional<'a, T: FromStr>(&mut self, word: Option<String>) -> Result<Option<T>>
+    where
+        <T as FromStr>::Err: std::error::Error,
     {
-        match self.next_uarg() {
+        let word = word.map(|w| self.prepare_value(w)).transpose()?;
+        if let Some(w) = &word {
+            self.verify_value(w)?;
+        }
+        match word {
             Some(word) => match word.parse::<T>() {
                 Ok(r) => Ok(Some(r)),
                 Err(err) => {
                     self.try_to_help()?;
                     self.prioritize_suggestion()?;
+                    let span = self.span_of_value(&word);
                     Err(Error::new(
                         self.help.clone(),
                         ErrorKind::BadType,
                         ErrorContext::FailedCast(
                             self.known_args.pop().unwrap(),
                             word,
-                            Box::new(err),
+                            err.to_string(),
                         ),
                         self.options.cap_mode,
-                    ))
+                    )
+                    .with_span(span))
                 }
             },
             None => Ok(None),
         }
     }
 
+    /// Prepares the raw `word` for the last known argument by reading the file it
+    /// references and resolving it as a path, if the argument allows either.
+    ///
+    /// Assumes the argument is already added as the last element to the `known_args` vector.
+    fn prepare_value(&mut self, word: String) -> Result<String> {
+        let word = self.read_file_ref(word)?;
+        self.resolve_path(word)
+    }
+
+    /// Resolves the raw `word` as a path relative to the base directory of the last
+    /// known argument, if it has one.
+    ///
+    /// Assumes the argument is already added as the last element to the `known_args` vector.
+    fn resolve_path(&mut self, word: String) -> Result<String> {
+        let (base, resolution) = match self.known_args.last().and_then(|a| a.as_value()) {
+            Some(v) => match v.get_base() {
+                Some(base) => (base.to_path_buf(), v.get_resolution()),
+                None => return Ok(word),
+            },
+            None => return Ok(word),
+        };
+        match resolution.resolve(&word, base) {
+            Ok(path) => Ok(path.to_string_lossy().into_owned()),
+            Err(err) => {
+                self.try_to_help()?;
+                Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::UnresolvedPath,
+                    ErrorContext::UnresolvedPath(self.known_args.pop().unwrap(), word.clone(), err),
+                    self.options.cap_mode,
+                )
+                .with_span(self.span_of_value(&word)))
+            }
+        }
+    }
+
+    /// Replaces the raw `word` with the contents of the file it references when it
+    /// begins with `@` and the last known argument allows file references.
+    ///
+    /// Assumes the argument is already added as the last element to the `known_args` vector.
+    fn read_file_ref(&mut self, word: String) -> Result<String> {
+        let allowed = self
+            .known_args
+            .last()
+            .and_then(|a| a.as_value())
+            .is_some_and(|v| v.allows_file_ref());
+        let path = match word.strip_prefix(symbol::FILE_REF) {
+            Some(path) if allowed == true => path,
+            _ => return Ok(word),
+        };
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Ok(contents),
+            Err(err) => {
+                self.try_to_help()?;
+                Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::UnreadableFile,
+                    ErrorContext::UnreadableFile(
+                        self.known_args.pop().unwrap(),
+                        path.to_string(),
+                        err,
+                    ),
+                    self.options.cap_mode,
+                )
+                .with_span(self.span_of_value(&word)))
+            }
+        }
+    }
+
+    /// Verifies the raw `word` satisfies the constraints declared on the value of
+    /// the last known argument before it is parsed.
+    ///
+    /// Assumes the argument is already added as the last element to the `known_args` vector.
+    fn verify_value(&mut self, word: &str) -> Result<()> {
+        #[cfg(feature = "regex")]
+        if let Some(pattern) = self
+            .known_args
+            .last()
+            .and_then(|a| a.as_value())
+            .and_then(|v| v.get_pattern())
+        {
+            if pattern.is_match(word) == false {
+                let pattern = pattern.as_str().to_string();
+                self.try_to_help()?;
+                return Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::MismatchedPattern,
+                    ErrorContext::MismatchedPattern(
+                        self.known_args.pop().unwrap(),
+                        word.to_string(),
+                        pattern,
+                    ),
+                    self.options.cap_mode,
+                )
+                .with_span(self.span_of_value(word)));
+            }
+        }
+        #[cfg(feature = "url")]
+        if let Some(schemes) = self
+            .known_args
+            .last()
+            .and_then(|a| a.as_value())
+            .and_then(|v| v.get_schemes())
+        {
+            if let Ok(url) = url::Url::parse(word) {
+                if schemes.iter().any(|s| s == url.scheme()) == false {
+                    let schemes = schemes.clone();
+                    self.try_to_help()?;
+                    return Err(Error::new(
+                        self.help.clone(),
+                        ErrorKind::DisallowedScheme,
+                        ErrorContext::DisallowedScheme(
+                            self.known_args.pop().unwrap(),
+                            url.scheme().to_string(),
+                            schemes,
+                        ),
+                        self.options.cap_mode,
+                    )
+                    .with_span(self.span_of_value(word)));
+                }
+            }
+        }
+        let in_range = self
+            .known_args
+            .last()
+            .and_then(|a| a.as_value())
+            .and_then(|v| v.in_range(word));
+        if let Some(Err(err)) = in_range {
+            self.try_to_help()?;
+            return Err(Error::new(
+                self.help.clone(),
+                ErrorKind::BadType,
+                ErrorContext::FailedCast(self.known_args.pop().unwrap(), word.to_string(), err),
+                self.options.cap_mode,
+            )
+            .with_span(self.span_of_value(word)));
+        }
+        if let Some(Ok(false)) = in_range {
+            let (start, end) = self
+                .known_args
+                .last()
+                .and_then(|a| a.as_value())
+                .and_then(|v| v.get_range())
+                .cloned()
+                .unwrap();
+            self.try_to_help()?;
+            return Err(Error::new(
+                self.help.clone(),
+                ErrorKind::OutsideRange,
+                ErrorContext::ValueOutsideRange(
+                    self.known_args.pop().unwrap(),
+                    word.to_string(),
+                    start,
+                    end,
+                ),
+                self.options.cap_mode,
+            )
+            .with_span(self.span_of_value(word)));
+        }
+        Ok(())
+    }
+
     /// Transforms the list of `known_args` into a list of the names for every available
     /// flag.
     ///
+    /// The names (including aliases) are listed in the order they were discovered without duplicates,
+    /// such that ties in spelling suggestions are resolved by the first discovered flag.
+    ///
     /// This method is useful for acquiring a word bank to offer a flag spelling suggestion.
-    fn known_args_as_flag_names(&self) -> HashSet<&str> {
-        // note: collect into a `std::collections::HashSet` to avoid dupe
+    fn known_args_as_flag_names(&self) -> Vec<&str> {
+        let mut bank = Vec::<&str>::with_capacity(self.known_args.len());
         self.known_args
             .iter()
             .filter_map(|f| match f {
-                ArgType::Flag(f) => Some(f.get_name()),
-                ArgType::Optional(o) => Some(o.get_flag().get_name()),
+                ArgType::Flag(f) => Some(f),
+                ArgType::Optional(o) => Some(o.get_flag()),
                 _ => None,
             })
-            .collect()
+            .filter(|f| f.is_switch_only() == false)
+            .flat_map(|f| {
+                std::iter::once(f.get_name()).chain(f.get_aliases().iter().map(|a| a.as_str()))
+            })
+            .for_each(|name| {
+                if bank.contains(&name) == false {
+                    bank.push(name)
+                }
+            });
+        bank
     }
 
     /// Returns the first index where a flag/switch still remains in the token stream.
@@ -1554,11 +4123,28 @@ impl Cli<Memory> {
     /// The flag must occur in the token stream before the `breakpoint` index. If
     /// the `opt_store` hashmap is empty, it will return none.
     fn find_first_flag_left(&self, breakpoint: usize) -> Option<(&str, usize)> {
+        let found = self.search_first_flag_left(self.store.iter(), breakpoint);
+        if self.options.deterministic == true {
+            let reversed = self.search_first_flag_left(self.store_reversed(), breakpoint);
+            if found != reversed {
+                panic!(
+                    "first flag left depends on the search order: {:?} and {:?}",
+                    found, reversed
+                );
+            }
+        }
+        found
+    }
+
+    /// Searches the lookup table's `entries` for the first index where a flag/switch
+    /// still remains before the `breakpoint` index, see [find_first_flag_left][Cli::find_first_flag_left].
+    fn search_first_flag_left<'b>(
+        &self,
+        entries: impl Iterator<Item = (&'b Tag<String>, &'b Slot)>,
+        breakpoint: usize,
+    ) -> Option<(&'b str, usize)> {
         let mut min_i: Option<(&str, usize)> = None;
-        let mut opt_it = self
-            .store
-            .iter()
-            .filter(|(_, slot)| slot.is_visited() == false);
+        let mut opt_it = entries.filter(|(_, slot)| slot.is_visited() == false);
         while let Some((key, val)) = opt_it.next() {
             // check if this flag's index comes before the currently known minimum index
             min_i = if *val.first().unwrap() < breakpoint
@@ -1576,29 +4162,34 @@ impl Cli<Memory> {
     fn capture_bad_flag<'a>(&self, i: usize) -> Result<Option<(&str, &str, usize)>> {
         if let Some((key, val)) = self.find_first_flag_left(i) {
             self.try_to_help()?;
-            // check what type of token it was to determine if it was called with '-' or '--'
+            // check what type of token it was to determine the symbols it was called with
             if let Some(t) = self.tokens.get(val).unwrap() {
+                let raw = self.args[t.get_index().unwrap()].split('=').next().unwrap();
                 let prefix = match t {
-                    Token::Switch(_, _) | Token::EmptySwitch(_) => symbol::SWITCH,
+                    Token::Switch(_, _) | Token::EmptySwitch(_) => {
+                        &raw[..raw.chars().next().unwrap().len_utf8()]
+                    }
                     Token::Flag(_) => {
+                        let prefix = &raw[..raw.len() - key.len()];
                         // try to match it with a valid flag from word bank
-                        let bank: Vec<&str> = self.known_args_as_flag_names().into_iter().collect();
-                        if let Some(closest) = if self.options.threshold > 0 {
-                            seqalin::sel_min_edit_str(key, &bank, self.options.threshold)
-                        } else {
-                            None
-                        } {
+                        let bank: Vec<&str> = self.known_args_as_flag_names();
+                        let closest = self.closest_words(key, &bank);
+                        if closest.is_empty() == false {
                             return Err(Error::new(
                                 self.help.clone(),
                                 ErrorKind::SuggestArg,
                                 ErrorContext::SuggestWord(
-                                    format!("{}{}", symbol::FLAG, key),
-                                    format!("{}{}", symbol::FLAG, closest),
+                                    format!("{}{}", prefix, key),
+                                    closest
+                                        .into_iter()
+                                        .map(|w| format!("{}{}", prefix, w))
+                                        .collect(),
                                 ),
                                 self.options.cap_mode,
-                            ));
+                            )
+                            .with_span(self.span_at(val)));
                         }
-                        symbol::FLAG
+                        prefix
                     }
                     _ => panic!("no other tokens are allowed in hashmap"),
                 };
@@ -1611,58 +4202,400 @@ impl Cli<Memory> {
         }
     }
 
-    /// Returns all locations in the token stream where the flag identifier `tag` is found.
+    /// Returns all locations in the token stream where the flag `f` is found by
+    /// its name, any of its aliases, or its switch.
+    /// Keeps the `locs` of the option `o` that are still in the token stream,
+    /// such as the ones left by a parent command.
     ///
-    /// Information about Option<Vec<T>> vs. empty Vec<T>: https://users.rust-lang.org/t/space-time-usage-to-construct-vec-t-vs-option-vec-t/35596/6
-    fn take_flag_locs(&mut self, tag: &str) -> Vec<usize> {
-        if let Some(slot) = self.store.get_mut(&Tag::Flag(tag.to_owned())) {
-            slot.visit();
-            slot.get_indices().to_vec()
-        } else {
-            Vec::new()
-        }
+    /// If the option only accepts values before the subcommand, the locations
+    /// after the subcommand are left out as well.
+    fn scope_locs(&self, o: &Optional, locs: Vec<usize>) -> Vec<usize> {
+        let end = match o.is_leading() {
+            true => self.find_subcommand_loc(),
+            false => self.tokens.len(),
+        };
+        locs.into_iter()
+            .filter(|j| *j < end && self.tokens.get(*j).is_some_and(|t| t.is_some()))
+            .collect()
     }
 
-    /// Returns all locations in the token stream where the switch identifier `c` is found.
-    fn take_switch_locs(&mut self, c: &char) -> Vec<usize> {
-        // allocate &str to the stack and not the heap to get from store
-        let mut arr = [0; 4];
-        let tag = c.encode_utf8(&mut arr);
+    /// Finds the location in the token stream of the next argument that can be a
+    /// subcommand's name.
+    ///
+    /// This is the first unattached argument that does not immediately follow a
+    /// flag or switch, since it could otherwise be the value of an option. If
+    /// there is no such argument, the end of the token stream is returned.
+    fn find_subcommand_loc(&self) -> usize {
+        let mut prev: Option<&Token> = None;
+        for (j, t) in self.tokens.iter().enumerate() {
+            let t = match t {
+                Some(t) => t,
+                None => continue,
+            };
+            match (t, prev) {
+                (Token::Terminator(_), _) => return j,
+                (
+                    Token::UnattachedArgument(_, _),
+                    Some(Token::Flag(_)) | Some(Token::Switch(_, _)),
+                ) => (),
+                (Token::UnattachedArgument(_, _), _) => return j,
+                _ => (),
+            }
+            prev = Some(t);
+        }
+        self.tokens.len()
+    }
 
-        if let Some(slot) = self.store.get_mut(&Tag::Switch(tag.to_owned())) {
-            slot.visit();
-            slot.get_indices().to_vec()
-        } else {
-            Vec::new()
+    /// Remembers the flag `f` when it is found at any `locs` and is only valid with
+    /// certain subcommands, to be verified once the subcommand is chosen.
+    fn restrict(&mut self, f: &Flag, locs: &[usize]) -> () {
+        if locs.is_empty() == false && f.get_subcommands().is_empty() == false {
+            self.restricted
+                .push((f.to_string(), f.get_subcommands().clone()));
         }
     }
 
-    /// Iterates through the list of tokens to find the first suggestion against a flag to return.
+    /// Verifies the arguments found by the current command are valid with the
+    /// chosen subcommand `command`, if one exists.
     ///
-    /// Returns ok if cannot make a suggestion.
-    fn prioritize_suggestion(&self) -> Result<()> {
-        let mut kv: Vec<(&String, &Vec<usize>)> = self
-            .store
-            .iter()
-            .map(|(tag, slot)| (tag.as_ref(), slot.get_indices()))
-            .collect::<Vec<(&String, &Vec<usize>)>>();
-        kv.sort_by(|a, b| a.1.first().unwrap().cmp(b.1.first().unwrap()));
-        let bank: Vec<&str> = self.known_args_as_flag_names().into_iter().collect();
-        let r = kv
+    /// Errors on the first argument that is not valid with the subcommand.
+    fn verify_restricted(&mut self, command: Option<&str>) -> Result<()> {
+        let invalid = self.restricted.drain(..).find(|(_, subs)| match command {
+            Some(c) => subs.iter().any(|s| s == c) == false,
+            None => true,
+        });
+        match invalid {
+            Some((arg, subs)) => {
+                self.try_to_help()?;
+                Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::RestrictedArg,
+                    ErrorContext::RestrictedArg(arg, subs),
+                    self.options.cap_mode,
+                ))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Verifies the most recently learned argument, if it was found at any `locs`,
+    /// is not experimental or experimental arguments are enabled.
+    fn verify_stable(&mut self, locs: &[usize]) -> Result<()> {
+        let var = match self.known_args.last() {
+            Some(ArgType::Flag(f)) => f.get_unstable(),
+            Some(ArgType::Optional(o)) => o.get_flag().get_unstable(),
+            _ => None,
+        };
+        let var = match var {
+            Some(v) if locs.is_empty() == false => v.to_string(),
+            _ => return Ok(()),
+        };
+        if self.unstable == Some(true) || std::env::var_os(&var).is_some() == true {
+            return Ok(());
+        }
+        self.try_to_help()?;
+        Err(Error::new(
+            self.help.clone(),
+            ErrorKind::UnstableArg,
+            ErrorContext::UnstableArg(
+                self.known_args.pop().unwrap(),
+                var,
+                self.unstable.map(|_| String::from("--enable-unstable")),
+            ),
+            self.options.cap_mode,
+        ))
+    }
+
+    fn take_locs(&mut self, f: &Flag) -> Vec<usize> {
+        let mut locs = match f.is_switch_only() {
+            true => Vec::new(),
+            false => self.take_flag_locs(f.get_name()),
+        };
+        f.get_aliases()
+            .iter()
+            .for_each(|a| locs.extend(self.take_flag_locs(a)));
+        if let Some(c) = f.get_switch() {
+            locs.extend(self.take_switch_locs(c));
+        }
+        // keep the command-line order when the names and switch are interleaved
+        locs.sort();
+        locs
+    }
+
+    /// Returns the position on the command-line of the argument that the token at
+    /// position `j` was written in, where the program name is at position 0.
+    ///
+    /// Assumes the token has not been taken from the token stream. Inserted tokens
+    /// were never written on the command-line, so they have no position.
+    fn arg_index(&self, j: usize) -> Option<usize> {
+        self.tokens
+            .get(j)
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .get_index()
+            .map(|i| i + 1)
+    }
+
+    /// Returns the position on the command-line of the value belonging to the
+    /// most recently learned option's flag at token position `j`, which is the
+    /// flag's own argument unless the value is written in the following argument.
+    fn value_index(&self, j: usize) -> usize {
+        let o = self.learned_option();
+        let index = match self.tokens.get(j + 1) {
+            Some(Some(Token::AttachedArgument(_, _))) => self.arg_index(j + 1),
+            Some(Some(Token::UnattachedArgument(_, _))) if o.is_attached_only() == false => {
+                self.arg_index(j + 1)
+            }
+            _ if o.is_attached_only() == false
+                && o.allows_hyphen_values() == true
+                && self.is_flag_like_value(j) == true =>
+            {
+                self.arg_index(j + 1)
+            }
+            _ => self.arg_index(j),
+        };
+        index.expect("an option's flag must be written on the command-line")
+    }
+
+    /// Finds the first switch at the flag `locations` that ends a group of switches
+    /// with an attached value (ex: `-vl=direct`), returning the raw argument and
+    /// its location on the command-line.
+    ///
+    /// Nothing is found if grouped values are bound to the last switch. This
+    /// function must be called before the flags at `locations` are pulled from the
+    /// token stream.
+    fn find_grouped_value(&self, locations: &[usize]) -> Option<(String, Option<Span>)> {
+        if self.options.bind_grouped == true {
+            return None;
+        }
+        let j = locations.iter().copied().find(|j| {
+            match (self.tokens.get(*j), self.tokens.get(*j + 1)) {
+                (Some(Some(Token::Switch(i, _))), Some(Some(Token::AttachedArgument(_, _)))) => {
+                    // the switch symbol is followed by more than one switch
+                    self.args[*i]
+                        .split_once('=')
+                        .is_some_and(|(switches, _)| switches.chars().count() > 2)
+                }
+                _ => false,
+            }
+        })?;
+        Some((self.raw_arg_at(j)?, self.span_at(j)))
+    }
+
+    /// Verifies the most recently learned option did not take a value attached to
+    /// a group of switches, as found by [find_grouped_value][Cli::find_grouped_value].
+    fn verify_grouped_value(&mut self, grouped: Option<(String, Option<Span>)>) -> Result<()> {
+        match grouped {
+            Some((word, span)) => {
+                self.try_to_help()?;
+                Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::GroupedValue,
+                    ErrorContext::GroupedValue(self.known_args.pop().unwrap(), word),
+                    self.options.cap_mode,
+                )
+                .with_span(span))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// References the option that was most recently added to the known arguments.
+    fn learned_option(&self) -> &Optional {
+        self.known_args
+            .last()
+            .and_then(|a| a.as_option())
+            .expect("an option must be learned before its values are pulled")
+    }
+
+    /// Checks if the flag at token position `j` is followed by a separate argument
+    /// that looks like a flag (ex: `--filter --name-only`).
+    fn is_flag_like_value(&self, j: usize) -> bool {
+        let origin = self
+            .tokens
+            .get(j)
+            .and_then(|t| t.as_ref())
+            .and_then(|t| t.get_index());
+        match self.tokens.get(j + 1) {
+            Some(Some(t @ (Token::Flag(_) | Token::Switch(_, _) | Token::EmptySwitch(_)))) => {
+                t.get_index() != origin
+            }
+            _ => false,
+        }
+    }
+
+    /// Takes the entire command-line argument that produced the token at position
+    /// `j`, removing all of its tokens from the token stream.
+    fn take_arg_at(&mut self, j: usize) -> String {
+        let i = self
+            .tokens
+            .get(j)
+            .unwrap()
+            .as_ref()
+            .unwrap()
+            .get_index()
+            .unwrap();
+        let word = self.raw_arg_at(j).unwrap();
+        let removed: Vec<usize> = (j..self.tokens.len())
+            .take_while(|k| {
+                self.tokens
+                    .get(*k)
+                    .unwrap()
+                    .as_ref()
+                    .is_some_and(|t| t.get_index() == Some(i))
+            })
+            .collect();
+        self.taken.push(Span::new(i + 1, 0, word.len()));
+        self.remove_tokens(&removed);
+        word
+    }
+
+    /// Parses the default value of the last known argument, if one applies.
+    ///
+    /// The first conditional default whose flag is present on the command-line
+    /// is chosen before the unconditional default.
+    ///
+    /// Assumes the argument is already added as the last element to the `known_args` vector.
+    fn get_default<'a, T: FromStr>(&mut self) -> Result<Option<T>>
+    where
+        <T as FromStr>::Err: std::error::Error,
+    {
+        let word = match self.known_args.last().and_then(|a| a.as_value()) {
+            Some(v) => v
+                .get_defaults_if()
+                .iter()
+                .find(|(f, _)| self.is_flag_present(f) == true)
+                .map(|(_, w)| w.as_str())
+                .or(v.get_default())
+                .map(String::from),
+            None => None,
+        };
+        self.cast_positional(word)
+    }
+
+    /// Lists the tags in the lookup table that the flag `f` can be found under,
+    /// which are its names and its switch.
+    fn tags_of(f: &Flag) -> Vec<Tag<String>> {
+        let mut tags: Vec<Tag<String>> = f
+            .get_aliases()
             .iter()
+            .map(|a| Tag::Flag(a.to_owned()))
+            .collect();
+        if f.is_switch_only() == false {
+            tags.push(Tag::Flag(f.get_name().to_owned()));
+        }
+        if let Some(c) = f.get_switch() {
+            tags.push(Tag::Switch(c.to_string()));
+        }
+        tags
+    }
+
+    /// Checks if the flag `f` was supplied on the command-line by any of its
+    /// names or its switch, regardless of whether it has been requested.
+    fn is_flag_present(&self, f: &Flag) -> bool {
+        Self::tags_of(f).iter().any(|t| {
+            self.store
+                .get(t)
+                .is_some_and(|slot| slot.get_indices().is_empty() == false)
+        })
+    }
+
+    /// Returns all locations in the token stream where the flag identifier `tag` is found.
+    ///
+    /// Information about Option<Vec<T>> vs. empty Vec<T>: https://users.rust-lang.org/t/space-time-usage-to-construct-vec-t-vs-option-vec-t/35596/6
+    fn take_flag_locs(&mut self, tag: &str) -> Vec<usize> {
+        if let Some(slot) = self.store.get_mut(&Tag::Flag(tag) as &dyn TagKey) {
+            slot.visit();
+            slot.get_indices().to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Returns all locations in the token stream where the switch identifier `c` is found.
+    fn take_switch_locs(&mut self, c: &char) -> Vec<usize> {
+        // allocate &str to the stack and not the heap to get from store
+        let mut arr = [0; 4];
+        let tag = c.encode_utf8(&mut arr);
+
+        if let Some(slot) = self.store.get_mut(&Tag::Switch(&*tag) as &dyn TagKey) {
+            slot.visit();
+            slot.get_indices().to_vec()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Ranks the words in `bank` closest to `word` under the configured cost
+    /// threshold for string alignment, keeping at most [MAX_SUGGESTIONS].
+    ///
+    /// Returns an empty list when the threshold is 0 and is not scaled.
+    fn closest_words<'b, T: AsRef<str>>(&self, word: &str, bank: &'b [T]) -> Vec<&'b str> {
+        let mut words = if self.options.threshold_auto == true {
+            seqalin::rank_edit_strs_scaled(word, bank)
+        // bypass sequence alignment algorithm if threshold == 0
+        } else if self.options.threshold > 0 {
+            seqalin::rank_edit_strs(word, bank, self.options.threshold)
+        } else {
+            Vec::new()
+        };
+        words.truncate(MAX_SUGGESTIONS);
+        words
+    }
+
+    /// Iterates through the list of tokens to find the first suggestion against a flag to return.
+    ///
+    /// Returns ok if cannot make a suggestion.
+    fn prioritize_suggestion(&self) -> Result<()> {
+        let r = self.search_suggestion(self.store.iter());
+        if self.options.deterministic == true {
+            let reversed = self.search_suggestion(self.store_reversed());
+            let (found, other) = (
+                r.as_ref().map(|e| e.to_string()),
+                reversed.map(|e| e.to_string()),
+            );
+            if found != other {
+                panic!(
+                    "flag suggestion depends on the search order: {:?} and {:?}",
+                    found, other
+                );
+            }
+        }
+        if self.asking_for_help == true {
+            Ok(())
+        } else if let Some(e) = r {
+            Err(e)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Searches the lookup table's `entries` for the first suggestion against a
+    /// flag, see [prioritize_suggestion][Cli::prioritize_suggestion].
+    fn search_suggestion<'b>(
+        &self,
+        entries: impl Iterator<Item = (&'b Tag<String>, &'b Slot)>,
+    ) -> Option<Error> {
+        let mut kv: Vec<(&String, &[usize])> = entries
+            .map(|(tag, slot)| (tag.as_ref(), slot.get_indices()))
+            .collect::<Vec<(&String, &[usize])>>();
+        kv.sort_by(|a, b| a.1.first().unwrap().cmp(b.1.first().unwrap()));
+        let bank: Vec<&str> = self.known_args_as_flag_names();
+        kv.iter()
             .find_map(|f| match self.tokens.get(*f.1.first().unwrap()).unwrap() {
                 Some(Token::Flag(_)) => {
-                    if let Some(word) = if self.options.threshold > 0 {
-                        seqalin::sel_min_edit_str(f.0, &bank, self.options.threshold)
-                    } else {
-                        None
-                    } {
+                    let words = self.closest_words(f.0, &bank);
+                    if words.is_empty() == false {
                         Some(Error::new(
                             self.help.clone(),
                             ErrorKind::SuggestArg,
                             ErrorContext::SuggestWord(
-                                format!("{}{}", symbol::FLAG, f.0),
-                                format!("{}{}", symbol::FLAG, word),
+                                format!("{}{}", self.flag_prefix(), f.0),
+                                words
+                                    .into_iter()
+                                    .map(|w| format!("{}{}", self.flag_prefix(), w))
+                                    .collect(),
                             ),
                             self.options.cap_mode,
                         ))
@@ -1671,14 +4604,13 @@ impl Cli<Memory> {
                     }
                 }
                 _ => None,
-            });
-        if self.asking_for_help == true {
-            Ok(())
-        } else if let Some(e) = r {
-            Err(e)
-        } else {
-            Ok(())
-        }
+            })
+    }
+
+    /// Iterates through the entries of the lookup table in the reverse of its
+    /// iteration order, see [verify_determinism][Cli::verify_determinism].
+    fn store_reversed(&self) -> impl Iterator<Item = (&Tag<String>, &Slot)> {
+        self.store.iter().collect::<Vec<_>>().into_iter().rev()
     }
 
     /// Grabs the flag/switch from the token stream, and collects.
@@ -1692,899 +4624,3975 @@ impl Cli<Memory> {
                 // remove the flag instance from the token stream
                 self.tokens.get_mut(*i).unwrap().take();
                 // check the next position for a value
-                if let Some(t_next) = self.tokens.get_mut(*i + 1) {
-                    match t_next {
-                        Some(Token::AttachedArgument(_, _)) => {
-                            Some(t_next.take().unwrap().take_str())
-                        }
-                        Some(Token::UnattachedArgument(_, _)) => {
-                            // do not take unattached arguments unless told by parameter
-                            match with_uarg {
-                                true => Some(t_next.take().unwrap().take_str()),
-                                false => None,
-                            }
+                match self.tokens.get(*i + 1) {
+                    Some(Some(Token::AttachedArgument(_, _))) => Some(self.take_value(*i + 1)),
+                    Some(Some(Token::UnattachedArgument(_, _))) => {
+                        // do not take unattached arguments unless told by parameter
+                        match with_uarg {
+                            true => Some(self.take_value(*i + 1)),
+                            false => None,
                         }
-                        _ => None,
                     }
-                } else {
-                    None
+                    _ => None,
                 }
             })
             .collect()
     }
 
-    /// Pulls the next `UnattachedArg` token from the token stream.
+    /// Pulls the values of the most recently learned option from each of its flag
+    /// `locations`, see [pull_flag][Cli::pull_flag].
     ///
-    /// If no more `UnattachedArg` tokens are left, it will return none.
-    fn next_uarg(&mut self) -> Option<String> {
-        if let Some(p) = self.tokens.iter_mut().find(|s| match s {
-            Some(Token::UnattachedArgument(_, _)) | Some(Token::Terminator(_)) => true,
+    /// An instance of the option that is supplied without a value takes the
+    /// option's missing value, if one exists.
+    fn pull_option(&mut self, locations: Vec<usize>) -> Vec<Option<String>> {
+        let o = self.learned_option();
+        let with_uarg = o.is_attached_only() == false;
+        let hyphens = with_uarg == true && o.allows_hyphen_values() == true;
+        let missing = o.get_missing().map(String::from);
+        // take the arguments that look like flags as values before the flags are pulled
+        let hyphenated: Vec<Option<String>> = locations
+            .iter()
+            .map(
+                |j| match hyphens == true && self.is_flag_like_value(*j) == true {
+                    true => Some(self.take_arg_at(*j + 1)),
+                    false => None,
+                },
+            )
+            .collect();
+        let mut values: Vec<Option<String>> = self
+            .pull_flag(locations, with_uarg)
+            .into_iter()
+            .zip(hyphenated)
+            .map(|(v, h)| h.or(v))
+            .collect();
+        if let Some(m) = missing {
+            values
+                .iter_mut()
+                .filter(|v| v.is_none())
+                .for_each(|v| *v = Some(m.clone()));
+        }
+        values
+    }
+
+    /// Pulls the text written behind each argument that begins with the switch `c`
+    /// (ex: `-Dkey=value`), or the next unattached argument if nothing is written
+    /// behind the switch (ex: `-D key=value`).
+    fn pull_prefix(&mut self, c: char) -> Vec<Option<String>> {
+        let prefix = format!("{}{}", self.options.switch, c);
+        let mut words = Vec::new();
+        let mut removed = Vec::new();
+        let mut prev: Option<usize> = None;
+        for (j, tkn) in self.tokens.iter().enumerate() {
+            let i = match tkn.as_ref().and_then(|t| t.get_index()) {
+                Some(i) => i,
+                None => continue,
+            };
+            // the remaining tokens of the argument make up its key and value
+            if prev == Some(i) {
+                removed.push(j);
+                continue;
+            }
+            match tkn {
+                Some(Token::Switch(_, d)) if *d == c && self.args[i].starts_with(&prefix) => (),
+                _ => continue,
+            }
+            prev = Some(i);
+            removed.push(j);
+            let text = &self.args[i][prefix.len()..];
+            words.push(match text.is_empty() {
+                true => match self.tokens.get(j + 1) {
+                    Some(Some(Token::UnattachedArgument(_, _))) => {
+                        removed.push(j + 1);
+                        if let Some(span) = self.span_at(j + 1) {
+                            self.taken.push(span);
+                        }
+                        self.raw_arg_at(j + 1)
+                    }
+                    _ => None,
+                },
+                false => Some(text.to_string()),
+            });
+        }
+        self.remove_tokens(&removed);
+        words
+    }
+
+    /// Adds `arg` to the list of known arguments.
+    ///
+    /// When the last word on the command-line is being completed, the argument
+    /// offers its flag names as candidates, or resolves the candidates for its
+    /// value if the word is expected to be the argument's value.
+    fn learn(&mut self, mut arg: ArgType) -> () {
+        arg.set_symbol(self.options.switch);
+        let names = arg
+            .as_flag()
+            .map(|f| self.flag_names(f))
+            .unwrap_or_default();
+        let uarg_exists = self.tokens.iter().any(|t| match t {
+            Some(Token::UnattachedArgument(_, _)) | Some(Token::Inserted(_)) => true,
             _ => false,
-        }) {
-            if let Some(Token::Terminator(_)) = p {
-                None
-            } else {
-                Some(p.take().unwrap().take_str())
+        });
+        if let Some(c) = &mut self.completion {
+            match &arg {
+                // the value follows the option's flag as the previous word
+                ArgType::Optional(o)
+                    if c.is_flag() == false
+                        && self.args.last().is_some_and(|w| names.contains(w)) == true =>
+                {
+                    c.resolve(o.get_positional().complete(c.current()))
+                }
+                ArgType::Positional(p) if c.is_flag() == false && uarg_exists == false => {
+                    c.resolve(p.complete(c.current()))
+                }
+                _ if c.is_flag() == true => c.offer(names),
+                _ => (),
             }
-        } else {
-            None
         }
+        self.known_args.push(arg);
     }
 
-    /// Checks if help is enabled and is some value.
-    fn is_help_enabled(&self) -> bool {
-        // change to does_help_exist()
-        self.help.is_some()
+    /// Lists the ways the flag `f` can be written on the command-line.
+    fn flag_names(&self, f: &Flag) -> Vec<String> {
+        let mut names = Vec::<String>::new();
+        if f.is_switch_only() == false {
+            names.push(format!("{}{}", self.flag_prefix(), f.get_name()));
+            f.get_aliases()
+                .iter()
+                .for_each(|a| names.push(format!("{}{}", self.flag_prefix(), a)));
+        }
+        if let Some(c) = f.get_switch() {
+            names.push(format!("{}{}", self.options.switch, c));
+        }
+        names
     }
 
-    /// Checks if help has been raised and will return its own error for displaying
-    /// help.
-    fn try_to_help(&self) -> Result<()> {
-        if self.options.prioritize_help == true
-            && self.asking_for_help == true
-            && self.is_help_enabled() == true
-        {
-            Err(Error::new(
-                self.help.clone(),
-                ErrorKind::Help,
-                ErrorContext::Help,
-                self.options.cap_mode,
-            ))
-        } else {
-            Ok(())
+    /// Serves the last word on the command-line as the subcommand to be selected
+    /// when it is being completed and no other subcommand was supplied.
+    fn complete_subcommand(&mut self) -> () {
+        let uarg_exists = self.tokens.iter().any(|t| match t {
+            Some(Token::UnattachedArgument(_, _)) | Some(Token::Inserted(_)) => true,
+            _ => false,
+        });
+        if let Some(c) = &mut self.completion {
+            if c.is_open() == true && c.is_flag() == false && uarg_exists == false {
+                c.select();
+                let word = c.current().to_string();
+                self.insert_uarg(word);
+            }
         }
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::error::ErrorKind;
+    /// Takes every remaining argument to be forwarded to an external process.
+    ///
+    /// Each argument is written as it was supplied on the command-line, except
+    /// combined switches only keep the switches that were never requested. If help
+    /// was detected on the command-line, the help flag is forwarded first instead
+    /// of displaying help.
+    pub(crate) fn forward(&mut self) -> Vec<String> {
+        let mut words = Vec::<String>::new();
+        if self.asking_for_help == true {
+            if let Some(help) = &self.help {
+                words.push(ArgType::from(help.get_arg()).to_string());
+            }
+            self.asking_for_help = false;
+        }
+        let mut prev: Option<(usize, bool)> = None;
+        for tkn in self.tokens.iter_mut() {
+            let tkn = match tkn.take() {
+                Some(t) => t,
+                None => continue,
+            };
+            // inserted words were never written on the command-line
+            let i = match tkn.get_index() {
+                Some(i) => i,
+                None => continue,
+            };
+            let joined = prev.is_some_and(|(p, _)| p == i);
+            let is_switch = match tkn {
+                Token::Switch(_, c) if joined == true => {
+                    words.last_mut().unwrap().push(c);
+                    true
+                }
+                Token::Switch(_, c) => {
+                    words.push(format!("{}{}", self.options.switch, c));
+                    true
+                }
+                Token::EmptySwitch(_) => {
+                    words.push(self.options.switch.to_string());
+                    true
+                }
+                // a value attached to a flag is already a part of its raw argument
+                Token::AttachedArgument(_, val) if joined == true => {
+                    if prev.is_some_and(|(_, s)| s == true) {
+                        words.last_mut().unwrap().push_str(&format!("={}", val));
+                    }
+                    false
+                }
+                _ => {
+                    words.push(self.args[i].clone());
+                    false
+                }
+            };
+            prev = Some((i, is_switch));
+        }
+        self.store.clear();
+        words
+    }
 
-    /// Helper test fn to write vec of &str as iterator for Cli parameter.
-    fn args<'a>(args: Vec<&'a str>) -> Box<dyn Iterator<Item = String> + 'a> {
-        Box::new(args.into_iter().map(|f| f.to_string()).into_iter())
+    /// Removes the tokens at each position in `locations` from the token stream.
+    ///
+    /// The removed flags and switches are also forgotten from the lookup table so
+    /// they can no longer be requested.
+    fn remove_tokens(&mut self, locations: &[usize]) -> () {
+        locations.iter().for_each(|j| {
+            self.tokens.get_mut(*j).unwrap().take();
+        });
+        self.store.retain(|_, slot| {
+            slot.pointers.retain(|j| locations.contains(j) == false);
+            slot.get_indices().is_empty() == false
+        });
     }
 
-    #[test]
-    fn get_all_optionals() {
-        // option provided multiple times
-        let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "plan",
-                "--fileset",
-                "a",
-                "--fileset=b",
-                "--fileset",
-                "c",
-            ]))
-            .save();
-        let sets: Vec<String> = cli
-            .get_option_all(Optional::new("fileset"))
-            .unwrap()
-            .unwrap();
-        assert_eq!(sets, vec!["a", "b", "c"]);
-        // failing case- bad conversion of 'c' to an integer
-        let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "plan",
-                "--digit",
-                "10",
-                "--digit=9",
-                "--digit",
-                "c",
-            ]))
-            .save();
-        assert_eq!(
-            cli.get_option_all::<i32>(Optional::new("digit")).is_err(),
-            true
-        ); // bad conversion
-           // option provided as valid integers
-        let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "plan",
-                "--digit",
-                "10",
+    /// Returns the raw command-line argument that produced the token at position `j`.
+    ///
+    /// Nothing is returned for an inserted token, since it was never written on
+    /// the command-line.
+    fn raw_arg_at(&self, j: usize) -> Option<String> {
+        match self.tokens.get(j).unwrap() {
+            Some(t) => t.get_index().map(|i| self.args.get(i).unwrap().clone()),
+            None => panic!("this token's values have been removed"),
+        }
+    }
+
+    /// Checks if the flag or switch at token position `j` has not been requested yet.
+    fn is_unhandled(&self, j: usize) -> bool {
+        self.store
+            .values()
+            .any(|slot| slot.is_visited() == false && slot.get_indices().contains(&j))
+    }
+
+    /// Returns the raw command-line argument following each flag location in `locations`
+    /// when it is an unhandled flag or switch that may have been intended as a value.
+    ///
+    /// For an option that requires its value to be attached with `=`, a following
+    /// unattached argument may also have been intended as its value.
+    ///
+    /// This function must be called before the flags at `locations` are pulled from the token stream.
+    fn flag_like_values(&self, locations: &[usize]) -> Vec<Option<String>> {
+        let equals = self
+            .known_args
+            .last()
+            .and_then(|a| a.as_option())
+            .is_some_and(|o| o.requires_equals());
+        locations
+            .iter()
+            .map(|i| {
+                let origin = self.tokens.get(*i)?.as_ref().and_then(|t| t.get_index());
+                match self.tokens.get(*i + 1) {
+                    // the value must be a separate argument from the flag itself
+                    Some(Some(
+                        t @ (Token::Flag(_) | Token::Switch(_, _) | Token::EmptySwitch(_)),
+                    )) if t.get_index() != origin && self.is_unhandled(*i + 1) => {
+                        self.raw_arg_at(*i + 1)
+                    }
+                    Some(Some(Token::UnattachedArgument(_, _))) if equals == true => {
+                        self.raw_arg_at(*i + 1)
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    /// Creates the error context for the most recently learned option that was
+    /// supplied without a value, where `hint` is the argument that may have been
+    /// intended as its value.
+    fn missing_value(&mut self, hint: Option<String>) -> ErrorContext {
+        let arg = self.known_args.pop().unwrap();
+        let equals = arg.as_option().is_some_and(|o| o.requires_equals());
+        match (hint, equals) {
+            (Some(word), true) => ErrorContext::DetachedValue(arg, word),
+            (Some(word), false) => ErrorContext::FlagLikeValue(arg, word),
+            (None, _) => ErrorContext::FailedArg(arg),
+        }
+    }
+
+    /// Inserts an `Inserted` token for `word` at the beginning of the current
+    /// scope in the token stream.
+    ///
+    /// The inserted token does not originate from the command-line, so it has no
+    /// argument index or location to report.
+    fn insert_uarg(&mut self, word: String) -> () {
+        let j = self.scope;
+        // shift all recorded token positions at or after the insertion point
+        self.store.values_mut().for_each(|slot| {
+            slot.pointers
+                .as_mut_slice()
+                .iter_mut()
+                .filter(|p| **p >= j)
+                .for_each(|p| *p += 1)
+        });
+        self.consumed
+            .iter_mut()
+            .filter(|p| **p >= j)
+            .for_each(|p| *p += 1);
+        self.tokens.insert(j, Some(Token::Inserted(word)));
+    }
+
+    /// Locates the token at position `j` on the command-line.
+    ///
+    /// Attached values are located after the `=` of the argument they are attached to.
+    fn span_at(&self, j: usize) -> Option<Span> {
+        let token = self.tokens.get(j)?.as_ref()?;
+        let i = token.get_index()?;
+        let arg = self.args.get(i)?;
+        let start = match token {
+            Token::AttachedArgument(_, s) => arg.len().checked_sub(s.len())?,
+            _ => 0,
+        };
+        match token {
+            // the word may differ from the argument it was written in
+            Token::UnattachedArgument(_, s) | Token::AttachedArgument(_, s)
+                if arg.get(start..) != Some(s.as_str()) =>
+            {
+                None
+            }
+            _ => Some(Span::new(i + 1, start, arg.len())),
+        }
+    }
+
+    /// Locates the most recently taken value `word` on the command-line.
+    fn span_of_value(&self, word: &str) -> Option<Span> {
+        self.taken
+            .iter()
+            .rev()
+            .find(|s| {
+                self.args
+                    .get(s.index() - 1)
+                    .and_then(|a| a.get(s.start()..s.end()))
+                    == Some(word)
+            })
+            .copied()
+    }
+
+    /// Takes the value at token position `j` from the token stream, remembering
+    /// where it was located on the command-line.
+    fn take_value(&mut self, j: usize) -> String {
+        if let Some(span) = self.span_at(j) {
+            self.taken.push(span);
+        }
+        self.tokens.get_mut(j).unwrap().take().unwrap().take_str()
+    }
+
+    /// Pulls the next `UnattachedArg` token from the token stream, preferring a
+    /// word that was inserted as the default subcommand.
+    ///
+    /// If no more `UnattachedArg` tokens are left, it will return none.
+    fn next_uarg(&mut self) -> Option<String> {
+        let j = match self.tokens.iter().position(|t| match t {
+            Some(Token::Inserted(_)) => true,
+            _ => false,
+        }) {
+            Some(j) => j,
+            None => self.find_uarg()?,
+        };
+        self.consumed.push(j);
+        Some(self.take_value(j))
+    }
+
+    /// Finds the token position of the next `UnattachedArg` token in the token stream.
+    fn find_uarg(&self) -> Option<usize> {
+        match self.tokens.iter().enumerate().find(|(_, s)| match s {
+            Some(Token::UnattachedArgument(_, _)) | Some(Token::Terminator(_)) => true,
+            _ => false,
+        }) {
+            Some((_, Some(Token::Terminator(_)))) | None => None,
+            Some((j, _)) => Some(j),
+        }
+    }
+
+    /// Finds the token position of the next `UnattachedArg` token that is not
+    /// reserved as the value of a flag that has not been requested yet.
+    fn find_unreserved_uarg(&self) -> Option<usize> {
+        self.tokens
+            .iter()
+            .enumerate()
+            .take_while(|(_, t)| match t {
+                Some(Token::Terminator(_)) => false,
+                _ => true,
+            })
+            .find(|(j, t)| match t {
+                Some(Token::UnattachedArgument(_, _)) => self.is_reserved(*j) == false,
+                _ => false,
+            })
+            .map(|(j, _)| j)
+    }
+
+    /// Checks if the token at position `j` directly follows a flag or switch
+    /// that has not been requested yet and is not a declared flag.
+    fn is_reserved(&self, j: usize) -> bool {
+        match j.checked_sub(1).and_then(|i| self.tokens.get(i)) {
+            Some(Some(Token::Flag(_))) | Some(Some(Token::Switch(_, _))) => {
+                self.is_unhandled(j - 1) == true && self.is_declared_flag_at(j - 1) == false
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks if the token at position `j` is an instance of a flag that was
+    /// declared to be part of the interface.
+    fn is_declared_flag_at(&self, j: usize) -> bool {
+        self.declared
+            .iter()
+            .filter_map(|d| d.as_flag())
+            .flat_map(Self::tags_of)
+            .any(|t| {
+                self.store
+                    .get(&t)
+                    .is_some_and(|slot| slot.get_indices().contains(&j))
+            })
+    }
+
+    /// Pulls the `UnattachedArg` token at the positional `index` within the current
+    /// scope from the token stream.
+    ///
+    /// Positionals that were already taken still count towards the `index`. If
+    /// no `UnattachedArg` token is left at the `index`, it will return none.
+    fn uarg_at(&mut self, index: usize) -> Option<String> {
+        let j = self
+            .tokens
+            .iter()
+            .enumerate()
+            .skip(self.scope)
+            .take_while(|(_, t)| match t {
+                Some(Token::Terminator(_)) => false,
+                _ => true,
+            })
+            .filter(|(j, t)| match t {
+                Some(Token::UnattachedArgument(_, _)) => true,
+                _ => self.consumed.contains(j),
+            })
+            .nth(index)?
+            .0;
+        match self.tokens.get(j).unwrap() {
+            Some(Token::UnattachedArgument(_, _)) => {
+                self.consumed.push(j);
+                Some(self.take_value(j))
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if help is enabled and is some value.
+    fn is_help_enabled(&self) -> bool {
+        // change to does_help_exist()
+        self.help.is_some()
+    }
+
+    /// Takes the output format attached to the help flag (ex: `--help=man`), if
+    /// one exists, to display the help in that format.
+    ///
+    /// Errors if the format is not a [HelpFormat].
+    fn select_help_format(&mut self) -> Result<()> {
+        let f = ArgType::from(self.help.as_ref().unwrap().get_arg())
+            .into_flag()
+            .unwrap();
+        for j in self.take_locs(&f) {
+            if let Some(Some(Token::AttachedArgument(_, _))) = self.tokens.get(j + 1) {
+                let word = self.take_value(j + 1);
+                match word.parse::<HelpFormat>() {
+                    Ok(format) => {
+                        let title = self.path.join("-");
+                        self.help.as_mut().unwrap().select(format, title);
+                    }
+                    Err(err) => {
+                        let span = self.span_of_value(&word);
+                        let mut arg =
+                            ArgType::Optional(Optional::new(f.get_name()).value("format"));
+                        arg.set_symbol(self.options.switch);
+                        return Err(Error::new(
+                            self.help.clone(),
+                            ErrorKind::BadType,
+                            ErrorContext::FailedCast(arg, word, err.to_string()),
+                            self.options.cap_mode,
+                        )
+                        .with_span(span));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that there are no more unprocessed arguments, without displaying
+    /// help that waits for the command-line to be valid.
+    fn verify_empty(&mut self) -> Result<()> {
+        self.proceed(MemoryState::End)?;
+        self.try_to_help()?;
+        if self.options.strict == true && cfg!(debug_assertions) == true {
+            if let Some(arg) = self
+                .declared
+                .iter()
+                .find(|d| self.known_args.iter().any(|k| k.is_same(d)) == false)
+            {
+                return Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::UnusedArg,
+                    ErrorContext::UnusedArg(arg.clone()),
+                    self.options.cap_mode,
+                ));
+            }
+        }
+        // check if map is empty, and return the minimum found index.
+        if let Some((prefix, key, pos)) = self.capture_bad_flag(self.tokens.len())? {
+            Err(Error::new(
+                self.help.clone(),
+                ErrorKind::UnexpectedArg,
+                ErrorContext::UnexpectedArg(
+                    format!("{}{}", prefix, key),
+                    self.path.clone(),
+                    self.known_args_as_flag_names()
+                        .iter()
+                        .map(|n| format!("{}{}", self.flag_prefix(), n))
+                        .collect(),
+                ),
+                self.options.cap_mode,
+            )
+            .with_span(self.span_at(pos)))
+        // find first non-none token
+        } else if let Some((j, t)) = self.tokens.iter().enumerate().find(|(_, p)| p.is_some()) {
+            let span = self.span_at(j);
+            match t {
+                // arguments can remain after the terminator if collecting the remainder failed
+                Some(Token::UnattachedArgument(_, word))
+                | Some(Token::AttachedArgument(_, word))
+                | Some(Token::Ignore(_, word))
+                | Some(Token::Inserted(word)) => Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::UnexpectedArg,
+                    ErrorContext::UnexpectedArg(word.to_string(), self.path.clone(), Vec::new()),
+                    self.options.cap_mode,
+                )
+                .with_span(span)),
+                Some(Token::Terminator(_)) => Err(Error::new(
+                    self.help.clone(),
+                    ErrorKind::UnexpectedArg,
+                    ErrorContext::UnexpectedArg(self.flag_prefix(), self.path.clone(), Vec::new()),
+                    self.options.cap_mode,
+                )
+                .with_span(span)),
+                _ => panic!("no other tokens types should be left"),
+            }
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Checks if help has been raised and will return its own error for displaying
+    /// help.
+    ///
+    /// Otherwise, an eager flag that has been raised returns its own error for
+    /// displaying its text.
+    fn try_to_help(&self) -> Result<()> {
+        if self.options.help_policy == HelpPolicy::Always {
+            self.try_to_help_if_valid()?;
+        }
+        match &self.eager {
+            Some(text) => Err(Error::new(
+                self.help.clone(),
+                ErrorKind::Eager,
+                ErrorContext::Eager(text.clone()),
+                self.options.cap_mode,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks if help has been raised and will return its own error for displaying
+    /// help, assuming the command-line has been interpreted without errors.
+    fn try_to_help_if_valid(&self) -> Result<()> {
+        if self.options.help_policy != HelpPolicy::Never
+            && self.asking_for_help == true
+            && self.is_help_enabled() == true
+        {
+            Err(Error::new(
+                self.help.clone(),
+                ErrorKind::Help,
+                ErrorContext::Help,
+                self.options.cap_mode,
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::error::ErrorKind;
+    use crate::help::HelpInheritance;
+    use crate::value::PathResolution;
+    use crate::Exit;
+    use std::path::PathBuf;
+
+    /// Helper test fn to write vec of &str as iterator for Cli parameter.
+    fn args<'a>(args: Vec<&'a str>) -> Box<dyn Iterator<Item = String> + 'a> {
+        Box::new(args.into_iter().map(|f| f.to_string()).into_iter())
+    }
+
+    /// Helper test fn to create an unvisited slot at the token `pointers`.
+    fn slot(pointers: &[usize]) -> Slot {
+        let mut slot = Slot::new();
+        pointers.iter().for_each(|j| slot.push(*j));
+        slot
+    }
+
+    #[test]
+    fn get_typed_values() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "serve", "--port", "8080", "a", "b"]))
+            .save();
+        // the value's type is inferred from the argument
+        let port = cli.get(Arg::option_of::<u16>("port")).unwrap();
+        assert_eq!(port, Some(8080));
+        let files = cli.get_all(Arg::positional_of::<String>("file")).unwrap();
+        assert_eq!(
+            files,
+            Some(vec![
+                String::from("serve"),
+                String::from("a"),
+                String::from("b")
+            ])
+        );
+        assert!(cli.empty().is_ok());
+
+        // untyped arguments are still requested by the caller's type
+        let mut cli = Cli::new().parse(args(vec!["orbit", "--port", "80"])).save();
+        assert_eq!(cli.require::<u8>(Arg::option("port")).unwrap(), 80);
+    }
+
+    #[test]
+    fn get_attached_only() {
+        let color = || Arg::option("color").value("when").attached_only();
+        // the following argument is never taken as the value
+        let mut cli = Cli::new().parse(args(vec!["ls", "--color", "src"])).save();
+        assert_eq!(
+            cli.get::<String>(color().default_missing("auto")).unwrap(),
+            Some(String::from("auto"))
+        );
+        assert_eq!(
+            cli.require::<String>(Arg::positional("dir")).unwrap(),
+            "src"
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["ls", "--color=never", "src"]))
+            .save();
+        assert_eq!(
+            cli.get::<String>(color().default_missing("auto")).unwrap(),
+            Some(String::from("never"))
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["ls", "src"])).save();
+        assert_eq!(
+            cli.get::<String>(color().default_missing("auto")).unwrap(),
+            None
+        );
+
+        // a value is still required without a missing value
+        let mut cli = Cli::new().parse(args(vec!["ls", "--color", "src"])).save();
+        let err = cli.get::<String>(color()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ExpectingValue);
+        assert!(err.to_string().contains("\"--color=<when>\""));
+    }
+
+    #[test]
+    fn get_optional_value() {
+        let jobs = || Arg::option("jobs").switch('j').default_missing("0");
+        // absent
+        let mut cli = Cli::new().parse(args(vec!["make"])).save();
+        assert_eq!(cli.get::<u8>(jobs()).unwrap(), None);
+        assert_eq!(
+            cli.get_or::<u8, _, std::io::Error>(jobs(), || Ok(1))
+                .unwrap(),
+            1
+        );
+        // present without a value
+        let mut cli = Cli::new()
+            .parse(args(vec!["make", "-j", "--verbose"]))
+            .save();
+        assert_eq!(cli.check(Arg::flag("verbose")).unwrap(), true);
+        assert_eq!(cli.get::<u8>(jobs()).unwrap(), Some(0));
+        let mut cli = Cli::new().parse(args(vec!["make", "--jobs"])).save();
+        assert_eq!(cli.get::<u8>(jobs()).unwrap(), Some(0));
+        // present with a value
+        let mut cli = Cli::new().parse(args(vec!["make", "--jobs", "8"])).save();
+        assert_eq!(cli.get::<u8>(jobs()).unwrap(), Some(8));
+
+        // every instance takes the missing value when supplied without one
+        let mut cli = Cli::new()
+            .parse(args(vec!["make", "-j", "--jobs=2", "-j"]))
+            .save();
+        assert_eq!(cli.get_all::<u8>(jobs()).unwrap(), Some(vec![0, 2, 0]));
+        let mut cli = Cli::new()
+            .parse(args(vec!["ls", "--color", "src", "--color=never"]))
+            .save();
+        assert_eq!(
+            cli.get_all_indexed::<String>(
+                Arg::option("color").attached_only().default_missing("auto")
+            )
+            .unwrap(),
+            Some(vec![(1, String::from("auto")), (3, String::from("never"))])
+        );
+    }
+
+    #[test]
+    fn get_hyphen_values() {
+        let filter = || Arg::option("filter").switch('f').allow_hyphen_values();
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "log",
+                "--filter",
+                "--name-only",
+                "-f",
+                "-abc",
+                "main",
+            ]))
+            .save();
+        assert_eq!(
+            cli.get_all_indexed::<String>(filter()).unwrap(),
+            Some(vec![
+                (2, String::from("--name-only")),
+                (4, String::from("-abc"))
+            ])
+        );
+        assert_eq!(
+            cli.require::<String>(Arg::positional("branch")).unwrap(),
+            "main"
+        );
+        // the values are not left behind as unknown flags
+        assert!(cli.empty().is_ok());
+
+        // attached and regular values are unchanged
+        let mut cli = Cli::new()
+            .parse(args(vec!["log", "--filter=-x", "-f", "y"]))
+            .save();
+        assert_eq!(
+            cli.get_all::<String>(filter()).unwrap(),
+            Some(vec![String::from("-x"), String::from("y")])
+        );
+
+        // without the setting, a flag-like value is still rejected
+        let mut cli = Cli::new()
+            .parse(args(vec!["log", "--filter", "--name-only"]))
+            .save();
+        assert_eq!(
+            cli.get::<String>(Arg::option("filter")).unwrap_err().kind(),
+            ErrorKind::ExpectingValue
+        );
+    }
+
+    #[test]
+    fn get_require_equals() {
+        let key = || Arg::option("key").require_equals();
+        let mut cli = Cli::new()
+            .parse(args(vec!["cfg", "--key=value", "name"]))
+            .save();
+        assert_eq!(
+            cli.get::<String>(key()).unwrap(),
+            Some(String::from("value"))
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["cfg", "--key", "value"])).save();
+        let err = cli.get::<String>(key()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ExpectingValue);
+        assert_eq!(
+            err.to_string(),
+            "option \"--key=<key>\" requires its value to be attached with \"=\"\n\nIf \"value\" was meant as its value, try \"--key=value\""
+        );
+
+        // a missing value allows the option to be supplied alone
+        let mut cli = Cli::new().parse(args(vec!["cfg", "--key", "name"])).save();
+        assert_eq!(
+            cli.get_all::<String>(key().default_missing("on")).unwrap(),
+            Some(vec![String::from("on")])
+        );
+        assert_eq!(
+            cli.require::<String>(Arg::positional("name")).unwrap(),
+            "name"
+        );
+    }
+
+    #[test]
+    fn get_grouped_values() {
+        let line = vec!["orbit", "-vl=direct", "-l=copy"];
+        let link = || Arg::option("link").switch('l');
+        let mut cli = Cli::new().parse(args(line.clone())).save();
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), true);
+        let err = cli.get_all::<String>(link()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::GroupedValue);
+        assert_eq!(
+            err.to_string(),
+            "option \"--link <link>\" cannot take a value attached to a group of switches \"-vl=direct\"\n\nWrite the switch with its value separately, such as \"-v -l=direct\""
+        );
+        assert_eq!(err.span().map(|s| s.index()), Some(1));
+
+        // the value can be bound to the last switch
+        let mut cli = Cli::new().bind_grouped_values().parse(args(line)).save();
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), true);
+        assert_eq!(
+            cli.get_all::<String>(link()).unwrap(),
+            Some(vec![String::from("direct"), String::from("copy")])
+        );
+    }
+
+    #[test]
+    fn get_defaults() {
+        let format = || {
+            Arg::option("format")
+                .default_if(Arg::flag("machine"), "json")
+                .default("table")
+        };
+        let mut cli = Cli::new().parse(args(vec!["orbit", "--machine"])).save();
+        assert_eq!(cli.get::<String>(format()).unwrap().unwrap(), "json");
+        let mut cli = Cli::new().parse(args(vec!["orbit"])).save();
+        assert_eq!(cli.require::<String>(format()).unwrap(), "table");
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--machine", "--format", "yaml"]))
+            .save();
+        assert_eq!(cli.check(Arg::flag("machine")).unwrap(), true);
+        assert_eq!(
+            cli.get_all::<String>(format()).unwrap().unwrap(),
+            vec!["yaml"]
+        );
+
+        // defaults are parsed the same as supplied values
+        let mut cli = Cli::new().parse(args(vec!["orbit"])).save();
+        assert_eq!(
+            cli.require::<u8>(Arg::option("jobs").default("4")).unwrap(),
+            4
+        );
+        assert_eq!(
+            cli.require::<u8>(Arg::positional("count").default("x"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::BadType
+        );
+    }
+
+    #[test]
+    fn get_all_in_order() {
+        // names, aliases, and switches are interleaved
+        let line = vec![
+            "orbit",
+            "-x",
+            "a",
+            "--exclude=b",
+            "--skip",
+            "c",
+            "-x",
+            "d",
+            "--exclude",
+            "e",
+        ];
+        let exclude = || Arg::option("exclude").alias("skip").switch('x');
+        let mut cli = Cli::new().parse(args(line.clone())).save();
+        assert_eq!(
+            cli.get_all::<String>(exclude()).unwrap().unwrap(),
+            vec!["a", "b", "c", "d", "e"]
+        );
+        let mut cli = Cli::new().parse(args(line)).save();
+        assert_eq!(
+            cli.require_all::<String>(exclude()).unwrap(),
+            vec!["a", "b", "c", "d", "e"]
+        );
+    }
+
+    #[test]
+    fn get_all_indexed() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "sync",
+                "--include",
+                "*.rs",
+                "src",
+                "--exclude=target",
+                "-i",
+                "*.md",
+                "docs",
+            ]))
+            .save();
+        let include: Vec<(usize, String)> = cli
+            .get_all_indexed(Arg::option("include").switch('i'))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            include,
+            vec![(2, String::from("*.rs")), (6, String::from("*.md"))]
+        );
+        let exclude: Vec<(usize, String)> = cli
+            .get_all_indexed(Arg::option("exclude"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(exclude, vec![(4, String::from("target"))]);
+        let dirs: Vec<(usize, String)> = cli
+            .get_all_indexed(Arg::positional("dir"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            dirs,
+            vec![(3, String::from("src")), (7, String::from("docs"))]
+        );
+        assert_eq!(
+            cli.get_all_indexed::<String>(Arg::positional("extra"))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn get_all_optionals() {
+        // option provided multiple times
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "plan",
+                "--fileset",
+                "a",
+                "--fileset=b",
+                "--fileset",
+                "c",
+            ]))
+            .save();
+        let sets: Vec<String> = cli
+            .get_option_all(Optional::new("fileset"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(sets, vec!["a", "b", "c"]);
+        // failing case- bad conversion of 'c' to an integer
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "plan",
+                "--digit",
+                "10",
+                "--digit=9",
+                "--digit",
+                "c",
+            ]))
+            .save();
+        assert_eq!(
+            cli.get_option_all::<i32>(Optional::new("digit")).is_err(),
+            true
+        ); // bad conversion
+           // option provided as valid integers
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "plan",
+                "--digit",
+                "10",
                 "--digit=9",
                 "--digit",
                 "1",
             ]))
             .save();
-        let sets: Vec<i32> = cli.get_option_all(Optional::new("digit")).unwrap().unwrap();
-        assert_eq!(sets, vec![10, 9, 1]);
-        // option provided once
+        let sets: Vec<i32> = cli.get_option_all(Optional::new("digit")).unwrap().unwrap();
+        assert_eq!(sets, vec![10, 9, 1]);
+        // option provided once
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "plan", "--fileset", "a"]))
+            .save();
+        let sets: Vec<String> = cli
+            .get_option_all(Optional::new("fileset"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(sets, vec!["a"]);
+        // option not provided
+        let mut cli = Cli::new().parse(args(vec!["orbit", "plan"])).save();
+        let sets: Option<Vec<String>> = cli.get_option_all(Optional::new("fileset")).unwrap();
+        assert_eq!(sets, None);
+    }
+
+    #[test]
+    fn match_command() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "get",
+                "rary.gates",
+                "--instance",
+                "--component",
+            ]))
+            .save();
+        // successfully matched 'get' command
+        assert_eq!(
+            cli.select(&["new", "get", "install", "edit"]).unwrap(),
+            "get".to_string()
+        );
+
+        let mut cli = Cli::new()
+            .threshold(4)
+            .parse(args(vec![
+                "orbit",
+                "got",
+                "rary.gates",
+                "--instance",
+                "--component",
+            ]))
+            .save();
+        // suggest 'get' command
+        assert!(cli.select(&["new", "get", "install", "edit"]).is_err());
+    }
+
+    #[test]
+    fn command_path() {
+        let mut cli = Cli::new()
+            .threshold(0)
+            .parse(args(vec![
+                "./target/debug/myapp",
+                "plan",
+                "build",
+                "--fast",
+            ]))
+            .save();
+        assert_eq!(cli.command_path(), &["myapp"]);
+        cli.select(&["plan"]).unwrap();
+        cli.select(&["build"]).unwrap();
+        assert_eq!(cli.command_path(), &["myapp", "plan", "build"]);
+        let err = cli.empty().unwrap_err();
+        match err.context() {
+            ErrorContext::UnexpectedArg(w, path, _) => {
+                assert_eq!(w, "--fast");
+                assert_eq!(path, &vec!["myapp", "plan", "build"]);
+            }
+            _ => panic!("expected an unexpected argument"),
+        }
+        assert_eq!(
+            err.to_string(),
+            "invalid argument \"--fast\" in \"myapp plan build\""
+        );
+
+        // unknown subcommands report the path before the subcommand
+        let mut cli = Cli::new()
+            .threshold(0)
+            .parse(args(vec!["myapp", "plan", "destroy"]))
+            .save();
+        cli.select(&["plan"]).unwrap();
+        cli.known_args
+            .push(ArgType::from(Arg::subcommand("command")));
+        match cli.select(&["build"]).unwrap_err().context() {
+            ErrorContext::UnknownSubcommand(_, w, path, _) => {
+                assert_eq!(w, "destroy");
+                assert_eq!(path, &vec!["myapp", "plan"]);
+            }
+            _ => panic!("expected an unknown subcommand"),
+        }
+    }
+
+    #[test]
+    #[should_panic = "requires positional argument"]
+    fn match_command_no_arg() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "got",
+                "rary.gates",
+                "--instance",
+                "--component",
+            ]))
+            .save();
+        // cli has no positional arguments loaded to report as error... panic
+        assert!(cli.select(&["new", "get", "install", "edit"]).is_err());
+    }
+
+    #[test]
+    fn find_first_flag_left() {
+        let cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--help",
+                "new",
+                "rary.gates",
+                "--vcs",
+                "git",
+            ]))
+            .save();
+        assert_eq!(
+            cli.find_first_flag_left(cli.tokens.len()),
+            Some(("help", 0))
+        );
+
+        let cli = Cli::new()
+            .parse(args(vec!["orbit", "new", "rary.gates"]))
+            .save();
+        assert_eq!(cli.find_first_flag_left(cli.tokens.len()), None);
+
+        let cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "new",
+                "rary.gates",
+                "--vcs",
+                "git",
+                "--help",
+            ]))
+            .save();
+        assert_eq!(cli.find_first_flag_left(cli.tokens.len()), Some(("vcs", 2)));
+
+        let cli = Cli::new()
+            .parse(args(vec!["orbit", "new", "rary.gates", "-c=git", "--help"]))
+            .save();
+        assert_eq!(cli.find_first_flag_left(cli.tokens.len()), Some(("c", 2)));
+
+        let cli = Cli::new()
+            .parse(args(vec!["orbit", "new", "rary.gates", "-c=git", "--help"]))
+            .save();
+        assert_eq!(cli.find_first_flag_left(1), None); // check before 'rary.gates' position
+
+        let cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--unknown",
+                "new",
+                "rary.gates",
+                "-c=git",
+                "--help",
+            ]))
+            .save();
+        assert_eq!(cli.find_first_flag_left(1), Some(("unknown", 0))); // check before 'new' subcommand
+    }
+
+    #[test]
+    fn processed_all_args() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--upgrade",
+                "new",
+                "rary.gates",
+                "--vcs",
+                "git",
+            ]))
+            .save();
+        // tokens are still in token stream
+        let _ = cli.check_flag(Flag::new("upgrade")).unwrap();
+        let _: Option<String> = cli.get_option(Optional::new("vcs")).unwrap();
+        let _: String = cli.require_positional(Positional::new("command")).unwrap();
+        let _: String = cli.require_positional(Positional::new("ip")).unwrap();
+        // no more tokens left in stream
+        assert_eq!(cli.empty().unwrap(), ());
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "new", "rary.gates", "--"]))
+            .save();
+        // removes only valid args/flags/opts
+        let _ = cli.check_flag(Flag::new("help")).unwrap();
+        let _: Option<String> = cli.get_option(Optional::new("vcs")).unwrap();
+        let _: String = cli.require_positional(Positional::new("command")).unwrap();
+        let _: String = cli.require_positional(Positional::new("ip")).unwrap();
+        // unexpected '--'
+        assert!(cli.empty().is_err());
+
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--help",
+                "new",
+                "rary.gates",
+                "--vcs",
+                "git",
+            ]))
+            .save();
+        // no tokens were removed (help will also raise error)
+        assert!(cli.empty().is_err());
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--", "some", "extra", "words"]))
+            .save();
+        let _: Vec<String> = cli.remainder().unwrap();
+        // terminator removed as well as its arguments that were ignored
+        assert_eq!(cli.empty().unwrap(), ());
+    }
+
+    #[test]
+    fn tokenizer() {
+        let cli = Cli::new().parse(args(vec![])).save();
+        assert_eq!(cli.tokens, vec![]);
+
+        let cli = Cli::new().parse(args(vec!["orbit"])).save();
+        assert_eq!(cli.tokens, vec![]);
+
+        let cli = Cli::new().parse(args(vec!["orbit", "--help"])).save();
+        assert_eq!(cli.tokens, vec![Some(Token::Flag(0))]);
+
+        let cli = Cli::new().parse(args(vec!["orbit", "--help", "-v"])).save();
+        assert_eq!(
+            cli.tokens,
+            vec![Some(Token::Flag(0)), Some(Token::Switch(1, 'v'))],
+        );
+
+        let cli = Cli::new()
+            .parse(args(vec!["orbit", "new", "rary.gates"]))
+            .save();
+        assert_eq!(
+            cli.tokens,
+            vec![
+                Some(Token::UnattachedArgument(0, "new".to_string())),
+                Some(Token::UnattachedArgument(1, "rary.gates".to_string())),
+            ],
+        );
+
+        let cli = Cli::new()
+            .parse(args(vec!["orbit", "--help", "-vh"]))
+            .save();
+        assert_eq!(
+            cli.tokens,
+            vec![
+                Some(Token::Flag(0)),
+                Some(Token::Switch(1, 'v')),
+                Some(Token::Switch(1, 'h')),
+            ],
+        );
+
+        let cli = Cli::new()
+            .parse(args(vec!["orbit", "--help", "-vhc=10"]))
+            .save();
+        assert_eq!(
+            cli.tokens,
+            vec![
+                Some(Token::Flag(0)),
+                Some(Token::Switch(1, 'v')),
+                Some(Token::Switch(1, 'h')),
+                Some(Token::Switch(1, 'c')),
+                Some(Token::AttachedArgument(1, "10".to_string())),
+            ],
+        );
+
+        // an attached argument can sneak in behind a terminator
+        let cli = Cli::new()
+            .parse(args(vec!["orbit", "--=value", "extra"]))
+            .save();
+        assert_eq!(
+            cli.tokens,
+            vec![
+                Some(Token::Terminator(0)),
+                Some(Token::AttachedArgument(0, "value".to_string())),
+                Some(Token::Ignore(1, "extra".to_string())),
+            ]
+        );
+
+        // final boss
+        let cli = Cli::new().parse(args(vec![
+            "orbit",
+            "--help",
+            "-v",
+            "new",
+            "ip",
+            "--lib",
+            "--name=rary.gates",
+            "--help",
+            "-sci",
+            "--",
+            "--map",
+            "synthesis",
+            "-jto",
+        ]));
+        assert_eq!(
+            cli.tokens,
+            vec![
+                Some(Token::Flag(0)),
+                Some(Token::Switch(1, 'v')),
+                Some(Token::UnattachedArgument(2, "new".to_string())),
+                Some(Token::UnattachedArgument(3, "ip".to_string())),
+                Some(Token::Flag(4)),
+                Some(Token::Flag(5)),
+                Some(Token::AttachedArgument(5, "rary.gates".to_string())),
+                Some(Token::Flag(6)),
+                Some(Token::Switch(7, 's')),
+                Some(Token::Switch(7, 'c')),
+                Some(Token::Switch(7, 'i')),
+                Some(Token::Terminator(8)),
+                Some(Token::Ignore(9, "--map".to_string())),
+                Some(Token::Ignore(10, "synthesis".to_string())),
+                Some(Token::Ignore(11, "-jto".to_string())),
+            ],
+        );
+    }
+
+    #[test]
+    fn view_tokens() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "find",
+                ".",
+                "-name=*.rs",
+                "--type",
+                "f",
+                "-",
+                "--",
+                "-print",
+            ]))
+            .save();
+        assert_eq!(
+            cli.tokens().collect::<Vec<TokenView>>(),
+            vec![
+                TokenView::Positional(1, "."),
+                TokenView::Switch(2, 'n'),
+                TokenView::Switch(2, 'a'),
+                TokenView::Switch(2, 'm'),
+                TokenView::Switch(2, 'e'),
+                TokenView::Value(2, "*.rs"),
+                TokenView::Flag(3, "type"),
+                TokenView::Positional(4, "f"),
+                TokenView::EmptySwitch(5),
+                TokenView::Terminator(6),
+                TokenView::Ignored(7, "-print"),
+            ]
+        );
+
+        // consumed arguments are no longer viewed
+        cli.get::<String>(Arg::option("type")).unwrap();
+        cli.require::<String>(Arg::positional("path")).unwrap();
+        assert_eq!(
+            cli.tokens().collect::<Vec<TokenView>>(),
+            vec![
+                TokenView::Switch(2, 'n'),
+                TokenView::Switch(2, 'a'),
+                TokenView::Switch(2, 'm'),
+                TokenView::Switch(2, 'e'),
+                TokenView::Value(2, "*.rs"),
+                TokenView::EmptySwitch(5),
+                TokenView::Terminator(6),
+                TokenView::Ignored(7, "-print"),
+            ]
+        );
+    }
+
+    #[test]
+    fn inserted_tokens_have_no_position() {
+        let mut cli = Cli::new().parse(args(vec!["orbit", "--all", "lib"])).save();
+        cli.insert_uarg(String::from("build"));
+        assert_eq!(cli.check_invariants(), Ok(()));
+        // the inserted word is never viewed or located on the command-line
+        assert_eq!(
+            cli.tokens().collect::<Vec<TokenView>>(),
+            vec![TokenView::Flag(1, "all"), TokenView::Positional(2, "lib")]
+        );
+        assert_eq!(cli.arg_index(0), None);
+        assert_eq!(cli.raw_arg_at(0), None);
+        assert_eq!(cli.span_at(0), None);
+        assert_eq!(cli.arg_index(1), Some(1));
+        assert_eq!(cli.raw_arg_at(1), Some(String::from("--all")));
+
+        // the inserted word is served first without a location
+        assert_eq!(cli.next_uarg(), Some(String::from("build")));
+        assert_eq!(cli.taken, Vec::new());
+        assert_eq!(
+            cli.get_all_indexed::<String>(Arg::positional("path"))
+                .unwrap(),
+            Some(vec![(2, String::from("lib"))])
+        );
+    }
+
+    #[test]
+    fn operand_expression() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "find", "--follow", "src", "(", "-name", "*.rs", "-o", "-size", "-5", ")",
+                "-print", "--", "extra",
+            ]))
+            .save();
+        // flags before the stream are requested first
+        assert_eq!(cli.check(Arg::flag("follow")).unwrap(), true);
+        assert_eq!(
+            cli.operands(),
+            vec![
+                Operand::Value(String::from("(")),
+                Operand::Flag(String::from("-name")),
+                Operand::Value(String::from("*.rs")),
+                Operand::Flag(String::from("-o")),
+                Operand::Flag(String::from("-size")),
+                Operand::Value(String::from("-5")),
+                Operand::Value(String::from(")")),
+                Operand::Flag(String::from("-print")),
+            ]
+        );
+        // arguments before the stream and after the terminator are left alone
+        assert_eq!(
+            cli.get::<String>(Arg::option("name").switch('n')).unwrap(),
+            None
+        );
+        assert_eq!(
+            cli.require::<String>(Arg::positional("path")).unwrap(),
+            "src"
+        );
+        assert_eq!(cli.remainder().unwrap(), vec!["extra"]);
+        assert!(cli.empty().is_ok());
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["find", "-empty", "-or", "!", "-readable"]))
+            .save();
+        assert_eq!(
+            cli.expression().unwrap(),
+            Some(Expr::Or(
+                Box::new(Expr::Primary(String::from("-empty"), Vec::new())),
+                Box::new(Expr::Not(Box::new(Expr::Primary(
+                    String::from("-readable"),
+                    Vec::new()
+                )))),
+            ))
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["find", "src"])).save();
+        assert_eq!(cli.expression().unwrap(), None);
+
+        let mut cli = Cli::new().parse(args(vec!["find", "(", "-empty"])).save();
+        let err = cli.expression().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidExpression);
+        assert_eq!(err.to_string(), "invalid expression: missing closing \")\"");
+    }
+
+    #[test]
+    fn getopt_long() {
+        use crate::getopt::HasArg;
+
+        let spec = Getopt::new("vn:c::")
+            .long("verbose", HasArg::No, 'v')
+            .long("lines", HasArg::Required, 'n')
+            .long("color", HasArg::Optional, 'c');
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "tail", "-vn5", "app.log", "--color", "-c=x", "--lines", "-", "--", "-v",
+            ]))
+            .save();
+        let m = cli.getopt(&spec).unwrap();
+        assert_eq!(
+            m.opts()
+                .iter()
+                .map(|o| (o.val(), o.arg()))
+                .collect::<Vec<(char, Option<&str>)>>(),
+            vec![
+                ('v', None),
+                ('n', Some("5")),
+                ('c', None),
+                ('c', Some("=x")),
+                ('n', Some("-")),
+            ]
+        );
+        assert_eq!(m.operands(), &["app.log", "-v"]);
+        assert!(cli.empty().is_ok());
+
+        let mut cli = Cli::new().parse(args(vec!["tail", "-x", "app.log"])).save();
+        let err = cli.getopt(&spec).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnexpectedArg);
+        assert_eq!(err.to_string(), "invalid option -- 'x'");
+        let err = Cli::new()
+            .parse(args(vec!["tail", "--lines"]))
+            .save()
+            .getopt(&spec)
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ExpectingValue);
+    }
+
+    #[test]
+    fn get_dynamic_options() {
+        let pair = |k: &str, v: &str| (k.to_string(), v.to_string());
+
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "cc",
+                "-DDEBUG",
+                "-v",
+                "-DLEVEL=2",
+                "main.c",
+                "-D",
+                "NAME=a=b",
+                "-vD=x",
+            ]))
+            .save();
+        assert_eq!(
+            cli.get_dynamic(Arg::prefix("D")).unwrap(),
+            vec![pair("DEBUG", ""), pair("LEVEL", "2"), pair("NAME", "a=b")]
+        );
+        assert_eq!(cli.check_all(Arg::flag("verbose").switch('v')).unwrap(), 2);
+        assert_eq!(
+            cli.require::<String>(Arg::positional("file")).unwrap(),
+            "main.c"
+        );
+        // a prefix that does not lead the argument is not dynamic
+        assert_eq!(cli.empty().unwrap_err().kind(), ErrorKind::UnexpectedArg);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["app", "--set", "a=1", "--set=b=2"]))
+            .save();
+        assert_eq!(
+            cli.get_dynamic(Arg::prefix("set")).unwrap(),
+            vec![pair("a", "1"), pair("b", "2")]
+        );
+        assert!(cli.empty().is_ok());
+
+        // the prefix is not supplied
+        let mut cli = Cli::new().parse(args(vec!["app"])).save();
+        assert_eq!(cli.get_dynamic(Arg::prefix("D")).unwrap(), vec![]);
+
+        // the key is missing
+        let mut cli = Cli::new().parse(args(vec!["app", "-D"])).save();
+        assert_eq!(
+            cli.get_dynamic(Arg::prefix("D")).unwrap_err().kind(),
+            ErrorKind::ExpectingValue
+        );
+        let mut cli = Cli::new().parse(args(vec!["app", "--set", "=1"])).save();
+        assert_eq!(
+            cli.get_dynamic(Arg::prefix("set")).unwrap_err().kind(),
+            ErrorKind::ExpectingValue
+        );
+    }
+
+    #[test]
+    fn collect_unknown_flags() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "wrap",
+                "--release",
+                "-vj=4",
+                "build",
+                "--target=x86",
+                "-",
+                "--",
+                "--extra",
+            ]))
+            .save();
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), true);
+        assert_eq!(
+            cli.collect_unknown().unwrap(),
+            vec!["--release", "-j=4", "--target=x86", "-"]
+        );
+        assert_eq!(
+            cli.require::<String>(Arg::positional("command")).unwrap(),
+            "build"
+        );
+        assert_eq!(cli.remainder().unwrap(), vec!["--extra"]);
+        assert!(cli.empty().is_ok());
+
+        // nothing is left to collect
+        let mut cli = Cli::new().parse(args(vec!["wrap", "build"])).save();
+        assert_eq!(cli.collect_unknown().unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    fn strict_unused_args() {
+        let mut cli = Cli::new()
+            .strict()
+            .parse(args(vec!["app", "--verbose", "file.txt"]))
+            .save();
+        cli.declare(Arg::flag("verbose"));
+        cli.declare(Arg::option("jobs").value("n"));
+        cli.declare(Arg::positional("file"));
+        assert_eq!(cli.check(Arg::flag("verbose")).unwrap(), true);
+        assert_eq!(
+            cli.require::<String>(Arg::positional("file")).unwrap(),
+            "file.txt"
+        );
+        let err = cli.empty().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnusedArg);
+        assert_eq!(
+            err.to_string(),
+            "strict mode: argument \"--jobs <n>\" was declared but never requested during interpretation"
+        );
+
+        // requesting an option that is not present still counts as requested
+        cli.rewind();
+        cli.declare(Arg::option("jobs").value("n"));
+        assert_eq!(cli.check(Arg::flag("verbose")).unwrap(), true);
+        assert_eq!(cli.get::<usize>(Arg::option("jobs")).unwrap(), None);
+        cli.require::<String>(Arg::positional("file")).unwrap();
+        assert!(cli.empty().is_ok());
+
+        // declarations are ignored without strict mode
+        let mut cli = Cli::new().parse(args(vec!["app"])).save();
+        cli.declare(Arg::flag("verbose"));
+        assert!(cli.empty().is_ok());
+    }
+
+    #[test]
+    fn find_flags_and_switches() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--help",
+                "-v",
+                "new",
+                "ip",
+                "--lib",
+                "--name=rary.gates",
+                "--help",
+                "-sci",
+                "-i",
+                "--",
+                "--map",
+                "synthesis",
+                "-jto",
+            ]))
+            .save();
+
+        // detects 0
+        assert_eq!(cli.take_flag_locs("version"), Vec::<usize>::new());
+        // detects 1
+        assert_eq!(cli.take_flag_locs("lib"), vec![4]);
+        // detects multiple
+        assert_eq!(cli.take_flag_locs("help"), vec![0, 7]);
+        // flag was past terminator and marked as ignore
+        assert_eq!(cli.take_flag_locs("map"), Vec::<usize>::new());
+        // filters out arguments
+        assert_eq!(cli.take_flag_locs("rary.gates"), Vec::<usize>::new());
+
+        // detects 0
+        assert_eq!(cli.take_switch_locs(&'q'), Vec::<usize>::new());
+        // detects 1
+        assert_eq!(cli.take_switch_locs(&'v'), vec![1]);
+        // detects multiple
+        assert_eq!(cli.take_switch_locs(&'i'), vec![10, 11]);
+        // switch was past terminator and marked as ignore
+        assert_eq!(cli.take_switch_locs(&'j'), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn flags_in_map() {
+        let cli = Cli::new().parse(args(vec![
+            "orbit",
+            "--help",
+            "-v",
+            "new",
+            "ip",
+            "--lib",
+            "--name=rary.gates",
+            "--help",
+            "-sci",
+            "--",
+            "--map",
+            "synthesis",
+            "-jto",
+        ]));
+        let mut store = Store::default();
+        // store long options
+        store.insert(Tag::Flag("help".to_string()), slot(&[0, 7]));
+        store.insert(Tag::Flag("lib".to_string()), slot(&[4]));
+        store.insert(Tag::Flag("name".to_string()), slot(&[5]));
+        // stores switches too
+        store.insert(Tag::Switch("v".to_string()), slot(&[1]));
+        store.insert(Tag::Switch("s".to_string()), slot(&[8]));
+        store.insert(Tag::Switch("c".to_string()), slot(&[9]));
+        store.insert(Tag::Switch("i".to_string()), slot(&[10]));
+        assert_eq!(cli.store, store);
+    }
+
+    #[test]
+    fn slot_pointers() {
+        let mut pointers = Pointers::new();
+        [3, 5].iter().for_each(|j| pointers.push(*j));
+        assert!(matches!(pointers, Pointers::Inline(_, 2)));
+        // more positions than fit inline are moved to the heap
+        [8, 13].iter().for_each(|j| pointers.push(*j));
+        assert_eq!(pointers.as_slice(), &[3, 5, 8, 13]);
+        pointers.retain(|j| j % 2 == 1);
+        assert_eq!(pointers, slot(&[3, 5, 13]).pointers);
+
+        let mut pointers = slot(&[1, 2]).pointers;
+        pointers.retain(|j| *j != 1);
+        assert_eq!(pointers.as_slice(), &[2]);
+    }
+
+    #[test]
+    fn take_unattached_args() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--help",
+                "-v",
+                "new",
+                "ip",
+                "--lib",
+                "--name=rary.gates",
+                "--help",
+                "-scii",
+                "get",
+                "--",
+                "--map",
+                "synthesis",
+                "-jto",
+            ]))
+            .save();
+
+        assert_eq!(cli.next_uarg().unwrap(), "new".to_string());
+        assert_eq!(cli.next_uarg().unwrap(), "ip".to_string());
+        assert_eq!(cli.next_uarg().unwrap(), "get".to_string());
+        assert_eq!(cli.next_uarg(), None);
+    }
+
+    #[test]
+    fn take_remainder_args() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--help",
+                "-v",
+                "new",
+                "ip",
+                "--lib",
+                "--name=rary.gates",
+                "--help",
+                "-scii",
+                "get",
+                "--",
+                "--map",
+                "synthesis",
+                "-jto",
+            ]))
+            .save();
+        assert_eq!(cli.remainder().unwrap(), vec!["--map", "synthesis", "-jto"]);
+        // the items were removed from the token stream
+        assert_eq!(cli.remainder().unwrap(), Vec::<String>::new());
+
+        // an attached argument can sneak in behind a terminator (handle in a result fn)
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--=value", "extra"]))
+            .save();
+        assert!(cli.remainder().is_err());
+
+        let mut cli = Cli::new().parse(args(vec!["orbit", "--help"])).save();
+        // the terminator was never found
+        assert_eq!(cli.remainder().unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn pull_values_from_flags() {
+        let mut cli = Cli::new().parse(args(vec!["orbit", "--help"])).save();
+        let locs = cli.take_flag_locs("help");
+        assert_eq!(cli.pull_flag(locs, false), vec![None]);
+        assert_eq!(cli.tokens.get(0), Some(&None));
+
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--name",
+                "gates",
+                "arg",
+                "--lib",
+                "new",
+                "--name=gates2",
+                "--opt=1",
+                "--opt",
+                "--help",
+            ]))
+            .save();
+        let locs = cli.take_flag_locs("lib");
+        assert_eq!(cli.pull_flag(locs, false), vec![None]);
+        // token no longer exists
+        assert_eq!(cli.tokens.get(3), Some(&None));
+
+        // gets strings and removes both instances of flag from token stream
+        let locs = cli.take_flag_locs("name");
+        assert_eq!(
+            cli.pull_flag(locs, true),
+            vec![Some("gates".to_string()), Some("gates2".to_string())]
+        );
+        assert_eq!(cli.tokens.get(0), Some(&None));
+        assert_eq!(cli.tokens.get(5), Some(&None));
+
+        let locs = cli.take_flag_locs("opt");
+        assert_eq!(cli.pull_flag(locs, true), vec![Some("1".to_string()), None]);
+
+        // gets switches as well from the store
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit",
+                "--name",
+                "gates",
+                "-sicn",
+                "dut",
+                "new",
+                "-vl=direct",
+                "--help",
+                "-l",
+                "-m",
+                "install",
+            ]))
+            .save();
+        let locs = cli.take_switch_locs(&'l');
+        assert_eq!(
+            cli.pull_flag(locs, true),
+            vec![Some("direct".to_string()), None]
+        );
+        assert_eq!(cli.tokens.get(9), Some(&None));
+        assert_eq!(cli.tokens.get(12), Some(&None));
+        let locs = cli.take_switch_locs(&'s');
+        assert_eq!(cli.pull_flag(locs, true), vec![None]);
+        let locs = cli.take_switch_locs(&'v');
+        assert_eq!(cli.pull_flag(locs, true), vec![None]);
+        let locs = cli.take_switch_locs(&'i');
+        assert_eq!(cli.pull_flag(locs, true), vec![None]);
+        let locs = cli.take_switch_locs(&'c');
+        assert_eq!(cli.pull_flag(locs, false), vec![None]);
+        let locs = cli.take_switch_locs(&'m');
+        assert_eq!(cli.pull_flag(locs, false), vec![None]);
+    }
+
+    #[test]
+    fn check_flag() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--help", "--verbose", "get"]))
+            .save();
+        assert_eq!(cli.check_flag(Flag::new("help")).unwrap(), true);
+        assert_eq!(cli.check_flag(Flag::new("verbose")).unwrap(), true);
+        assert_eq!(cli.check_flag(Flag::new("version")).unwrap(), false);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--upgrade", "-u"]))
+            .save();
+        assert_eq!(
+            cli.check_flag(Flag::new("upgrade").switch('u'))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::DuplicateOptions
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--verbose", "--verbose", "--version=9"]))
+            .save();
+        assert_eq!(
+            cli.check_flag(Flag::new("verbose")).unwrap_err().kind(),
+            ErrorKind::DuplicateOptions
+        );
+        assert_eq!(
+            cli.check_flag(Flag::new("version")).unwrap_err().kind(),
+            ErrorKind::UnexpectedValue
+        );
+    }
+
+    #[test]
+    fn check_positional() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "new", "rary.gates"]))
+            .save();
+        assert_eq!(
+            cli.get_positional::<String>(Positional::new("command"))
+                .unwrap(),
+            Some("new".to_string())
+        );
+        assert_eq!(
+            cli.get_positional::<String>(Positional::new("ip")).unwrap(),
+            Some("rary.gates".to_string())
+        );
+        assert_eq!(
+            cli.get_positional::<i32>(Positional::new("path")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn check_option() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "command", "--rate", "10"]))
+            .save();
+        assert_eq!(cli.get_option(Optional::new("rate")).unwrap(), Some(10));
+
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit", "--flag", "--rate=9", "command", "-r", "14",
+            ]))
+            .save();
+        assert_eq!(
+            cli.get_option::<i32>(Optional::new("rate").switch('r'))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::DuplicateOptions
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--flag", "-r", "14"]))
+            .save();
+        assert_eq!(
+            cli.get_option(Optional::new("rate").switch('r')).unwrap(),
+            Some(14)
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--flag", "--rate", "--verbose"]))
+            .save();
+        assert_eq!(
+            cli.get_option::<i32>(Optional::new("rate"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::ExpectingValue
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--flag", "--rate", "five", "--verbose"]))
+            .save();
+        assert!(cli.get_option::<i32>(Optional::new("rate")).is_err());
+    }
+
+    #[test]
+    fn take_token_str() {
+        let t = Token::UnattachedArgument(0, "get".to_string());
+        // consumes token and returns its internal string
+        assert_eq!(t.take_str(), "get");
+
+        let t = Token::AttachedArgument(1, "rary.gates".to_string());
+        assert_eq!(t.take_str(), "rary.gates");
+
+        let t = Token::Ignore(7, "--map".to_string());
+        assert_eq!(t.take_str(), "--map");
+    }
+
+    #[test]
+    #[should_panic]
+    fn take_impossible_token_flag_str() {
+        let t = Token::Flag(7);
+        t.take_str();
+    }
+
+    #[test]
+    #[should_panic]
+    fn take_impossible_token_switch_str() {
+        let t = Token::Switch(7, 'h');
+        t.take_str();
+    }
+
+    #[test]
+    #[should_panic]
+    fn take_impossible_token_terminator_str() {
+        let t = Token::Terminator(9);
+        t.take_str();
+    }
+
+    #[test]
+    fn try_help_fail() {
+        let mut cli = Cli::new().parse(args(vec!["orbit", "--h"])).save();
+        let locs = cli.take_flag_locs("help");
+        assert_eq!(locs.len(), 0);
+        assert_eq!(cli.pull_flag(locs, false), vec![]);
+    }
+
+    #[test]
+    fn help_policies() {
+        let run = |policy: HelpPolicy, line: Vec<&str>| -> Result<String> {
+            let mut cli = Cli::new().help_policy(policy).parse(args(line)).save();
+            cli.help(Help::with("Usage: orbit <name>"))?;
+            let name = cli.require::<String>(Arg::positional("name"))?;
+            cli.empty()?;
+            Ok(name)
+        };
+        // help is displayed even though the required argument is missing
+        assert_eq!(
+            run(HelpPolicy::Always, vec!["orbit", "--help"])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Help
+        );
+        // help waits for the rest of the command-line to be valid
+        assert_eq!(
+            run(HelpPolicy::OnlyIfValid, vec!["orbit", "--help"])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingPositional
+        );
+        assert_eq!(
+            run(HelpPolicy::OnlyIfValid, vec!["orbit", "--help", "ada"])
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Help
+        );
+        // help is left to the caller
+        assert_eq!(
+            run(HelpPolicy::Never, vec!["orbit", "--help", "ada"]).unwrap(),
+            "ada"
+        );
+    }
+
+    #[test]
+    fn check_option_n() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "command", "--rate", "10"]))
+            .save();
+        assert_eq!(
+            cli.get_option_until(Optional::new("rate"), 1).unwrap(),
+            Some(vec![10])
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "command", "--rate", "10"]))
+            .save();
+        assert_eq!(
+            cli.get_option_until(Optional::new("rate"), 2).unwrap(),
+            Some(vec![10])
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit", "command", "--rate", "10", "--rate", "4", "--rate", "3",
+            ]))
+            .save();
+        assert_eq!(
+            cli.get_option_until::<u8>(Optional::new("rate"), 2)
+                .is_err(),
+            true
+        );
+    }
+
+    #[test]
+    fn check_flag_n() {
+        let mut cli = Cli::new().parse(args(vec!["orbit"])).save();
+        assert_eq!(cli.check_flag_until(Flag::new("debug"), 4).unwrap(), 0);
+
+        let mut cli = Cli::new().parse(args(vec!["orbit", "--debug"])).save();
+        assert_eq!(cli.check_flag_until(Flag::new("debug"), 1).unwrap(), 1);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--debug", "--debug"]))
+            .save();
+        assert_eq!(cli.check_flag_until(Flag::new("debug"), 3).unwrap(), 2);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--debug", "--debug", "--debug"]))
+            .save();
+        assert_eq!(cli.check_flag_until(Flag::new("debug"), 3).unwrap(), 3);
+
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "orbit", "--debug", "--debug", "--debug", "--debug",
+            ]))
+            .save();
+        assert_eq!(cli.check_flag_until(Flag::new("debug"), 3).is_err(), true);
+    }
+
+    #[test]
+    fn check_flag_all() {
+        let mut cli = Cli::new().parse(args(vec!["orbit"])).save();
+        assert_eq!(cli.check_flag_all(Flag::new("debug")).unwrap(), 0);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--debug", "--debug", "--debug"]))
+            .save();
+        assert_eq!(cli.check_flag_all(Flag::new("debug")).unwrap(), 3);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "--debug", "--debug", "--debug=1"]))
+            .save();
+        assert_eq!(cli.check_flag_all(Flag::new("debug")).is_err(), true);
+    }
+
+    #[test]
+    fn requires_positional_all() {
+        let mut cli = Cli::new().parse(args(vec!["sum", "10", "20", "30"])).save();
+        assert_eq!(
+            cli.require_positional_all::<u8>(Positional::new("digit"))
+                .unwrap(),
+            vec![10, 20, 30]
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["sum"])).save();
+        assert_eq!(
+            cli.require_positional_all::<u8>(Positional::new("digit"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingPositional
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["sum", "10", "--option", "20", "30"]))
+            .save();
+        // note: remember to always check options and flags before positional arguments
+        assert_eq!(
+            cli.get_option::<u8>(Optional::new("option")).unwrap(),
+            Some(20)
+        );
+        assert_eq!(
+            cli.require_positional_all::<u8>(Positional::new("digit"))
+                .unwrap(),
+            vec![10, 30]
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["sum", "100"])).save();
+        assert_eq!(
+            cli.require_positional_all::<u8>(Positional::new("digit"))
+                .unwrap(),
+            vec![100]
+        );
+    }
+
+    /// Parse error that borrows data with a non-'static lifetime.
+    #[derive(Debug)]
+    struct Reserved<'a>(&'a str);
+
+    impl<'a> std::fmt::Display for Reserved<'a> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "\"{}\" is a reserved word", self.0)
+        }
+    }
+
+    impl<'a> std::error::Error for Reserved<'a> {}
+
+    #[derive(Debug, PartialEq)]
+    struct Ident<'a>(String, PhantomData<&'a str>);
+
+    impl<'a> FromStr for Ident<'a> {
+        type Err = Reserved<'a>;
+
+        fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+            match s {
+                "fn" => Err(Reserved("fn")),
+                _ => Ok(Ident(s.to_string(), PhantomData)),
+            }
+        }
+    }
+
+    /// Requests a value whose cast error is bound to the lifetime `'a`.
+    fn get_ident<'a>(cli: &mut Cli<Memory>) -> Result<Option<Ident<'a>>> {
+        cli.get(Arg::positional("ident"))
+    }
+
+    #[test]
+    fn get_with_borrowed_cast_error() {
+        let mut cli = Cli::new().parse(args(vec!["orbit", "gates"])).save();
+        assert_eq!(
+            get_ident(&mut cli).unwrap(),
+            Some(Ident(String::from("gates"), PhantomData))
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["orbit", "fn"])).save();
+        let err = get_ident(&mut cli).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadType);
+        match err.context() {
+            ErrorContext::FailedCast(_, value, message) => {
+                assert_eq!(value, "fn");
+                assert_eq!(message, "\"fn\" is a reserved word");
+            }
+            _ => panic!("expected a failed cast error context"),
+        }
+    }
+
+    #[test]
+    fn suggestion_ties_resolve_by_discovery_order() {
+        let suggest = |first: &str, second: &str| -> String {
+            let mut cli = Cli::new()
+                .threshold(2)
+                .parse(args(vec!["orbit", "--hat"]))
+                .save();
+            cli.check(Arg::flag(first)).unwrap();
+            cli.check(Arg::flag(second)).unwrap();
+            match cli.empty().unwrap_err().context() {
+                ErrorContext::SuggestWord(_, w) => w.join(" "),
+                _ => panic!("expected a suggestion"),
+            }
+        };
+        assert_eq!(suggest("cat", "bat"), "--cat --bat");
+        assert_eq!(suggest("bat", "cat"), "--bat --cat");
+
+        // the ranked suggestions are listed in one message
+        let mut cli = Cli::new()
+            .threshold(4)
+            .parse(args(vec!["orbit", "--verbsoe"]))
+            .save();
+        cli.check(Arg::flag("version")).unwrap();
+        cli.check(Arg::flag("verbose")).unwrap();
+        cli.check(Arg::flag("lib")).unwrap();
+        assert_eq!(
+            cli.empty().unwrap_err().to_string(),
+            "invalid argument \"--verbsoe\"\n\nDid you mean \"--verbose\", \"--version\"?"
+        );
+    }
+
+    #[test]
+    fn suggestion_threshold_auto() {
+        let suggest = |word: &str| -> Option<String> {
+            let mut cli = Cli::new()
+                .threshold_auto()
+                .parse(args(vec!["orbit", word]))
+                .save();
+            cli.check(Arg::flag("no-default-features")).unwrap();
+            cli.check(Arg::flag("lib")).unwrap();
+            match cli.empty().unwrap_err().context() {
+                ErrorContext::SuggestWord(_, w) => Some(w[0].clone()),
+                _ => None,
+            }
+        };
+        // long flags allow more edits than a fixed threshold
+        assert_eq!(
+            suggest("--no-defualt-feature"),
+            Some(String::from("--no-default-features"))
+        );
+        // short flags allow only a single edit
+        assert_eq!(suggest("--lob"), Some(String::from("--lib")));
+        assert_eq!(suggest("--dog"), None);
+
+        // subcommands scale by the length of each candidate
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "plan", "--fileset", "a"]))
+            .threshold_auto()
+            .parse(args(vec!["orbit", "instlal"]))
             .save();
-        let sets: Vec<String> = cli
-            .get_option_all(Optional::new("fileset"))
-            .unwrap()
-            .unwrap();
-        assert_eq!(sets, vec!["a"]);
-        // option not provided
-        let mut cli = Cli::new().parse(args(vec!["orbit", "plan"])).save();
-        let sets: Option<Vec<String>> = cli.get_option_all(Optional::new("fileset")).unwrap();
-        assert_eq!(sets, None);
+        match cli.select(&["new", "install"]).unwrap_err().context() {
+            ErrorContext::SuggestWord(_, w) => assert_eq!(w, &vec!["install"]),
+            _ => panic!("expected a suggestion"),
+        }
+        let mut cli = Cli::new()
+            .threshold_auto()
+            .parse(args(vec!["orbit", "nix"]))
+            .save();
+        cli.known_args
+            .push(ArgType::from(Arg::subcommand("command")));
+        assert!(
+            match cli.select(&["new", "install"]).unwrap_err().context() {
+                ErrorContext::SuggestWord(_, _) => false,
+                _ => true,
+            }
+        );
     }
 
     #[test]
-    fn match_command() {
+    fn diagnostics_are_reproducible() {
+        let report = |argv: Vec<&str>| -> String {
+            let mut cli = Cli::new()
+                .threshold(2)
+                .verify_determinism()
+                .parse(args(argv))
+                .save();
+            cli.check(Arg::flag("verbose")).unwrap();
+            cli.check(Arg::flag("version")).unwrap();
+            let _: Option<String> = cli.get(Arg::positional("command")).unwrap();
+            cli.empty().unwrap_err().to_string()
+        };
+        // each run iterates the lookup table in a different order, and every
+        // search is repeated in reverse
+        let argv = || {
+            vec![
+                "orbit",
+                "-z",
+                "--verbsoe",
+                "--lib",
+                "-q",
+                "--versoin",
+                "new",
+            ]
+        };
+        let expected = report(argv());
+        assert_eq!(
+            expected,
+            "invalid argument \"-z\" in \"orbit\"\n\nexpected one of: --verbose, --version"
+        );
+        for _ in 0..32 {
+            assert_eq!(report(argv()), expected);
+        }
+        for _ in 0..32 {
+            let mut cli = Cli::new()
+                .threshold(2)
+                .verify_determinism()
+                .parse(args(vec!["orbit", "--versio", "--verbos"]))
+                .save();
+            cli.check(Arg::flag("verbose")).unwrap();
+            cli.check(Arg::flag("version")).unwrap();
+            assert_eq!(
+                cli.empty().unwrap_err().to_string(),
+                "invalid argument \"--versio\"\n\nDid you mean \"--version\"?"
+            );
+        }
+    }
+
+    #[test]
+    fn check_switch_only() {
+        let mut cli = Cli::new().parse(args(vec!["cp", "-p"])).save();
+        assert_eq!(cli.check(Arg::switch_only('p')).unwrap(), true);
+        assert!(cli.empty().is_ok());
+
+        // the long form is not recognized
+        let mut cli = Cli::new().parse(args(vec!["cp", "--p"])).save();
+        assert_eq!(cli.check(Arg::switch_only('p')).unwrap(), false);
+        assert_eq!(cli.empty().unwrap_err().kind(), ErrorKind::UnexpectedArg);
+
+        let mut cli = Cli::new().parse(args(vec!["cp", "-pp"])).save();
+        let err = cli.check(Arg::switch_only('p')).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DuplicateOptions);
+        assert_eq!(err.to_string(), "argument \"-p\" can only be supplied once");
+    }
+
+    #[test]
+    fn check_flag_alias() {
+        let mut cli = Cli::new().parse(args(vec!["ls", "--dir"])).save();
+        assert_eq!(
+            cli.check(Arg::flag("directory").alias("dir")).unwrap(),
+            true
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["ls", "--directory"])).save();
+        assert_eq!(
+            cli.check(Arg::flag("directory").alias("dir")).unwrap(),
+            true
+        );
+
+        // both spellings refer to the same argument
+        let mut cli = Cli::new()
+            .parse(args(vec!["ls", "--directory", "--dir"]))
+            .save();
+        let err = cli.check(Arg::flag("directory").alias("dir")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DuplicateOptions);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["ls", "--out", "a", "-o", "b"]))
+            .save();
+        assert_eq!(
+            cli.get::<String>(Arg::option("output").alias("out").switch('o'))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::DuplicateOptions
+        );
+
+        // suggestions know about the alias
+        let mut cli = Cli::new()
+            .threshold(3)
+            .parse(args(vec!["ls", "--dri"]))
+            .save();
+        cli.check(Arg::flag("directory").alias("dir")).unwrap();
+        match cli.empty().unwrap_err().context() {
+            ErrorContext::SuggestWord(_, w) => assert_eq!(w, &vec!["--dir"]),
+            _ => panic!("expected a suggestion"),
+        }
+    }
+
+    #[test]
+    fn positionals_at_index() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["cp", "a.txt", "--force", "b.txt"]))
+            .save();
+        assert_eq!(cli.check(Arg::flag("force")).unwrap(), true);
+        assert_eq!(
+            cli.require_at::<String>(Arg::positional("dest"), 1)
+                .unwrap(),
+            "b.txt"
+        );
+        assert_eq!(
+            cli.require_at::<String>(Arg::positional("src"), 0).unwrap(),
+            "a.txt"
+        );
+        assert!(cli.empty().is_ok());
+
+        // mixing index-based and next-remaining requests
+        let mut cli = Cli::new().parse(args(vec!["cp", "1", "2", "3"])).save();
+        assert_eq!(cli.get_at::<u8>(Arg::positional("c"), 2).unwrap(), Some(3));
+        assert_eq!(cli.get_at::<u8>(Arg::positional("d"), 3).unwrap(), None);
+        assert_eq!(cli.require::<u8>(Arg::positional("a")).unwrap(), 1);
+        assert_eq!(cli.require_at::<u8>(Arg::positional("b"), 1).unwrap(), 2);
+
+        let mut cli = Cli::new().parse(args(vec!["cp", "a.txt"])).save();
+        assert_eq!(
+            cli.require_at::<String>(Arg::positional("dest"), 1)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::MissingPositional
+        );
+
+        // indices are relative to the subcommand
+        let mut cli = Cli::new()
+            .parse(args(vec!["orbit", "new", "gates", "lib"]))
+            .save();
+        assert_eq!(cli.select(&["new"]).unwrap(), "new");
+        assert_eq!(
+            cli.require_at::<String>(Arg::positional("path"), 1)
+                .unwrap(),
+            "lib"
+        );
+        assert_eq!(
+            cli.require_at::<String>(Arg::positional("name"), 0)
+                .unwrap(),
+            "gates"
+        );
+    }
+
+    #[test]
+    #[should_panic = "argument discovery is in an invalid order"]
+    fn structural_hazard_panics() {
+        let mut cli = Cli::new().parse(args(vec!["cp", "a", "--force"])).save();
+        let _: String = cli.require(Arg::positional("src")).unwrap();
+        let _ = cli.check(Arg::flag("force"));
+    }
+
+    #[test]
+    fn catch_structural_hazards() {
+        let mut cli = Cli::new()
+            .catch_structural_hazards()
+            .parse(args(vec!["cp", "a", "--force"]))
+            .save();
+        let _: String = cli.require(Arg::positional("src")).unwrap();
+        let err = cli.check(Arg::flag("force")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::StructuralHazard);
+        assert_eq!(
+            err.to_string(),
+            "structural hazard: argument discovery is in an invalid order: flags cannot be requested after positionals (arguments must be requested as flags, options, positionals, then subcommands)"
+        );
+        match err.context() {
+            ErrorContext::StructuralHazard(prev, next) => {
+                assert_eq!(prev, "positionals");
+                assert_eq!(next, "flags");
+            }
+            _ => panic!("expected a structural hazard"),
+        }
+    }
+
+    #[test]
+    fn unordered_discovery() {
+        let mut cli = Cli::new()
+            .unordered_discovery()
+            .parse(args(vec!["cp", "--force", "--out", "dst", "src"]))
+            .save();
+        assert_eq!(cli.check(Arg::flag("force")).unwrap(), true);
+        // the positional skips the value reserved for the option
+        assert_eq!(
+            cli.require::<String>(Arg::positional("src")).unwrap(),
+            "src"
+        );
+        assert_eq!(
+            cli.get::<String>(Arg::option("out")).unwrap(),
+            Some(String::from("dst"))
+        );
+        assert_eq!(cli.check(Arg::flag("verbose")).unwrap(), false);
+        cli.empty().unwrap();
+
+        let mut cli = Cli::new()
+            .unordered_discovery()
+            .parse(args(vec!["cp", "src", "-o", "dst"]))
+            .save();
+        assert_eq!(
+            cli.require::<String>(Arg::positional("src")).unwrap(),
+            "src"
+        );
+        assert_eq!(
+            cli.get::<String>(Arg::option("out").switch('o')).unwrap(),
+            Some(String::from("dst"))
+        );
+        cli.empty().unwrap();
+    }
+
+    #[test]
+    fn intermixed() {
+        let release = || Arg::flag("release");
+        for line in [
+            vec!["build", "app", "--release", "--out", "dst", "lib"],
+            vec!["build", "--release", "app", "lib", "--out=dst"],
+        ] {
+            let mut cli = Cli::new().intermixed().parse(args(line)).save();
+            cli.declare(release());
+            assert_eq!(
+                cli.require::<String>(Arg::positional("target")).unwrap(),
+                "app"
+            );
+            assert_eq!(cli.check(release()).unwrap(), true);
+            assert_eq!(
+                cli.get::<String>(Arg::option("out")).unwrap(),
+                Some(String::from("dst"))
+            );
+            assert_eq!(
+                cli.require::<String>(Arg::positional("extra")).unwrap(),
+                "lib"
+            );
+            cli.empty().unwrap();
+        }
+
+        // nothing is requested after the emptiness check
+        let mut cli = Cli::new()
+            .intermixed()
+            .catch_structural_hazards()
+            .parse(args(vec!["app"]))
+            .save();
+        cli.empty().unwrap();
+        assert_eq!(
+            cli.check(release()).unwrap_err().kind(),
+            ErrorKind::StructuralHazard
+        );
+    }
+
+    #[test]
+    fn posix_strict() {
+        let line = vec!["grep", "-i", "--max=2", "todo", "-v", "--", "-x"];
+        let mut cli = Cli::new().posix_strict().parse(args(line.clone())).save();
+        assert_eq!(cli.check_invariants(), Ok(()));
+        assert_eq!(
+            cli.check(Arg::flag("ignore-case").switch('i')).unwrap(),
+            true
+        );
+        assert_eq!(cli.check(Arg::flag("invert").switch('v')).unwrap(), false);
+        assert_eq!(cli.get::<usize>(Arg::option("max")).unwrap(), Some(2));
+        assert_eq!(
+            cli.get_all::<String>(Arg::positional("words")).unwrap(),
+            Some(vec![
+                String::from("todo"),
+                String::from("-v"),
+                String::from("--"),
+                String::from("-x")
+            ])
+        );
+        cli.empty().unwrap();
+
+        // flags are processed until the terminator by default
+        let mut cli = Cli::new().parse(args(line)).save();
+        assert_eq!(
+            cli.check(Arg::flag("ignore-case").switch('i')).unwrap(),
+            true
+        );
+        assert_eq!(cli.check(Arg::flag("invert").switch('v')).unwrap(), true);
+    }
+
+    #[test]
+    fn posix_strict_declared_options() {
+        let file = || Arg::option("file").switch('f');
+        let line = vec![
+            "tar", "-cf", "out.tar", "-v", "--file", "b.tar", "src", "-x",
+        ];
+        let mut cli = Cli::new()
+            .posix_strict()
+            .posix_option(file())
+            .parse(args(line.clone()))
+            .save();
+        assert_eq!(cli.check_invariants(), Ok(()));
+        assert_eq!(cli.check(Arg::flag("create").switch('c')).unwrap(), true);
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), true);
+        assert_eq!(
+            cli.get_all::<String>(file()).unwrap(),
+            Some(vec![String::from("out.tar"), String::from("b.tar")])
+        );
+        assert_eq!(
+            cli.get_all::<String>(Arg::positional("paths")).unwrap(),
+            Some(vec![String::from("src"), String::from("-x")])
+        );
+        cli.empty().unwrap();
+
+        // without the declaration, the separate value ends flag processing
+        let mut cli = Cli::new().posix_strict().parse(args(line)).save();
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), false);
+    }
+
+    #[test]
+    fn posix_strict_subcommands() {
+        let line = vec!["git", "-v", "commit", "--amend", "msg", "--all"];
+        let mut cli = Cli::new()
+            .posix_strict()
+            .posix_subcommand(Arg::subcommand("commit"))
+            .parse(args(line.clone()))
+            .save();
+        assert_eq!(cli.check_invariants(), Ok(()));
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), true);
+        assert_eq!(cli.check(Arg::flag("amend")).unwrap(), true);
+        assert_eq!(cli.check(Arg::flag("all")).unwrap(), false);
+        assert_eq!(
+            cli.require::<String>(Arg::positional("command")).unwrap(),
+            "commit"
+        );
+        assert_eq!(
+            cli.get_all::<String>(Arg::positional("words")).unwrap(),
+            Some(vec![String::from("msg"), String::from("--all")])
+        );
+
+        // without the declaration, the subcommand's flags are operands
+        let mut cli = Cli::new().posix_strict().parse(args(line)).save();
+        assert_eq!(cli.check(Arg::flag("amend")).unwrap(), false);
+    }
+
+    #[test]
+    fn paired_symbol() {
         let mut cli = Cli::new()
+            .paired_symbol('+')
             .parse(args(vec![
-                "orbit",
-                "get",
-                "rary.gates",
-                "--instance",
-                "--component",
+                "xterm",
+                "+sb",
+                "-fg",
+                "red",
+                "-sb",
+                "+b",
+                "--title=dev",
+                "+ls",
+                "-",
             ]))
             .save();
-        // successfully matched 'get' command
+        assert_eq!(cli.check_invariants(), Ok(()));
+        assert_eq!(cli.toggle(Arg::flag("sb")).unwrap(), Some(false));
+        assert_eq!(
+            cli.toggle(Arg::flag("bell").switch('b')).unwrap(),
+            Some(true)
+        );
+        assert_eq!(cli.toggle(Arg::flag("cursor")).unwrap(), None);
+        assert_eq!(
+            cli.get::<String>(Arg::option("fg")).unwrap(),
+            Some(String::from("red"))
+        );
+        assert_eq!(
+            cli.get::<String>(Arg::option("title")).unwrap(),
+            Some(String::from("dev"))
+        );
+        assert_eq!(
+            cli.collect_unknown().unwrap(),
+            vec![String::from("+ls"), String::from("-")]
+        );
+        assert!(cli.empty().is_ok());
+
+        // unknown flags are reported with the symbol they were written with
+        let mut cli = Cli::new()
+            .paired_symbol('+')
+            .threshold(2)
+            .parse(args(vec!["xterm", "+sbb"]))
+            .save();
+        assert_eq!(cli.toggle(Arg::flag("sb")).unwrap(), None);
+        assert_eq!(
+            cli.empty().unwrap_err().to_string(),
+            "invalid argument \"+sbb\"\n\nDid you mean \"+sb\"?"
+        );
+    }
+
+    #[test]
+    fn switch_symbol() {
+        let mut cli = Cli::new()
+            .switch_symbol('/')
+            .parse(args(vec!["app", "/vq", "//jobs=4", "-x", "//", "/rest"]))
+            .save();
+        assert_eq!(cli.check_invariants(), Ok(()));
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), true);
+        assert_eq!(cli.check(Arg::flag("quiet").switch('q')).unwrap(), true);
+        assert_eq!(cli.get::<usize>(Arg::option("jobs")).unwrap(), Some(4));
+        // the dash no longer begins a switch
+        assert_eq!(
+            cli.require::<String>(Arg::positional("path")).unwrap(),
+            "-x"
+        );
+        assert_eq!(cli.remainder().unwrap(), vec![String::from("/rest")]);
+        assert!(cli.empty().is_ok());
+
+        // flags are displayed with the symbol
+        let mut cli = Cli::new()
+            .switch_symbol('/')
+            .parse(args(vec!["app", "//jobs"]))
+            .save();
+        cli.help(Help::new()).unwrap();
+        assert_eq!(
+            cli.get::<usize>(Arg::option("jobs"))
+                .unwrap_err()
+                .to_string(),
+            "option \"//jobs <jobs>\" accepts one value but zero were supplied"
+        );
+        let mut cli = Cli::new()
+            .switch_symbol('/')
+            .threshold(2)
+            .parse(args(vec!["app", "//verbos"]))
+            .save();
+        assert_eq!(cli.check(Arg::flag("verbose")).unwrap(), false);
+        assert_eq!(
+            cli.empty().unwrap_err().to_string(),
+            "invalid argument \"//verbos\"\n\nDid you mean \"//verbose\"?"
+        );
+        let mut cli = Cli::new()
+            .switch_symbol('/')
+            .parse(args(vec!["app", "//"]))
+            .save();
+        cli.help(Help::new()).unwrap();
+        assert_eq!(
+            cli.after_terminator_one::<String>().unwrap_err().to_string(),
+            "expected one argument after \"//\" but zero were supplied\n\nFor more information, try \"//help\"."
+        );
+
+        let cli = Cli::new()
+            .switch_symbol('/')
+            .parse(args(vec!["app"]))
+            .save();
+        assert_eq!(
+            cli.reject(Arg::option("out"), "must be writable")
+                .to_string(),
+            "argument \"//out <out>\" is invalid: must be writable"
+        );
+
+        /// Command that rejects its output path during validation.
+        #[derive(Debug)]
+        struct Save;
+
+        impl Command for Save {
+            fn interpret(_: &mut Cli<Memory>) -> Result<Self> {
+                Ok(Save)
+            }
+
+            fn validate(self) -> Result<Self> {
+                Err(Error::custom_for(
+                    Arg::option("out"),
+                    std::io::Error::new(std::io::ErrorKind::PermissionDenied, "read-only"),
+                ))
+            }
+
+            fn execute(self) -> crate::proc::Result {
+                Ok(())
+            }
+        }
         assert_eq!(
-            cli.select(&["new", "get", "install", "edit"]).unwrap(),
-            "get".to_string()
+            Cli::new()
+                .switch_symbol('/')
+                .parse(args(vec!["app"]))
+                .build::<Save>()
+                .unwrap_err()
+                .to_string(),
+            "argument \"//out <out>\" is invalid: read-only"
         );
-
-        let mut cli = Cli::new()
-            .threshold(4)
-            .parse(args(vec![
-                "orbit",
-                "got",
-                "rary.gates",
-                "--instance",
-                "--component",
-            ]))
-            .save();
-        // suggest 'get' command
-        assert!(cli.select(&["new", "get", "install", "edit"]).is_err());
     }
 
     #[test]
-    #[should_panic = "requires positional argument"]
-    fn match_command_no_arg() {
+    fn rewind() {
         let mut cli = Cli::new()
             .parse(args(vec![
-                "orbit",
-                "got",
-                "rary.gates",
-                "--instance",
-                "--component",
+                "app",
+                "--config",
+                "dev.toml",
+                "--verbose",
+                "run",
+                "fast",
             ]))
             .save();
-        // cli has no positional arguments loaded to report as error... panic
-        assert!(cli.select(&["new", "get", "install", "edit"]).is_err());
-    }
+        // pre-scan for the configuration file
+        assert_eq!(cli.check(Arg::flag("verbose")).unwrap(), true);
+        assert_eq!(
+            cli.get::<String>(Arg::option("config")).unwrap(),
+            Some(String::from("dev.toml"))
+        );
+        assert_eq!(cli.select(&["run"]).unwrap(), "run");
 
-    #[test]
-    fn find_first_flag_left() {
-        let cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "--help",
-                "new",
-                "rary.gates",
-                "--vcs",
-                "git",
-            ]))
-            .save();
+        cli.rewind();
+        assert_eq!(cli.command_path(), &["app"]);
+        assert_eq!(cli.check(Arg::flag("verbose")).unwrap(), true);
         assert_eq!(
-            cli.find_first_flag_left(cli.tokens.len()),
-            Some(("help", 0))
+            cli.get::<String>(Arg::option("config")).unwrap(),
+            Some(String::from("dev.toml"))
+        );
+        assert_eq!(cli.select(&["run"]).unwrap(), "run");
+        assert_eq!(
+            cli.require::<String>(Arg::positional("mode")).unwrap(),
+            "fast"
         );
+        cli.empty().unwrap();
+    }
 
+    #[test]
+    fn program_name() {
         let cli = Cli::new()
-            .parse(args(vec!["orbit", "new", "rary.gates"]))
+            .parse(args(vec!["/usr/local/bin/myapp", "--version"]))
             .save();
-        assert_eq!(cli.find_first_flag_left(cli.tokens.len()), None);
+        assert_eq!(cli.program_name(), Some("myapp"));
 
         let cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "new",
-                "rary.gates",
-                "--vcs",
-                "git",
-                "--help",
-            ]))
+            .bin_name("app")
+            .parse(args(vec!["/usr/local/bin/myapp", "--version"]))
             .save();
-        assert_eq!(cli.find_first_flag_left(cli.tokens.len()), Some(("vcs", 2)));
+        assert_eq!(cli.program_name(), Some("app"));
+        assert_eq!(cli.command_path(), &["app"]);
 
-        let cli = Cli::new()
-            .parse(args(vec!["orbit", "new", "rary.gates", "-c=git", "--help"]))
-            .save();
-        assert_eq!(cli.find_first_flag_left(cli.tokens.len()), Some(("c", 2)));
+        let cli = Cli::new().parse(args(vec![])).save();
+        assert_eq!(cli.program_name(), None);
+    }
 
-        let cli = Cli::new()
-            .parse(args(vec!["orbit", "new", "rary.gates", "-c=git", "--help"]))
-            .save();
-        assert_eq!(cli.find_first_flag_left(1), None); // check before 'rary.gates' position
+    #[test]
+    fn reject_arg() {
+        let mut cli = Cli::new().parse(args(vec!["make", "--jobs", "0"])).save();
+        cli.help(Help::with("Usage: make [--jobs <n>]")).unwrap();
+        let jobs: u8 = cli.require(Arg::option("jobs").value("n")).unwrap();
+        assert_eq!(jobs, 0);
+        let err = cli.reject(Arg::option("jobs").value("n"), "must be at least 1");
+        assert_eq!(err.kind(), ErrorKind::CustomRule);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--jobs <n>\" is invalid: must be at least 1\n\nFor more information, try \"--help\"."
+        );
 
-        let cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "--unknown",
-                "new",
-                "rary.gates",
-                "-c=git",
-                "--help",
-            ]))
-            .save();
-        assert_eq!(cli.find_first_flag_left(1), Some(("unknown", 0))); // check before 'new' subcommand
+        let err = Error::custom_for(
+            Arg::positional("path"),
+            std::io::Error::new(std::io::ErrorKind::NotFound, "file not found"),
+        );
+        match err.context() {
+            ErrorContext::CustomArg(_, e) => assert_eq!(e.to_string(), "file not found"),
+            _ => panic!("expected a custom argument error"),
+        }
+        assert_eq!(
+            err.to_string(),
+            "argument \"<path>\" is invalid: file not found"
+        );
     }
 
     #[test]
-    fn processed_all_args() {
-        let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "--upgrade",
-                "new",
-                "rary.gates",
-                "--vcs",
-                "git",
-            ]))
-            .save();
-        // tokens are still in token stream
-        let _ = cli.check_flag(Flag::new("upgrade")).unwrap();
-        let _: Option<String> = cli.get_option(Optional::new("vcs")).unwrap();
-        let _: String = cli.require_positional(Positional::new("command")).unwrap();
-        let _: String = cli.require_positional(Positional::new("ip")).unwrap();
-        // no more tokens left in stream
-        assert_eq!(cli.empty().unwrap(), ());
-
-        let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "new", "rary.gates", symbol::FLAG]))
-            .save();
-        // removes only valid args/flags/opts
-        let _ = cli.check_flag(Flag::new("help")).unwrap();
-        let _: Option<String> = cli.get_option(Optional::new("vcs")).unwrap();
-        let _: String = cli.require_positional(Positional::new("command")).unwrap();
-        let _: String = cli.require_positional(Positional::new("ip")).unwrap();
-        // unexpected '--'
-        assert!(cli.empty().is_err());
+    fn render_error() {
+        let mut cli = Cli::new().parse(args(vec!["make", "--jobs", "0"])).save();
+        cli.help(Help::with("Usage: make [--jobs <n>]")).unwrap();
+        let err = cli.reject(Arg::option("jobs").value("n"), "must be at least 1");
 
-        let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "--help",
-                "new",
-                "rary.gates",
-                "--vcs",
-                "git",
-            ]))
-            .save();
-        // no tokens were removed (help will also raise error)
-        assert!(cli.empty().is_err());
+        // plain rendering has no colors regardless of global settings
+        assert_eq!(
+            err.render(&ErrorStyle::new()),
+            "argument \"--jobs <n>\" is invalid: must be at least 1\n\nFor more information, try \"--help\"."
+        );
 
-        let mut cli = Cli::new()
-            .parse(args(vec!["orbit", symbol::FLAG, "some", "extra", "words"]))
-            .save();
-        let _: Vec<String> = cli.remainder().unwrap();
-        // terminator removed as well as its arguments that were ignored
-        assert_eq!(cli.empty().unwrap(), ());
+        let style = ErrorStyle::new()
+            .prefix("error: ")
+            .suffix("!")
+            .uppercase()
+            .wrap(24);
+        assert_eq!(
+            err.render(&style),
+            "error: Argument \"--jobs\n<n>\" is invalid: must be\nat least 1\n\nFor more information,\ntry \"--help\".!"
+        );
     }
 
     #[test]
-    fn tokenizer() {
-        let cli = Cli::new().parse(args(vec![])).save();
-        assert_eq!(cli.tokens, vec![]);
+    #[cfg(feature = "color")]
+    fn render_error_color() {
+        let mut cli = Cli::new().parse(args(vec!["make", "--jobs", "0"])).save();
+        cli.help(Help::with("Usage: make [--jobs <n>]")).unwrap();
+        let err = cli.reject(Arg::option("jobs").value("n"), "must be at least 1");
 
-        let cli = Cli::new().parse(args(vec!["orbit"])).save();
-        assert_eq!(cli.tokens, vec![]);
+        assert_eq!(
+            err.render(&ErrorStyle::new().color(true)),
+            "argument \"\x1b[34m--jobs <n>\x1b[0m\" is invalid: must be at least 1\n\nFor more information, try \"\x1b[32m--help\x1b[0m\"."
+        );
+        // wrapping does not count color escape sequences
+        let style = ErrorStyle::new()
+            .prefix("error: ")
+            .suffix("!")
+            .uppercase()
+            .wrap(24);
+        assert_eq!(
+            err.render(&style.color(true)),
+            "error: Argument \"\x1b[34m--jobs\n<n>\x1b[0m\" is invalid: must be\nat least 1\n\nFor more information,\ntry \"\x1b[32m--help\x1b[0m\".!"
+        );
+    }
 
-        let cli = Cli::new().parse(args(vec!["orbit", "--help"])).save();
-        assert_eq!(cli.tokens, vec![Some(Token::Flag(0))]);
+    #[test]
+    #[cfg(not(feature = "color"))]
+    fn render_error_without_color() {
+        let cli = Cli::new().parse(args(vec!["make", "--jobs", "0"])).save();
+        let err = cli.reject(Arg::option("jobs").value("n"), "must be at least 1");
+        // styling passes through when colors are not compiled in
+        assert_eq!(
+            err.render(&ErrorStyle::new().color(true)),
+            err.render(&ErrorStyle::new())
+        );
+    }
 
-        let cli = Cli::new().parse(args(vec!["orbit", "--help", "-v"])).save();
+    #[test]
+    fn point_error_spans() {
+        let line = vec!["run", "--threads", "x"];
+        let mut cli = Cli::new().parse(args(line.clone())).save();
+        let err = cli
+            .get::<usize>(Arg::option("threads").value("n"))
+            .unwrap_err();
+        assert_eq!(err.span(), Some(&Span::new(2, 0, 1)));
         assert_eq!(
-            cli.tokens,
-            vec![Some(Token::Flag(0)), Some(Token::Switch(1, 'v'))],
+            err.render(&ErrorStyle::new().point_to(&line))
+                .ends_with("\n\n  run --threads x\n                ^"),
+            true
+        );
+        // errors do not point anywhere unless the command-line is given
+        assert_eq!(err.render(&ErrorStyle::new()), err.to_string());
+
+        // attached values only underline the value
+        let line = vec!["run", "--threads=abc"];
+        let mut cli = Cli::new().parse(args(line.clone())).save();
+        let err = cli
+            .get::<usize>(Arg::option("threads").value("n"))
+            .unwrap_err();
+        assert_eq!(err.span(), Some(&Span::new(1, 10, 13)));
+        assert_eq!(
+            err.render(&ErrorStyle::new().point_to(&line))
+                .ends_with("\n\n  run --threads=abc\n                ^^^"),
+            true
         );
 
-        let cli = Cli::new()
-            .parse(args(vec!["orbit", "new", "rary.gates"]))
-            .save();
+        // unexpected arguments point to the entire word
+        let line = vec!["run", "--verbose", "--quiet"];
+        let mut cli = Cli::new().parse(args(line.clone())).save();
+        cli.check(Arg::flag("verbose")).unwrap();
+        let err = cli.empty().unwrap_err();
+        assert_eq!(err.span(), Some(&Span::new(2, 0, 7)));
+    }
+
+    #[test]
+    #[cfg(feature = "color")]
+    fn color_per_processor() {
+        let colored = Cli::default().enable_color();
+        let plain = Cli::default().disable_color();
+        // the styling of one processor does not affect another
+        let _ = plain.parse(args(vec!["app"]));
         assert_eq!(
-            cli.tokens,
-            vec![
-                Some(Token::UnattachedArgument(0, "new".to_string())),
-                Some(Token::UnattachedArgument(1, "rary.gates".to_string())),
-            ],
+            colored
+                .options
+                .format_report(String::from("bad input"), String::new()),
+            "\x1b[1;31merror\x1b[0m: bad input"
+        );
+        let plain = Cli::default().disable_color().error_suffix(".");
+        let _ = Cli::default().enable_color().parse(args(vec!["app"]));
+        assert_eq!(
+            plain
+                .options
+                .format_report(String::from("bad input"), String::new()),
+            "error: bad input."
         );
+    }
 
-        let cli = Cli::new()
-            .parse(args(vec!["orbit", "--help", "-vh"]))
-            .save();
+    #[test]
+    fn check_eager_flag() {
+        let version = || Arg::flag("version").switch('V').eager("app 1.0.0");
+
+        let mut cli = Cli::new().parse(args(vec!["app", "-V", "--bad"])).save();
+        let err = cli.check(version()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Eager);
+        assert_eq!(err.code(), Exit::Success);
+        assert_eq!(err.to_string(), "app 1.0.0");
         assert_eq!(
-            cli.tokens,
-            vec![
-                Some(Token::Flag(0)),
-                Some(Token::Switch(1, 'v')),
-                Some(Token::Switch(1, 'h')),
-            ],
+            err.render(&ErrorStyle::new().prefix("error: ")),
+            "app 1.0.0"
         );
 
-        let cli = Cli::new()
-            .parse(args(vec!["orbit", "--help", "-vhc=10"]))
-            .save();
+        // not raising the flag continues interpretation
+        let mut cli = Cli::new().parse(args(vec!["app", "file.txt"])).save();
+        assert_eq!(cli.check(version()).unwrap(), false);
         assert_eq!(
-            cli.tokens,
-            vec![
-                Some(Token::Flag(0)),
-                Some(Token::Switch(1, 'v')),
-                Some(Token::Switch(1, 'h')),
-                Some(Token::Switch(1, 'c')),
-                Some(Token::AttachedArgument(1, "10".to_string())),
-            ],
+            cli.require::<String>(Arg::positional("file")).unwrap(),
+            "file.txt"
         );
 
-        // an attached argument can sneak in behind a terminator
-        let cli = Cli::new()
-            .parse(args(vec!["orbit", "--=value", "extra"]))
+        // help has priority over eager flags
+        let mut cli = Cli::new()
+            .parse(args(vec!["app", "--version", "--help"]))
+            .save();
+        cli.help(Help::with("Usage: app [--version]")).unwrap();
+        assert_eq!(cli.check(version()).unwrap_err().kind(), ErrorKind::Help);
+
+        // a declared eager flag has priority over arguments requested before it
+        let mut cli = Cli::new()
+            .eager(version())
+            .parse(args(vec!["app", "--jobs=x", "-V"]))
+            .save();
+        let err = cli.get::<usize>(Arg::option("jobs")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Eager);
+        assert_eq!(err.to_string(), "app 1.0.0");
+        let mut cli = Cli::new()
+            .eager(version())
+            .parse(args(vec!["app", "--version"]))
+            .save();
+        let err = cli.require::<String>(Arg::positional("file")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Eager);
+        assert_eq!(cli.empty().unwrap_err().kind(), ErrorKind::Eager);
+
+        // the first eager flag on the command-line is displayed
+        let license = || Arg::flag("license").eager("MIT");
+        let mut cli = Cli::new()
+            .eager(version())
+            .eager(license())
+            .parse(args(vec!["app", "--license", "-V"]))
+            .save();
+        assert_eq!(cli.check(version()).unwrap_err().to_string(), "MIT");
+
+        // help still has priority over a declared eager flag
+        let mut cli = Cli::new()
+            .eager(version())
+            .parse(args(vec!["app", "-V", "--help"]))
             .save();
+        cli.help(Help::with("Usage: app [--version]")).unwrap();
         assert_eq!(
-            cli.tokens,
-            vec![
-                Some(Token::Terminator(0)),
-                Some(Token::AttachedArgument(0, "value".to_string())),
-                Some(Token::Ignore(1, "extra".to_string())),
-            ]
+            cli.require::<String>(Arg::positional("file"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::Help
         );
+    }
 
-        // final boss
-        let cli = Cli::new().parse(args(vec![
-            "orbit",
-            "--help",
-            "-v",
-            "new",
-            "ip",
-            "--lib",
-            "--name=rary.gates",
-            "--help",
-            "-sci",
-            symbol::FLAG,
-            "--map",
-            "synthesis",
-            "-jto",
-        ]));
+    #[test]
+    fn after_terminator_one() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["exec", "pod", "--", "ls"]))
+            .save();
+        let _: String = cli.require(Arg::positional("pod")).unwrap();
+        assert_eq!(cli.after_terminator_one::<String>().unwrap(), "ls");
+        assert!(cli.empty().is_ok());
+
+        let mut cli = Cli::new().parse(args(vec!["exec", "--"])).save();
+        let err = cli.after_terminator_one::<String>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingPositional);
         assert_eq!(
-            cli.tokens,
-            vec![
-                Some(Token::Flag(0)),
-                Some(Token::Switch(1, 'v')),
-                Some(Token::UnattachedArgument(2, "new".to_string())),
-                Some(Token::UnattachedArgument(3, "ip".to_string())),
-                Some(Token::Flag(4)),
-                Some(Token::Flag(5)),
-                Some(Token::AttachedArgument(5, "rary.gates".to_string())),
-                Some(Token::Flag(6)),
-                Some(Token::Switch(7, 's')),
-                Some(Token::Switch(7, 'c')),
-                Some(Token::Switch(7, 'i')),
-                Some(Token::Terminator(8)),
-                Some(Token::Ignore(9, "--map".to_string())),
-                Some(Token::Ignore(10, "synthesis".to_string())),
-                Some(Token::Ignore(11, "-jto".to_string())),
-            ],
+            err.to_string(),
+            "expected one argument after \"--\" but zero were supplied"
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["exec", "--", "ls", "-l"]))
+            .save();
+        let err = cli.after_terminator_one::<String>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ExceedingMaxCount);
+        assert_eq!(
+            err.to_string(),
+            "expected one argument after \"--\" but 2 were supplied"
         );
+
+        let mut cli = Cli::new().parse(args(vec!["sleep", "--", "ten"])).save();
+        let err = cli.after_terminator_one::<u8>().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadType);
     }
 
     #[test]
-    fn find_flags_and_switches() {
+    fn help_categories() {
+        let help = || {
+            Help::with("Usage: app [options]")
+                .category("Options", "  --verbose  Print more output")
+                .hidden_category("Advanced", "  --cache  Set the cache directory")
+        };
+
+        let mut cli = Cli::new().parse(args(vec!["app", "--help"])).save();
+        cli.help(help()).unwrap();
+        assert_eq!(
+            cli.raise_help().unwrap_err().to_string(),
+            "Usage: app [options]\n\nOptions:\n  --verbose  Print more output"
+        );
+
         let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "--help",
-                "-v",
-                "new",
-                "ip",
-                "--lib",
-                "--name=rary.gates",
-                "--help",
-                "-sci",
-                "-i",
-                "--",
-                "--map",
-                "synthesis",
-                "-jto",
-            ]))
+            .parse(args(vec!["app", "--help", "--all"]))
             .save();
+        cli.help(help()).unwrap();
+        assert_eq!(
+            cli.raise_help().unwrap_err().to_string(),
+            "Usage: app [options]\n\nOptions:\n  --verbose  Print more output\n\nAdvanced:\n  --cache  Set the cache directory"
+        );
 
-        // detects 0
-        assert_eq!(cli.take_flag_locs("version"), vec![]);
-        // detects 1
-        assert_eq!(cli.take_flag_locs("lib"), vec![4]);
-        // detects multiple
-        assert_eq!(cli.take_flag_locs("help"), vec![0, 7]);
-        // flag was past terminator and marked as ignore
-        assert_eq!(cli.take_flag_locs("map"), vec![]);
-        // filters out arguments
-        assert_eq!(cli.take_flag_locs("rary.gates"), vec![]);
-
-        // detects 0
-        assert_eq!(cli.take_switch_locs(&'q'), vec![]);
-        // detects 1
-        assert_eq!(cli.take_switch_locs(&'v'), vec![1]);
-        // detects multiple
-        assert_eq!(cli.take_switch_locs(&'i'), vec![10, 11]);
-        // switch was past terminator and marked as ignore
-        assert_eq!(cli.take_switch_locs(&'j'), vec![]);
+        // the flag to show all categories is left alone when help is not raised
+        let mut cli = Cli::new().parse(args(vec!["app", "--all"])).save();
+        cli.help(help()).unwrap();
+        assert_eq!(cli.check(Arg::flag("all")).unwrap(), true);
     }
 
     #[test]
-    fn flags_in_map() {
-        let cli = Cli::new().parse(args(vec![
-            "orbit",
-            "--help",
-            "-v",
-            "new",
-            "ip",
-            "--lib",
-            "--name=rary.gates",
-            "--help",
-            "-sci",
-            symbol::FLAG,
-            "--map",
-            "synthesis",
-            "-jto",
-        ]));
-        let mut store = HashMap::<Tag<String>, Slot>::new();
-        // store long options
-        store.insert(
-            Tag::Flag("help".to_string()),
-            Slot {
-                pointers: vec![0, 7],
-                visited: false,
-            },
-        );
-        store.insert(
-            Tag::Flag("lib".to_string()),
-            Slot {
-                pointers: vec![4],
-                visited: false,
-            },
+    fn select_help_format() {
+        let help = || Help::with("Usage: app [options]").format_selector();
+
+        let mut cli = Cli::new().parse(args(vec!["app", "--help=man"])).save();
+        assert_eq!(cli.help(help()).unwrap(), true);
+        assert_eq!(
+            cli.raise_help().unwrap_err().to_string(),
+            ".TH \"APP\" 1\n.SH \"DESCRIPTION\"\n.nf\nUsage: app [options]\n.fi\n"
         );
-        store.insert(
-            Tag::Flag("name".to_string()),
-            Slot {
-                pointers: vec![5],
-                visited: false,
-            },
+
+        // manual pages are not wrapped to the width of the style
+        let err = cli.raise_help().unwrap_err();
+        assert_eq!(err.render(&ErrorStyle::new().wrap(10)), err.to_string());
+
+        let mut cli = Cli::new().parse(args(vec!["app", "-h=plain"])).save();
+        assert_eq!(cli.help(help()).unwrap(), true);
+        let err = cli.raise_help().unwrap_err();
+        assert_eq!(err.to_string(), "Usage: app [options]");
+        assert_eq!(
+            err.render(&ErrorStyle::new().wrap(10)),
+            "Usage: app\n[options]"
         );
-        // stores switches too
-        store.insert(
-            Tag::Switch("v".to_string()),
-            Slot {
-                pointers: vec![1],
-                visited: false,
-            },
+
+        let mut cli = Cli::new().parse(args(vec!["app", "--help=pdf"])).save();
+        let err = cli.help(help()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadType);
+        assert_eq!(err.span().map(|s| s.index()), Some(1));
+
+        // the format is only selectable when allowed
+        let mut cli = Cli::new().parse(args(vec!["app", "--help=man"])).save();
+        assert_eq!(
+            cli.help(Help::with("Usage: app")).unwrap_err().kind(),
+            ErrorKind::UnexpectedValue
         );
-        store.insert(
-            Tag::Switch("s".to_string()),
-            Slot {
-                pointers: vec![8],
-                visited: false,
-            },
+    }
+
+    #[test]
+    fn push_and_pop_help() {
+        let mut cli = Cli::new()
+            .unordered_discovery()
+            .parse(args(vec!["app"]))
+            .save();
+        cli.help(Help::with("Usage: app <command>").usage("app <command>"))
+            .unwrap();
+        cli.push_help(
+            Help::with("Usage: app build <target>")
+                .usage("app build <target>")
+                .inherit(HelpInheritance::Usage),
+        )
+        .unwrap();
+        // errors reference the nested command's manual
+        assert_eq!(
+            cli.require::<String>(Arg::option("target"))
+                .unwrap_err()
+                .to_string(),
+            "missing required option \"--target <target>\"\n\nusage: app <command>\n       app build <target>\n\nFor more information, try \"--help\"."
         );
-        store.insert(
-            Tag::Switch("c".to_string()),
-            Slot {
-                pointers: vec![9],
-                visited: false,
-            },
+        assert_eq!(
+            cli.pop_help().unwrap().get_text(),
+            "Usage: app build <target>"
         );
-        store.insert(
-            Tag::Switch("i".to_string()),
-            Slot {
-                pointers: vec![10],
-                visited: false,
-            },
+        assert_eq!(
+            cli.require::<String>(Arg::option("profile"))
+                .unwrap_err()
+                .to_string(),
+            "missing required option \"--profile <profile>\"\n\nusage: app <command>\n\nFor more information, try \"--help\"."
         );
-        assert_eq!(cli.store, store);
+        assert_eq!(cli.pop_help().unwrap().get_text(), "Usage: app <command>");
+        assert_eq!(cli.pop_help(), None);
     }
 
     #[test]
-    fn take_unattached_args() {
+    fn cast_error_example() {
         let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit",
-                "--help",
-                "-v",
-                "new",
-                "ip",
-                "--lib",
-                "--name=rary.gates",
-                "--help",
-                "-scii",
-                "get",
-                symbol::FLAG,
-                "--map",
-                "synthesis",
-                "-jto",
-            ]))
+            .parse(args(vec!["log", "--when", "yesterday"]))
             .save();
+        let err = cli
+            .get::<u32>(Arg::option("when").example("--when 20240101"))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadType);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--when <when>\" failed to process value \"yesterday\": invalid digit found in string\n\nexample: --when 20240101"
+        );
 
-        assert_eq!(cli.next_uarg().unwrap(), "new".to_string());
-        assert_eq!(cli.next_uarg().unwrap(), "ip".to_string());
-        assert_eq!(cli.next_uarg().unwrap(), "get".to_string());
-        assert_eq!(cli.next_uarg(), None);
+        let mut cli = Cli::new().parse(args(vec!["sleep", "ten"])).save();
+        let err = cli
+            .require::<u8>(Arg::positional("secs").example("sleep 10"))
+            .unwrap_err();
+        assert!(err.to_string().ends_with("\n\nexample: sleep 10"));
     }
 
     #[test]
-    fn take_remainder_args() {
+    fn require_unless() {
+        let unless = || [Arg::flag("list").switch('l'), Arg::flag("version")];
+
+        let mut cli = Cli::new().parse(args(vec!["copy", "a.txt"])).save();
+        assert_eq!(cli.check(Arg::flag("list").switch('l')).unwrap(), false);
+        let src: Option<String> = cli
+            .require_unless(Arg::positional("src"), &unless())
+            .unwrap();
+        assert_eq!(src, Some(String::from("a.txt")));
+
+        // the flags are found even after they have been checked
+        let mut cli = Cli::new().parse(args(vec!["copy", "-l"])).save();
+        assert_eq!(cli.check(Arg::flag("list").switch('l')).unwrap(), true);
+        let src: Option<String> = cli
+            .require_unless(Arg::positional("src"), &unless())
+            .unwrap();
+        assert_eq!(src, None);
+        assert!(cli.empty().is_ok());
+
+        let mut cli = Cli::new().parse(args(vec!["copy"])).save();
+        let err = cli
+            .require_unless::<String>(Arg::positional("src"), &unless())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingPositional);
+        assert_eq!(
+            err.to_string(),
+            "missing positional argument \"<src>\" (required unless \"--list\" or \"--version\" is supplied)"
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["copy"])).save();
+        let err = cli
+            .require_unless::<String>(Arg::option("dest"), &unless())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingOption);
+    }
+
+    #[test]
+    fn resource_limits() {
+        let limited = || Cli::new().max_tokens(5).max_arg_len(8).max_value_len(3);
+        let mut cli = limited()
+            .parse(args(vec!["app", "-vq", "--id=abc", "run"]))
+            .save();
+        assert_eq!(cli.check(Arg::flag("verbose").switch('v')).unwrap(), true);
+
+        let err = limited()
+            .parse(args(vec!["app", "-vqx", "--id=abc", "run"]))
+            .save()
+            .check(Arg::flag("verbose").switch('v'))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ExceededLimit);
+        assert_eq!(
+            err.to_string(),
+            "exceeded resource limit: the command-line has 6 tokens but at most 5 are allowed"
+        );
+        // the arguments after the one that crosses the limit are not split
+        let err = Cli::new()
+            .max_tokens(2)
+            .parse(args(vec!["app", "-abc", "-def", "-ghi"]))
+            .save()
+            .empty()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "exceeded resource limit: the command-line has 3 tokens but at most 2 are allowed"
+        );
+        let mut cli = limited().parse(args(vec!["app", "run", "-i=abcd"])).save();
+        assert_eq!(
+            cli.empty().unwrap_err().to_string(),
+            "exceeded resource limit: the value attached to argument 2 has 4 bytes but at most 3 are allowed"
+        );
+        // the limit persists after rewinding
+        cli.rewind();
+        assert!(cli.get::<String>(Arg::option("id")).is_err());
+        let err = limited()
+            .parse(args(vec!["app", "--verbose"]))
+            .save()
+            .require::<String>(Arg::positional("name"))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "exceeded resource limit: argument 1 has 9 bytes but at most 8 are allowed"
+        );
+        // oversized arguments are not copied for processing
+        let cli = limited().parse(args(vec!["app", "run", "--verbose"]));
+        assert_eq!(cli.args.len(), 0);
+        assert_eq!(cli.raw_args.len(), 3);
+    }
+
+    #[test]
+    fn preprocess_args() {
         let mut cli = Cli::new()
+            .preprocess(|a| match a.strip_prefix('+') {
+                Some(t) => vec![String::from("--toolchain"), t.to_string()],
+                None => vec![a],
+            })
+            .preprocess(|a| match a.as_str() {
+                "-legacy" => vec![String::from("--compat")],
+                "--ignored" => vec![],
+                _ => vec![a],
+            })
             .parse(args(vec![
-                "orbit",
-                "--help",
-                "-v",
-                "new",
-                "ip",
-                "--lib",
-                "--name=rary.gates",
-                "--help",
-                "-scii",
-                "get",
-                symbol::FLAG,
-                "--map",
-                "synthesis",
-                "-jto",
+                "+cargo",
+                "+nightly",
+                "-legacy",
+                "--ignored",
+                "build",
             ]))
             .save();
-        assert_eq!(cli.remainder().unwrap(), vec!["--map", "synthesis", "-jto"]);
-        // the items were removed from the token stream
-        assert_eq!(cli.remainder().unwrap(), Vec::<String>::new());
+        // the program's name is not rewritten
+        assert_eq!(cli.program_name(), Some("+cargo"));
+        assert_eq!(cli.check(Arg::flag("compat")).unwrap(), true);
+        assert_eq!(
+            cli.require::<String>(Arg::option("toolchain")).unwrap(),
+            "nightly"
+        );
+        assert_eq!(
+            cli.require::<String>(Arg::positional("command")).unwrap(),
+            "build"
+        );
+        assert!(cli.empty().is_ok());
+        // the raw arguments are left untouched
+        assert_eq!(
+            cli.raw_args(),
+            &["+cargo", "+nightly", "-legacy", "--ignored", "build"]
+        );
+    }
+
+    #[test]
+    fn read_value_from_file() {
+        let path = std::env::temp_dir().join(format!("cliproc-msg-{}.txt", std::process::id()));
+        std::fs::write(&path, "fix the parser\n\nlong description").unwrap();
+        let file_ref = format!("@{}", path.display());
 
-        // an attached argument can sneak in behind a terminator (handle in a result fn)
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--=value", "extra"]))
+            .parse(args(vec!["commit", "--message", &file_ref, &file_ref]))
             .save();
-        assert!(cli.remainder().is_err());
+        let message: String = cli
+            .require(Arg::option("message").allow_file_ref())
+            .unwrap();
+        assert_eq!(message, "fix the parser\n\nlong description");
+        // values are taken literally unless file references are allowed
+        let path_arg: String = cli.require(Arg::positional("path")).unwrap();
+        assert_eq!(path_arg, file_ref);
+        std::fs::remove_file(&path).unwrap();
 
-        let mut cli = Cli::new().parse(args(vec!["orbit", "--help"])).save();
-        // the terminator was never found
-        assert_eq!(cli.remainder().unwrap(), Vec::<String>::new());
+        let mut cli = Cli::new()
+            .parse(args(vec!["commit", "-m", "@missing.txt"]))
+            .save();
+        let err = cli
+            .get::<String>(Arg::option("message").switch('m').allow_file_ref())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnreadableFile);
+        assert!(err
+            .to_string()
+            .starts_with("argument \"--message <message>\" failed to read file \"missing.txt\": "));
+        assert!(std::error::Error::source(&err).is_some());
     }
 
     #[test]
-    fn pull_values_from_flags() {
-        let mut cli = Cli::new().parse(args(vec!["orbit", "--help"])).save();
-        let locs = cli.take_flag_locs("help");
-        assert_eq!(cli.pull_flag(locs, false), vec![None]);
-        assert_eq!(cli.tokens.get(0), Some(&None));
-
+    fn resolve_relative_paths() {
+        let root = std::env::temp_dir().join(format!("cliproc-root-{}", std::process::id()));
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        // an absolute path on any platform is kept as supplied
+        let abs = std::env::temp_dir().join("out");
         let mut cli = Cli::new()
             .parse(args(vec![
-                "orbit",
-                "--name",
-                "gates",
-                "arg",
-                "--lib",
-                "new",
-                "--name=gates2",
-                "--opt=1",
-                "--opt",
-                "--help",
+                "build",
+                "--out",
+                abs.to_str().unwrap(),
+                "src/../lib.rs",
+                "./src",
             ]))
             .save();
-        let locs = cli.take_flag_locs("lib");
-        assert_eq!(cli.pull_flag(locs, false), vec![None]);
-        // token no longer exists
-        assert_eq!(cli.tokens.get(3), Some(&None));
+        let out: PathBuf = cli.require(Arg::option("out").relative_to(&root)).unwrap();
+        assert_eq!(out, abs);
+        let file: PathBuf = cli
+            .require(
+                Arg::positional("file")
+                    .relative_to(&root)
+                    .resolve_paths(PathResolution::Normalize),
+            )
+            .unwrap();
+        assert_eq!(file, root.join("lib.rs"));
+        let dir: PathBuf = cli
+            .require(
+                Arg::positional("dir")
+                    .relative_to(&root)
+                    .resolve_paths(PathResolution::Canonicalize),
+            )
+            .unwrap();
+        assert_eq!(dir, root.join("src").canonicalize().unwrap());
+
+        let mut cli = Cli::new().parse(args(vec!["build", "missing"])).save();
+        let err = cli
+            .require::<PathBuf>(
+                Arg::positional("dir")
+                    .relative_to(&root)
+                    .resolve_paths(PathResolution::Canonicalize),
+            )
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnresolvedPath);
+        assert!(err
+            .to_string()
+            .starts_with("argument \"<dir>\" failed to resolve path \"missing\": "));
+        std::fs::remove_dir_all(&root).unwrap();
+    }
 
-        // gets strings and removes both instances of flag from token stream
-        let locs = cli.take_flag_locs("name");
+    #[test]
+    fn missing_arg_usage() {
+        let help = || Help::with("Copy files.").usage("copy [--dest <dir>] <src>");
+        let mut cli = Cli::new().parse(args(vec!["copy"])).save();
+        cli.help(help()).unwrap();
+        let err = cli.require::<String>(Arg::positional("src")).unwrap_err();
         assert_eq!(
-            cli.pull_flag(locs, true),
-            vec![Some("gates".to_string()), Some("gates2".to_string())]
+            err.to_string(),
+            "missing positional argument \"<src>\"\n\nusage: copy [--dest <dir>] <src>\n\nFor more information, try \"--help\"."
         );
-        assert_eq!(cli.tokens.get(0), Some(&None));
-        assert_eq!(cli.tokens.get(5), Some(&None));
 
-        let locs = cli.take_flag_locs("opt");
-        assert_eq!(cli.pull_flag(locs, true), vec![Some("1".to_string()), None]);
+        let mut cli = Cli::new().parse(args(vec!["copy"])).save();
+        cli.help(help()).unwrap();
+        let err = cli.require::<String>(Arg::option("dest")).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "missing required option \"--dest <dest>\"\n\nusage: copy [--dest <dir>] <src>\n\nFor more information, try \"--help\"."
+        );
 
-        // gets switches as well from the store
+        // the usage line is not part of the help text
+        assert_eq!(help().get_text(), "Copy files.");
+    }
+
+    #[test]
+    fn arg_actions() {
         let mut cli = Cli::new()
             .parse(args(vec![
-                "orbit",
-                "--name",
-                "gates",
-                "-sicn",
-                "dut",
-                "new",
-                "-vl=direct",
-                "--help",
-                "-l",
-                "-m",
-                "install",
+                "build",
+                "-v",
+                "--verbose",
+                "--force",
+                "--lib",
+                "a",
+                "--lib",
+                "b",
+                "main.rs",
             ]))
             .save();
-        let locs = cli.take_switch_locs(&'l');
         assert_eq!(
-            cli.pull_flag(locs, true),
-            vec![Some("direct".to_string()), None]
+            cli.count(Arg::flag("verbose").switch('v').action(Action::Count))
+                .unwrap(),
+            2
+        );
+        assert_eq!(cli.count(Arg::flag("force")).unwrap(), 1);
+        assert_eq!(
+            cli.count(Arg::flag("quiet").action(Action::SetTrue))
+                .unwrap(),
+            0
+        );
+        assert_eq!(
+            cli.values::<String>(Arg::option("lib").action(Action::Append))
+                .unwrap(),
+            vec!["a", "b"]
+        );
+        assert_eq!(
+            cli.values::<String>(Arg::option("target")).unwrap(),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            cli.values::<String>(Arg::positional("src")).unwrap(),
+            vec!["main.rs"]
+        );
+
+        // setting true fails on multiple occurrences like check
+        let mut cli = Cli::new().parse(args(vec!["build", "-v", "-v"])).save();
+        assert_eq!(
+            cli.count(Arg::flag("verbose").switch('v'))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::DuplicateOptions
+        );
+    }
+
+    #[test]
+    fn arg_action_mismatch() {
+        let mut cli = Cli::new().parse(args(vec!["build"])).save();
+        let err = cli
+            .count(Arg::flag("verbose").action(Action::Append))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnsupportedAction);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--verbose\" cannot be processed with the action Append"
+        );
+        assert_eq!(
+            cli.values::<String>(Arg::option("lib").action(Action::Count))
+                .unwrap_err()
+                .to_string(),
+            "argument \"--lib <lib>\" cannot be processed with the action Count"
         );
-        assert_eq!(cli.tokens.get(9), Some(&None));
-        assert_eq!(cli.tokens.get(12), Some(&None));
-        let locs = cli.take_switch_locs(&'s');
-        assert_eq!(cli.pull_flag(locs, true), vec![None]);
-        let locs = cli.take_switch_locs(&'v');
-        assert_eq!(cli.pull_flag(locs, true), vec![None]);
-        let locs = cli.take_switch_locs(&'i');
-        assert_eq!(cli.pull_flag(locs, true), vec![None]);
-        let locs = cli.take_switch_locs(&'c');
-        assert_eq!(cli.pull_flag(locs, false), vec![None]);
-        let locs = cli.take_switch_locs(&'m');
-        assert_eq!(cli.pull_flag(locs, false), vec![None]);
     }
 
     #[test]
-    fn check_flag() {
-        let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--help", "--verbose", "get"]))
-            .save();
-        assert_eq!(cli.check_flag(Flag::new("help")).unwrap(), true);
-        assert_eq!(cli.check_flag(Flag::new("verbose")).unwrap(), true);
-        assert_eq!(cli.check_flag(Flag::new("version")).unwrap(), false);
+    fn value_range() {
+        let threads = || Arg::option("threads").range(1..=256);
+
+        let mut cli = Cli::new().parse(args(vec!["run", "--threads", "8"])).save();
+        assert_eq!(cli.get::<u16>(threads()).unwrap(), Some(8));
+
+        let mut cli = Cli::new().parse(args(vec!["run", "--threads", "0"])).save();
+        let err = cli.get::<u16>(threads()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OutsideRange);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--threads <threads>\" received value \"0\" but must be between 1 and 256"
+        );
 
+        // values that are not numbers are reported by their type
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--upgrade", "-u"]))
+            .parse(args(vec!["run", "--threads", "many"]))
             .save();
         assert_eq!(
-            cli.check_flag(Flag::new("upgrade").switch('u'))
+            cli.get::<u16>(threads()).unwrap_err().kind(),
+            ErrorKind::BadType
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["scale", "1.5"])).save();
+        let err = cli
+            .require::<f32>(Arg::positional("factor").range(0.0..1.0))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "argument \"<factor>\" received value \"1.5\" but must be at least 0 and less than 1"
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["nice", "--level=-5"])).save();
+        assert_eq!(
+            cli.get::<i8>(Arg::option("level").range(-20..)).unwrap(),
+            Some(-5)
+        );
+        let mut cli = Cli::new().parse(args(vec!["nice", "--level=-21"])).save();
+        assert_eq!(
+            cli.get::<i8>(Arg::option("level").range(-20..))
                 .unwrap_err()
-                .kind(),
-            ErrorKind::DuplicateOptions
+                .to_string(),
+            "argument \"--level <level>\" received value \"-21\" but must be at least -20"
         );
 
+        // bounds that are not numbers are compared as their own type
+        let since =
+            || Arg::option("since").range(String::from("2024-01-01")..String::from("2025-01-01"));
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--verbose", "--verbose", "--version=9"]))
+            .parse(args(vec!["log", "--since=2024-06-30"]))
             .save();
         assert_eq!(
-            cli.check_flag(Flag::new("verbose")).unwrap_err().kind(),
-            ErrorKind::DuplicateOptions
+            cli.get::<String>(since()).unwrap(),
+            Some(String::from("2024-06-30"))
         );
+        let mut cli = Cli::new()
+            .parse(args(vec!["log", "--since=2030-01-01"]))
+            .save();
         assert_eq!(
-            cli.check_flag(Flag::new("version")).unwrap_err().kind(),
-            ErrorKind::UnexpectedValue
+            cli.get::<String>(since()).unwrap_err().kind(),
+            ErrorKind::OutsideRange
+        );
+
+        // a value that cannot be parsed as the bounds' type is rejected
+        let mut cli = Cli::new().parse(args(vec!["run", "--jobs=0x10"])).save();
+        let err = cli
+            .get::<String>(Arg::option("jobs").range(1..=8))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadType);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--jobs <jobs>\" failed to process value \"0x10\": invalid digit found in string"
         );
     }
 
     #[test]
-    fn check_positional() {
+    fn expected_words() {
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "new", "rary.gates"]))
+            .threshold(0)
+            .parse(args(vec!["app", "--fast"]))
             .save();
+        cli.check(Arg::flag("verbose")).unwrap();
+        cli.get::<String>(Arg::option("out").alias("output"))
+            .unwrap();
         assert_eq!(
-            cli.get_positional::<String>(Positional::new("command"))
-                .unwrap(),
-            Some("new".to_string())
-        );
-        assert_eq!(
-            cli.get_positional::<String>(Positional::new("ip")).unwrap(),
-            Some("rary.gates".to_string())
+            cli.empty().unwrap_err().to_string(),
+            "invalid argument \"--fast\" in \"app\"\n\nexpected one of: --verbose, --out, --output"
         );
+
+        // the list is capped with the number of words left out
+        let mut cli = Cli::new()
+            .threshold(0)
+            .parse(args(vec!["app", "zip"]))
+            .save();
+        cli.known_args
+            .push(ArgType::from(Arg::subcommand("command")));
+        let bank = ["add", "build", "check", "doc", "fmt", "run", "test"];
         assert_eq!(
-            cli.get_positional::<i32>(Positional::new("path")).unwrap(),
-            None
+            cli.select(&bank).unwrap_err().to_string(),
+            "invalid subcommand \"zip\" for \"<command>\" in \"app\"\n\nexpected one of: add, build, check, doc, fmt … and 2 more"
         );
     }
 
     #[test]
-    fn check_option() {
+    fn check_color() {
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "command", "--rate", "10"]))
+            .disable_color()
+            .parse(args(vec!["app", "--color", "always"]))
             .save();
-        assert_eq!(cli.get_option(Optional::new("rate")).unwrap(), Some(10));
+        assert_eq!(cli.check_color().unwrap(), ColorChoice::Always);
+        assert_eq!(cli.options.color_mode, ColorMode::On);
+        assert_eq!(ColorChoice::Always.is_enabled(), true);
+
+        // the builder's coloring is kept when the option is not supplied
+        let mut cli = Cli::new().disable_color().parse(args(vec!["app"])).save();
+        assert_eq!(cli.check_color().unwrap(), ColorChoice::Never);
 
         let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit", "--flag", "--rate=9", "command", "-r", "14",
-            ]))
+            .parse(args(vec!["app", "--color=sometimes"]))
             .save();
+        let err = cli.check_color().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::BadType);
         assert_eq!(
-            cli.get_option::<i32>(Optional::new("rate").switch('r'))
-                .unwrap_err()
-                .kind(),
+            err.to_string(),
+            "argument \"--color <when>\" failed to process value \"sometimes\": expected one of \"always\", \"never\", or \"auto\"\n\nexample: --color never"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn check_yes() {
+        let mut cli = Cli::new().parse(args(vec!["app", "-y"])).save();
+        let confirm = cli.check_yes().unwrap();
+        assert_eq!(confirm.is_assumed(), true);
+        assert_eq!(confirm.ask("Delete 42 files?").unwrap(), true);
+
+        let mut cli = Cli::new().parse(args(vec!["app"])).save();
+        assert_eq!(cli.check_yes().unwrap().is_assumed(), false);
+
+        let mut cli = Cli::new().parse(args(vec!["app", "--yes", "-y"])).save();
+        assert_eq!(
+            cli.check_yes().unwrap_err().kind(),
             ErrorKind::DuplicateOptions
         );
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn status_follows_verbosity() {
+        let mut cli = Cli::new().parse(args(vec!["app", "-q"])).save();
+        assert_eq!(cli.status().is_quiet(), false);
+        cli.check_verbosity().unwrap();
+        assert_eq!(cli.status().is_quiet(), true);
+
+        let mut cli = Cli::new().parse(args(vec!["app", "-v"])).save();
+        cli.check_verbosity().unwrap();
+        assert_eq!(cli.status().is_verbose(), true);
+    }
+
+    #[test]
+    fn check_output() {
+        struct Report;
+
+        impl Render for Report {
+            fn render(&self, format: OutputFormat) -> String {
+                match format {
+                    OutputFormat::Json => String::from("[]"),
+                    _ => String::from("(empty)"),
+                }
+            }
+
+            fn formats() -> &'static [OutputFormat] {
+                &[OutputFormat::Table, OutputFormat::Json]
+            }
+        }
 
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--flag", "-r", "14"]))
+            .parse(args(vec!["app", "--output", "json"]))
             .save();
+        let format = cli.check_output::<Report>().unwrap();
+        assert_eq!(format, OutputFormat::Json);
+        assert_eq!(Report.render(format), "[]");
+
+        let mut cli = Cli::new().parse(args(vec!["app"])).save();
+        assert_eq!(cli.check_output::<Report>().unwrap(), OutputFormat::Table);
+
+        // a known format must also be supported by the output
+        for word in ["yaml", "xml"] {
+            let mut cli = Cli::new().parse(args(vec!["app", "--output", word])).save();
+            let err = cli.check_output::<Report>().unwrap_err();
+            assert_eq!(err.kind(), ErrorKind::BadType);
+            assert_eq!(
+                err.to_string(),
+                format!("argument \"--output <format>\" failed to process value \"{}\": supported formats are \"table\", \"json\"", word)
+            );
+        }
+    }
+
+    #[test]
+    fn check_verbosity() {
+        let mut cli = Cli::new().parse(args(vec!["app"])).save();
+        assert_eq!(cli.check_verbosity().unwrap(), Verbosity::Normal);
+
+        let mut cli = Cli::new().parse(args(vec!["app", "-q"])).save();
+        assert_eq!(cli.check_verbosity().unwrap(), Verbosity::Quiet);
+
+        let mut cli = Cli::new().parse(args(vec!["app", "--verbose"])).save();
+        assert_eq!(cli.check_verbosity().unwrap(), Verbosity::Verbose);
+
+        let mut cli = Cli::new().parse(args(vec!["app", "-vv"])).save();
+        assert_eq!(cli.check_verbosity().unwrap(), Verbosity::Debug);
+
+        let mut cli = Cli::new().parse(args(vec!["app", "--quiet", "-v"])).save();
         assert_eq!(
-            cli.get_option(Optional::new("rate").switch('r')).unwrap(),
-            Some(14)
+            cli.check_verbosity().unwrap_err().kind(),
+            ErrorKind::CustomRule
         );
 
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--flag", "--rate", "--verbose"]))
+            .switch_symbol('/')
+            .parse(args(vec!["app", "/q", "//verbose"]))
             .save();
         assert_eq!(
-            cli.get_option::<i32>(Optional::new("rate"))
+            cli.check_verbosity().unwrap_err().to_string(),
+            "argument \"//quiet\" is invalid: cannot be used with \"//verbose\""
+        );
+    }
+
+    #[test]
+    fn unstable_args() {
+        let resolver = || Arg::flag("new-resolver").unstable("CLIPROC_TEST_UNSTABLE_ARGS");
+        let mut cli = Cli::new().parse(args(vec!["app", "--new-resolver"])).save();
+        let err = cli.check(resolver()).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::UnstableArg);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--new-resolver\" is experimental\n\nSet the environment variable \"CLIPROC_TEST_UNSTABLE_ARGS\" to use it"
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["app", "--jobs=4"])).save();
+        assert_eq!(cli.check_unstable().unwrap(), false);
+        assert_eq!(cli.check(resolver()).unwrap(), false);
+        assert_eq!(
+            cli.get::<u8>(Arg::option("jobs").unstable("CLIPROC_TEST_UNSTABLE_ARGS"))
                 .unwrap_err()
-                .kind(),
-            ErrorKind::ExpectingValue
+                .to_string(),
+            "argument \"--jobs <jobs>\" is experimental\n\nSet the environment variable \"CLIPROC_TEST_UNSTABLE_ARGS\" or supply \"--enable-unstable\" to use it"
         );
 
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--flag", "--rate", "five", "--verbose"]))
+            .parse(args(vec!["app", "--new-resolver", "--enable-unstable"]))
             .save();
-        assert!(cli.get_option::<i32>(Optional::new("rate")).is_err());
+        assert_eq!(cli.check_unstable().unwrap(), true);
+        assert_eq!(cli.check(resolver()).unwrap(), true);
+
+        std::env::set_var("CLIPROC_TEST_UNSTABLE_ARGS", "1");
+        let mut cli = Cli::new().parse(args(vec!["app", "--new-resolver"])).save();
+        assert_eq!(cli.check(resolver()).unwrap(), true);
+        std::env::remove_var("CLIPROC_TEST_UNSTABLE_ARGS");
     }
 
     #[test]
-    fn take_token_str() {
-        let t = Token::UnattachedArgument(0, "get".to_string());
-        // consumes token and returns its internal string
-        assert_eq!(t.take_str(), "get");
+    fn check_level() {
+        let level = |cli: &mut Cli<Memory>| {
+            cli.check_level(
+                Arg::flag("verbose").switch('v'),
+                Arg::flag("quiet").switch('q'),
+                -1..=2,
+            )
+        };
+        let mut cli = Cli::new().parse(args(vec!["app"])).save();
+        assert_eq!(level(&mut cli).unwrap(), 0);
 
-        let t = Token::AttachedArgument(1, "rary.gates".to_string());
-        assert_eq!(t.take_str(), "rary.gates");
+        let mut cli = Cli::new()
+            .parse(args(vec!["app", "-vvq", "--verbose"]))
+            .save();
+        assert_eq!(level(&mut cli).unwrap(), 2);
+        assert_eq!(cli.empty().is_ok(), true);
 
-        let t = Token::Ignore(7, "--map".to_string());
-        assert_eq!(t.take_str(), "--map");
-    }
+        // the level is clamped within the bounds
+        let mut cli = Cli::new().parse(args(vec!["app", "-qqq", "-v"])).save();
+        assert_eq!(level(&mut cli).unwrap(), -1);
+        let mut cli = Cli::new().parse(args(vec!["app", "-vvvv"])).save();
+        assert_eq!(level(&mut cli).unwrap(), 2);
 
-    #[test]
-    #[should_panic]
-    fn take_impossible_token_flag_str() {
-        let t = Token::Flag(7);
-        t.take_str();
+        let mut cli = Cli::new().parse(args(vec!["app", "--quiet=1"])).save();
+        assert_eq!(
+            level(&mut cli).unwrap_err().kind(),
+            ErrorKind::UnexpectedValue
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn take_impossible_token_switch_str() {
-        let t = Token::Switch(7, 'h');
-        t.take_str();
+    #[should_panic(expected = "level bounds 2..=-1 must not be empty")]
+    fn check_level_empty_bounds() {
+        let mut cli = Cli::new().parse(args(vec!["app", "-v"])).save();
+        let _ = cli.check_level(
+            Arg::flag("verbose").switch('v'),
+            Arg::flag("quiet").switch('q'),
+            std::ops::RangeInclusive::new(2, -1),
+        );
     }
 
     #[test]
-    #[should_panic]
-    fn take_impossible_token_terminator_str() {
-        let t = Token::Terminator(9);
-        t.take_str();
+    #[cfg(feature = "runner")]
+    fn format_error_chain() {
+        #[derive(Debug)]
+        struct LoadError(std::io::Error);
+
+        impl std::fmt::Display for LoadError {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "failed to load config")
+            }
+        }
+
+        impl std::error::Error for LoadError {
+            fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+                Some(&self.0)
+            }
+        }
+
+        let err = LoadError(std::io::Error::new(
+            std::io::ErrorKind::NotFound,
+            "file not found",
+        ));
+        assert_eq!(utils::format_chain(&err), "\n  caused by: file not found");
+        assert_eq!(utils::format_chain(&err.0), "");
+
+        // custom errors expose their underlying error as the source
+        let err = Error::transform::<(), LoadError>(Err(err)).unwrap_err();
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(source.to_string(), "failed to load config");
+        assert_eq!(utils::format_chain(source), "\n  caused by: file not found");
     }
 
     #[test]
-    fn try_help_fail() {
-        let mut cli = Cli::new().parse(args(vec!["orbit", "--h"])).save();
-        let locs = cli.take_flag_locs("help");
-        assert_eq!(locs.len(), 0);
-        assert_eq!(cli.pull_flag(locs, false), vec![]);
+    fn tokenizer_invariants() {
+        // generate arguments from a small alphabet that stresses the tokenizer
+        let alphabet = ['-', '=', 'a', 'b', 'é'];
+        let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = |n: u64| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed % n
+        };
+        for _ in 0..2_000 {
+            let mut argv = vec![String::from("fuzz")];
+            for _ in 0..next(6) {
+                argv.push((0..next(5)).map(|_| alphabet[next(5) as usize]).collect());
+            }
+            let cli = Cli::new().parse(argv.clone().into_iter());
+            assert_eq!(cli.check_invariants(), Ok(()), "{:?}", argv);
+            let mut cli = cli.save();
+            let _ = cli.check(Arg::flag("a").switch('a'));
+            let _ = cli.get::<String>(Arg::option("b").switch('b'));
+            assert_eq!(cli.check_invariants(), Ok(()), "{:?}", argv);
+            let _ = cli.get::<String>(Arg::positional("c"));
+            let _ = cli.remainder();
+            assert_eq!(cli.check_invariants(), Ok(()), "{:?}", argv);
+            let _ = cli.empty();
+        }
     }
 
     #[test]
-    fn check_option_n() {
+    fn flag_like_value_hint() {
+        // positional
+        let mut cli = Cli::new().parse(args(vec!["grep", "-pattern"])).save();
+        let err = cli
+            .require::<String>(Arg::positional("pattern"))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MissingPositional);
+        match err.context() {
+            ErrorContext::FlagLikeValue(_, w) => assert_eq!(w, "-pattern"),
+            _ => panic!("expected a flag-like value"),
+        }
+
+        // typos of known flags are still suggested first
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "command", "--rate", "10"]))
+            .threshold(2)
+            .parse(args(vec!["grep", "--verbos"]))
             .save();
+        cli.check(Arg::flag("verbose")).unwrap();
         assert_eq!(
-            cli.get_option_until(Optional::new("rate"), 1).unwrap(),
-            Some(vec![10])
+            cli.require::<String>(Arg::positional("pattern"))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::SuggestArg
         );
 
+        // option
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "command", "--rate", "10"]))
+            .parse(args(vec!["grep", "--filter", "-x"]))
             .save();
+        let err = cli.get::<String>(Arg::option("filter")).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::ExpectingValue);
+        match err.context() {
+            ErrorContext::FlagLikeValue(_, w) => assert_eq!(w, "-x"),
+            _ => panic!("expected a flag-like value"),
+        }
+        assert_eq!(err.to_string().contains("try \"--filter=-x\""), true);
+
+        // attaching with '=' allows the value to be captured
+        let mut cli = Cli::new().parse(args(vec!["grep", "--filter=-x"])).save();
         assert_eq!(
-            cli.get_option_until(Optional::new("rate"), 2).unwrap(),
-            Some(vec![10])
+            cli.get::<String>(Arg::option("filter")).unwrap(),
+            Some(String::from("-x"))
         );
 
+        // a handled flag is not considered a value
         let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit", "command", "--rate", "10", "--rate", "4", "--rate", "3",
-            ]))
+            .parse(args(vec!["grep", "--filter", "--verbose"]))
             .save();
-        assert_eq!(
-            cli.get_option_until::<u8>(Optional::new("rate"), 2)
-                .is_err(),
-            true
-        );
+        cli.check(Arg::flag("verbose")).unwrap();
+        match cli
+            .get::<String>(Arg::option("filter"))
+            .unwrap_err()
+            .context()
+        {
+            ErrorContext::FailedArg(_) => (),
+            _ => panic!("expected a failed argument"),
+        }
+
+        // switches combined with the option are not considered a value
+        let mut cli = Cli::new().parse(args(vec!["grep", "-fv"])).save();
+        match cli
+            .get::<String>(Arg::option("filter").switch('f'))
+            .unwrap_err()
+            .context()
+        {
+            ErrorContext::FailedArg(_) => (),
+            _ => panic!("expected a failed argument"),
+        }
     }
 
     #[test]
-    fn check_flag_n() {
-        let mut cli = Cli::new().parse(args(vec!["orbit"])).save();
-        assert_eq!(cli.check_flag_until(Flag::new("debug"), 4).unwrap(), 0);
-
-        let mut cli = Cli::new().parse(args(vec!["orbit", "--debug"])).save();
-        assert_eq!(cli.check_flag_until(Flag::new("debug"), 1).unwrap(), 1);
+    fn get_or_default() {
+        let mut cli = Cli::new().parse(args(vec!["ls", "src"])).save();
+        let path: std::path::PathBuf = cli
+            .get_or(Arg::positional("path"), std::env::current_dir)
+            .unwrap();
+        assert_eq!(path, std::path::PathBuf::from("src"));
 
-        let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--debug", "--debug"]))
-            .save();
-        assert_eq!(cli.check_flag_until(Flag::new("debug"), 3).unwrap(), 2);
+        let mut cli = Cli::new().parse(args(vec!["ls"])).save();
+        let path: std::path::PathBuf = cli
+            .get_or(Arg::positional("path"), std::env::current_dir)
+            .unwrap();
+        assert_eq!(path, std::env::current_dir().unwrap());
+        // the argument is still known even though it was not supplied
+        assert_eq!(cli.known_args.last().unwrap().to_string(), "<path>");
+
+        let mut cli = Cli::new().parse(args(vec!["ls"])).save();
+        let err = cli
+            .get_or::<u8, _, _>(Arg::positional("depth"), || "x".parse::<u8>())
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CustomRule);
+    }
 
+    #[test]
+    #[cfg(feature = "regex")]
+    fn value_matches_pattern() {
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--debug", "--debug", "--debug"]))
+            .parse(args(vec!["git", "--tag", "v1-0", "main"]))
             .save();
-        assert_eq!(cli.check_flag_until(Flag::new("debug"), 3).unwrap(), 3);
+        assert_eq!(
+            cli.get::<String>(Arg::option("tag").matches(r"^[a-z0-9-]+$"))
+                .unwrap(),
+            Some(String::from("v1-0"))
+        );
+        assert_eq!(
+            cli.get::<String>(Arg::positional("branch").matches(r"^[a-z]+$"))
+                .unwrap(),
+            Some(String::from("main"))
+        );
 
-        let mut cli = Cli::new()
-            .parse(args(vec![
-                "orbit", "--debug", "--debug", "--debug", "--debug",
-            ]))
-            .save();
-        assert_eq!(cli.check_flag_until(Flag::new("debug"), 3).is_err(), true);
+        let mut cli = Cli::new().parse(args(vec!["git", "--tag", "V1.0"])).save();
+        let err = cli
+            .get::<String>(Arg::option("tag").matches(r"^[a-z0-9-]+$"))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::MismatchedPattern);
+        match err.context() {
+            ErrorContext::MismatchedPattern(_, value, pattern) => {
+                assert_eq!(value, "V1.0");
+                assert_eq!(pattern, r"^[a-z0-9-]+$");
+            }
+            _ => panic!("expected a mismatched pattern error context"),
+        }
     }
 
     #[test]
-    fn check_flag_all() {
-        let mut cli = Cli::new().parse(args(vec!["orbit"])).save();
-        assert_eq!(cli.check_flag_all(Flag::new("debug")).unwrap(), 0);
+    #[cfg(feature = "regex")]
+    #[should_panic = "invalid regular expression"]
+    fn invalid_pattern() {
+        // the pattern is compiled before any value is processed
+        let _ = Arg::option("tag").matches(r"^[a-z");
+    }
 
+    #[test]
+    #[cfg(feature = "url")]
+    fn value_url_schemes() {
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--debug", "--debug", "--debug"]))
+            .parse(args(vec!["sync", "--remote", "https://example.com/repo"]))
             .save();
-        assert_eq!(cli.check_flag_all(Flag::new("debug")).unwrap(), 3);
+        let remote: crate::value::Url = cli
+            .require(Arg::option("remote").url(&["https", "ssh"]))
+            .unwrap();
+        assert_eq!(remote.host_str(), Some("example.com"));
 
         let mut cli = Cli::new()
-            .parse(args(vec!["orbit", "--debug", "--debug", "--debug=1"]))
+            .parse(args(vec!["sync", "--remote", "ftp://example.com/repo"]))
             .save();
-        assert_eq!(cli.check_flag_all(Flag::new("debug")).is_err(), true);
-    }
-
-    #[test]
-    fn requires_positional_all() {
-        let mut cli = Cli::new().parse(args(vec!["sum", "10", "20", "30"])).save();
-        assert_eq!(
-            cli.require_positional_all::<u8>(Positional::new("digit"))
-                .unwrap(),
-            vec![10, 20, 30]
-        );
-
-        let mut cli = Cli::new().parse(args(vec!["sum"])).save();
-        assert_eq!(
-            cli.require_positional_all::<u8>(Positional::new("digit"))
-                .unwrap_err()
-                .kind(),
-            ErrorKind::MissingPositional
-        );
+        let err = cli
+            .require::<crate::value::Url>(Arg::option("remote").url(&["https", "ssh"]))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::DisallowedScheme);
+        match err.context() {
+            ErrorContext::DisallowedScheme(_, scheme, accepted) => {
+                assert_eq!(scheme, "ftp");
+                assert_eq!(accepted, &vec!["https", "ssh"]);
+            }
+            _ => panic!("expected a disallowed scheme error context"),
+        }
 
+        // values that are not urls are reported by the value's type
         let mut cli = Cli::new()
-            .parse(args(vec!["sum", "10", "--option", "20", "30"]))
+            .parse(args(vec!["sync", "--remote", "not a url"]))
             .save();
-        // note: remember to always check options and flags before positional arguments
         assert_eq!(
-            cli.get_option::<u8>(Optional::new("option")).unwrap(),
-            Some(20)
-        );
-        assert_eq!(
-            cli.require_positional_all::<u8>(Positional::new("digit"))
-                .unwrap(),
-            vec![10, 30]
-        );
-
-        let mut cli = Cli::new().parse(args(vec!["sum", "100"])).save();
-        assert_eq!(
-            cli.require_positional_all::<u8>(Positional::new("digit"))
-                .unwrap(),
-            vec![100]
+            cli.require::<crate::value::Url>(Arg::option("remote").url(&["https"]))
+                .unwrap_err()
+                .kind(),
+            ErrorKind::BadType
         );
     }
 
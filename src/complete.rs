@@ -0,0 +1,310 @@
+//! Dynamic shell completions that are provided by the program at runtime.
+//!
+//! A completion script registered with the shell calls back into the program
+//! through the hidden entry point `__complete`, passing along the words typed so
+//! far. The last word is the word being completed:
+//!
+//! ```text
+//! myapp __complete checkout --branch ma
+//! ```
+//!
+//! The command is interpreted as usual, except each requested argument offers
+//! itself as a candidate. The candidates beginning with the word being completed
+//! are written to `stdout`, one per line. Values are completed by attaching a
+//! [Completer] to an argument.
+//!
+//! Values without a completer are completed from their
+//! [ValueHint][crate::ValueHint]: path hints list the matching entries on the
+//! file system and the hostname hint lists the hosts known to the system.
+
+use std::path::{Path, MAIN_SEPARATOR};
+use std::sync::Arc;
+
+mod symbol {
+    pub const HIDDEN: char = '.';
+    pub const COMMENT: char = '#';
+}
+
+/// The file mapping hostnames to addresses.
+#[cfg(not(windows))]
+const HOSTS_FILE: &str = "/etc/hosts";
+#[cfg(windows)]
+const HOSTS_FILE: &str = "C:\\Windows\\System32\\drivers\\etc\\hosts";
+
+/// Provides the candidates for a value while it is being typed on the
+/// command-line (ex: branch names or registry packages).
+pub trait Completer {
+    /// Returns the candidates for the value that so far is written as `current`.
+    ///
+    /// Candidates that do not begin with `current` are discarded.
+    fn complete(&self, current: &str) -> Vec<String>;
+}
+
+impl<F: Fn(&str) -> Vec<String>> Completer for F {
+    fn complete(&self, current: &str) -> Vec<String> {
+        self(current)
+    }
+}
+
+/// A shared reference to a [Completer] attached to an argument.
+#[derive(Clone)]
+pub(crate) struct CompleterRef(Arc<dyn Completer + Send + Sync>);
+
+impl CompleterRef {
+    pub fn new<C: Completer + Send + Sync + 'static>(completer: C) -> Self {
+        Self(Arc::new(completer))
+    }
+
+    pub fn complete(&self, current: &str) -> Vec<String> {
+        self.0.complete(current)
+    }
+}
+
+impl PartialEq for CompleterRef {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl std::fmt::Debug for CompleterRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Completer")
+    }
+}
+
+/// Lists the paths that continue the partially written path `current`, as
+/// found in the directory `base` when `current` is relative.
+///
+/// Directories end with a separator so they can be descended into, and are the
+/// only entries listed when `dirs_only` is set. Hidden entries are only listed
+/// when the name being completed begins with a `.`.
+pub(crate) fn paths(current: &str, dirs_only: bool, base: Option<&Path>) -> Vec<String> {
+    let (dir, name) = match current.rfind(std::path::is_separator) {
+        Some(i) => current.split_at(i + 1),
+        None => ("", current),
+    };
+    let search = base.unwrap_or(Path::new(".")).join(dir);
+    let entries = match std::fs::read_dir(&search) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut paths: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let file_name = e.file_name().into_string().ok()?;
+            if file_name.starts_with(symbol::HIDDEN) == true
+                && name.starts_with(symbol::HIDDEN) == false
+            {
+                return None;
+            }
+            // follow symbolic links to decide if the entry is a directory
+            match (e.path().is_dir(), dirs_only) {
+                (true, _) => Some(format!("{}{}{}", dir, file_name, MAIN_SEPARATOR)),
+                (false, false) => Some(format!("{}{}", dir, file_name)),
+                (false, true) => None,
+            }
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Lists the hostnames known to the system through its hosts file.
+pub(crate) fn hosts() -> Vec<String> {
+    let contents = std::fs::read_to_string(HOSTS_FILE).unwrap_or_default();
+    let mut hosts = Vec::<String>::new();
+    contents
+        .lines()
+        .map(|l| l.split(symbol::COMMENT).next().unwrap_or_default())
+        // the first word on each line is the address
+        .flat_map(|l| l.split_whitespace().skip(1))
+        .for_each(|h| {
+            if hosts.iter().any(|k| k == h) == false {
+                hosts.push(h.to_string());
+            }
+        });
+    hosts
+}
+
+/// The shells that can call back into the program for completions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    /// Writes the script that registers the program `bin` with the shell to
+    /// request its completions through `__complete`.
+    pub fn script<T: AsRef<str>>(&self, bin: T) -> String {
+        let bin = bin.as_ref();
+        let func: String = bin
+            .chars()
+            .map(|c| match c.is_ascii_alphanumeric() {
+                true => c,
+                false => '_',
+            })
+            .collect();
+        match self {
+            Self::Bash => format!(
+                "_{func}() {{\n    local IFS=$'\\n'\n    COMPREPLY=($(\"{bin}\" __complete \"${{COMP_WORDS[@]:1:COMP_CWORD}}\"))\n    [[ ${{#COMPREPLY[@]}} -eq 1 && ${{COMPREPLY[0]}} == */ ]] && compopt -o nospace\n}}\ncomplete -o default -F _{func} {bin}\n",
+            ),
+            Self::Zsh => format!(
+                "#compdef {bin}\n_{func}() {{\n    local -a candidates\n    candidates=(\"${{(@f)$(\"{bin}\" __complete \"${{(@)words[2,$CURRENT]}}\")}}\")\n    compadd -S '' -- ${{(M)candidates:#*/}}\n    compadd -- ${{candidates:#*/}}\n}}\ncompdef _{func} {bin}\n",
+            ),
+            Self::Fish => format!(
+                "complete -c {bin} -f -a '({bin} __complete (commandline -opc)[2..-1] (commandline -ct))'\n",
+            ),
+        }
+    }
+}
+
+/// The state of completing the last word on the command-line.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Completion {
+    current: String,
+    switch: char,
+    candidates: Vec<String>,
+    resolved: bool,
+    selecting: bool,
+}
+
+impl Completion {
+    /// Creates the state for completing the word `current`, where flags begin
+    /// with the `switch` symbol.
+    pub fn new(current: String, switch: char) -> Self {
+        Self {
+            current: current,
+            switch: switch,
+            candidates: Vec::new(),
+            resolved: false,
+            selecting: false,
+        }
+    }
+
+    /// References the word being completed.
+    pub fn current(&self) -> &str {
+        &self.current
+    }
+
+    /// Checks if the word being completed is written like a flag.
+    pub fn is_flag(&self) -> bool {
+        self.current.starts_with(self.switch)
+    }
+
+    /// Checks if the candidates are still being collected.
+    pub fn is_open(&self) -> bool {
+        self.resolved == false
+    }
+
+    /// Adds the `words` as candidates while they are still being collected.
+    pub fn offer<I: IntoIterator<Item = String>>(&mut self, words: I) -> () {
+        if self.resolved == false {
+            self.candidates.extend(words);
+        }
+    }
+
+    /// Replaces the candidates with `words` once the word being completed is
+    /// known to be a value, after which no other candidates are collected.
+    pub fn resolve<I: IntoIterator<Item = String>>(&mut self, words: I) -> () {
+        if self.resolved == false {
+            self.candidates = words.into_iter().collect();
+            self.resolved = true;
+        }
+    }
+
+    /// Marks the word being completed as the name of a subcommand, see
+    /// [is_selecting][Completion::is_selecting].
+    pub fn select(&mut self) -> () {
+        self.selecting = true;
+    }
+
+    /// Checks if the next subcommand to be selected is the word being completed,
+    /// clearing the mark.
+    pub fn is_selecting(&mut self) -> bool {
+        std::mem::replace(&mut self.selecting, false)
+    }
+
+    /// Lists the unique candidates beginning with the word being completed, in
+    /// the order they were collected.
+    pub fn candidates(&self) -> Vec<&str> {
+        let mut words = Vec::<&str>::new();
+        self.candidates
+            .iter()
+            .filter(|w| w.starts_with(&self.current))
+            .for_each(|w| {
+                if words.contains(&w.as_str()) == false {
+                    words.push(w);
+                }
+            });
+        words
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::arg::Positional;
+    use crate::ValueHint;
+
+    #[test]
+    fn collect_candidates() {
+        let mut c = Completion::new(String::from("--v"), '-');
+        assert_eq!(c.is_flag(), true);
+        c.offer([String::from("--verbose"), String::from("--lib")]);
+        c.offer([String::from("--version"), String::from("--verbose")]);
+        assert_eq!(c.candidates(), vec!["--verbose", "--version"]);
+
+        // resolving a value discards the flags
+        let mut c = Completion::new(String::from("ma"), '-');
+        c.offer([String::from("main")]);
+        c.resolve(vec![String::from("master"), String::from("dev")]);
+        c.offer([String::from("map")]);
+        assert_eq!(c.is_open(), false);
+        assert_eq!(c.candidates(), vec!["master"]);
+    }
+
+    #[test]
+    fn complete_hinted_paths() {
+        let dir = std::env::temp_dir().join(format!("cliproc-paths-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        std::fs::create_dir_all(dir.join(".git")).unwrap();
+        std::fs::write(dir.join("setup.cfg"), "").unwrap();
+        std::fs::write(dir.join("src").join("lib.rs"), "").unwrap();
+
+        let sep = MAIN_SEPARATOR;
+        let file = Positional::new("file").base(&dir).hint(ValueHint::FilePath);
+        assert_eq!(
+            file.complete("s"),
+            vec![String::from("setup.cfg"), format!("src{}", sep)]
+        );
+        assert_eq!(
+            file.complete(&format!("src{}", sep)),
+            vec![format!("src{}lib.rs", sep)]
+        );
+        // hidden entries are only listed when asked for
+        assert_eq!(file.complete("").len(), 2);
+        assert_eq!(file.complete(".")[0], format!(".git{}", sep));
+
+        let dir_only = Positional::new("dir").base(&dir).hint(ValueHint::DirPath);
+        assert_eq!(dir_only.complete(""), vec![format!("src{}", sep)]);
+        // a completer takes priority over the hint
+        let custom = dir_only.completer(CompleterRef::new(|_: &str| vec![String::from("x")]));
+        assert_eq!(custom.complete(""), vec![String::from("x")]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn shell_scripts() {
+        assert_eq!(
+            Shell::Bash.script("my-app"),
+            "_my_app() {\n    local IFS=$'\\n'\n    COMPREPLY=($(\"my-app\" __complete \"${COMP_WORDS[@]:1:COMP_CWORD}\"))\n    [[ ${#COMPREPLY[@]} -eq 1 && ${COMPREPLY[0]} == */ ]] && compopt -o nospace\n}\ncomplete -o default -F _my_app my-app\n"
+        );
+        assert_eq!(
+            Shell::Fish.script("myapp"),
+            "complete -c myapp -f -a '(myapp __complete (commandline -opc)[2..-1] (commandline -ct))'\n"
+        );
+        assert!(Shell::Zsh.script("myapp").starts_with("#compdef myapp\n"));
+    }
+}
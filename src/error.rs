@@ -1,7 +1,7 @@
-use crate::arg::ArgType;
+use crate::arg::{Action, Arg, ArgState, ArgType};
 use crate::help::Help;
-use colored::Colorize;
 use std::fmt::Display;
+use std::io::IsTerminal;
 use std::ops::Bound::*;
 
 #[derive(Debug, PartialEq, Clone)]
@@ -22,13 +22,77 @@ impl ColorMode {
         Self::Off
     }
 
-    pub fn sync(&self) {
+    /// Determines if output should be colored under this mode.
+    ///
+    /// The [Normal][ColorMode::Normal] mode follows the `CLICOLOR_FORCE`, `NO_COLOR`, and
+    /// `CLICOLOR` environment variables before checking if `stderr` is a terminal
+    /// that can display colors, see [enable_virtual_terminal].
+    pub fn is_enabled(&self) -> bool {
         match self {
-            Self::On => colored::control::set_override(true),
-            Self::Off => colored::control::set_override(false),
-            Self::Normal => colored::control::unset_override(),
+            Self::On => {
+                enable_virtual_terminal();
+                true
+            }
+            Self::Off => false,
+            Self::Normal => {
+                let var = |key: &str| std::env::var_os(key);
+                if var("CLICOLOR_FORCE").is_some_and(|v| v != "0") {
+                    enable_virtual_terminal();
+                    true
+                } else if var("NO_COLOR").is_some() || var("CLICOLOR").is_some_and(|v| v == "0") {
+                    false
+                } else {
+                    std::io::stderr().is_terminal() && enable_virtual_terminal()
+                }
+            }
+        }
+    }
+}
+
+/// Enables the console to process the escape sequences that color the output,
+/// returning `false` if the console cannot display colors.
+///
+/// Legacy Windows consoles write escape sequences as raw text unless virtual
+/// terminal processing is turned on for the output handle. This is attempted
+/// once for both `stdout` and `stderr`, and the result for `stderr` (where
+/// errors are written) is remembered. Text itself is written to the console as
+/// UTF-16 by the standard library, so multi-byte characters next to a colored
+/// prefix are not garbled.
+#[cfg(windows)]
+fn enable_virtual_terminal() -> bool {
+    use std::os::windows::io::{AsRawHandle, RawHandle};
+    use std::sync::OnceLock;
+
+    const ENABLE_VIRTUAL_TERMINAL_PROCESSING: u32 = 0x0004;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetConsoleMode(handle: RawHandle, mode: *mut u32) -> i32;
+        fn SetConsoleMode(handle: RawHandle, mode: u32) -> i32;
+    }
+
+    fn enable(handle: RawHandle) -> bool {
+        let mut mode: u32 = 0;
+        // SAFETY: the handle of a standard stream is valid for the life of the process
+        unsafe {
+            GetConsoleMode(handle, &mut mode) != 0
+                && (mode & ENABLE_VIRTUAL_TERMINAL_PROCESSING != 0
+                    || SetConsoleMode(handle, mode | ENABLE_VIRTUAL_TERMINAL_PROCESSING) != 0)
         }
     }
+
+    static ENABLED: OnceLock<bool> = OnceLock::new();
+    *ENABLED.get_or_init(|| {
+        enable(std::io::stdout().as_raw_handle());
+        enable(std::io::stderr().as_raw_handle())
+    })
+}
+
+/// Enables the console to process the escape sequences that color the output,
+/// which terminals outside of Windows already do.
+#[cfg(not(windows))]
+fn enable_virtual_terminal() -> bool {
+    true
 }
 
 #[derive(Debug, PartialEq, Copy, Clone)]
@@ -52,9 +116,57 @@ impl Default for CapMode {
 
 const NEW_PARAGRAPH: &str = "\n\n";
 
-mod exit_code {
-    pub const BAD: u8 = 101;
-    pub const OKAY: u8 = 0;
+/// The maximum number of expected words to list in an error.
+const MAX_EXPECTED: usize = 5;
+
+/// The meaning of the code a program exits with.
+///
+/// A usage error and a runtime error both exit with code 101, matching the code
+/// of a panic. An [Exit] may be returned with `?` from a
+/// [Command][crate::Command]'s execution process to end the program with its
+/// code without reporting anything, see [Halt][crate::proc::Halt].
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Exit {
+    /// The program completed as intended (code 0).
+    Success,
+    /// The command-line could not be interpreted (code 101).
+    UsageError,
+    /// The command failed while it was executing (code 101).
+    RuntimeError,
+    /// The program was stopped by an interrupt signal (code 130).
+    Interrupted,
+    /// The program exits with the given code.
+    Custom(u8),
+}
+
+impl Exit {
+    /// Returns the exit code.
+    pub fn code(&self) -> u8 {
+        match self {
+            Self::Success => 0,
+            Self::UsageError | Self::RuntimeError => 101,
+            Self::Interrupted => 130,
+            Self::Custom(c) => *c,
+        }
+    }
+
+    /// Checks if the exit code indicates success.
+    pub fn is_success(&self) -> bool {
+        self.code() == 0
+    }
+}
+
+impl From<Exit> for u8 {
+    fn from(value: Exit) -> Self {
+        value.code()
+    }
+}
+
+#[cfg(feature = "runner")]
+impl From<Exit> for std::process::ExitCode {
+    fn from(value: Exit) -> Self {
+        std::process::ExitCode::from(value.code())
+    }
 }
 
 type Value = String;
@@ -65,7 +177,199 @@ type CurCount = usize;
 type CurStart = std::ops::Bound<usize>;
 type CurEnd = std::ops::Bound<usize>;
 type SomeError = Box<dyn std::error::Error>;
+type Message = String;
+type Pattern = String;
+type Scheme = String;
 type Argument = String;
+type CommandPath = Vec<String>;
+type Stage = String;
+type Expected = Vec<String>;
+type ValueBound = std::ops::Bound<String>;
+type Text = String;
+type Unit = &'static str;
+
+/// Applies terminal colors to text when enabled.
+///
+/// Text always passes through unchanged when the `color` feature is disabled.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) struct Paint {
+    enabled: bool,
+}
+
+impl Paint {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled: enabled && cfg!(feature = "color"),
+        }
+    }
+
+    fn paint<T: Display>(&self, code: &str, s: T) -> String {
+        match self.enabled {
+            true => format!("\x1b[{}m{}\x1b[0m", code, s),
+            false => s.to_string(),
+        }
+    }
+
+    pub fn blue<T: Display>(&self, s: T) -> String {
+        self.paint("34", s)
+    }
+
+    pub fn green<T: Display>(&self, s: T) -> String {
+        self.paint("32", s)
+    }
+
+    pub fn yellow<T: Display>(&self, s: T) -> String {
+        self.paint("33", s)
+    }
+
+    #[cfg(feature = "runner")]
+    pub fn bold_green<T: Display>(&self, s: T) -> String {
+        self.paint("1;32", s)
+    }
+
+    pub fn bold_red<T: Display>(&self, s: T) -> String {
+        self.paint("1;31", s)
+    }
+}
+
+/// The location of an argument within the command-line.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Span {
+    index: usize,
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    pub(crate) fn new(index: usize, start: usize, end: usize) -> Self {
+        Self {
+            index: index,
+            start: start,
+            end: end,
+        }
+    }
+
+    /// The position of the argument on the command-line, where the program
+    /// name is at position 0.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The byte offset where the span begins within the argument.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// The byte offset where the span ends within the argument.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    /// Echoes the `command_line` with the span underlined by carets below it.
+    ///
+    /// Returns `None` if the span does not fall within the `command_line`.
+    pub(crate) fn underline(&self, command_line: &[String], p: Paint) -> Option<String> {
+        let arg = command_line.get(self.index)?;
+        let before = arg.get(..self.start)?;
+        let text = arg.get(self.start..self.end)?;
+        let offset = command_line
+            .iter()
+            .take(self.index)
+            .map(|a| a.chars().count() + 1)
+            .sum::<usize>()
+            + before.chars().count();
+        Some(format!(
+            "  {}\n  {}{}",
+            command_line.join(" "),
+            " ".repeat(offset),
+            p.bold_red("^".repeat(text.chars().count().max(1)))
+        ))
+    }
+}
+
+/// The appearance of an [Error] when it is rendered into text, see [Error::render].
+///
+/// By default, the error is rendered without colors, a prefix, a suffix, line
+/// wrapping, or changes to its capitalization.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ErrorStyle {
+    color: bool,
+    prefix: String,
+    suffix: String,
+    width: Option<usize>,
+    cap_mode: CapMode,
+    command_line: Option<Vec<String>>,
+}
+
+impl ErrorStyle {
+    /// Creates a new plain style.
+    pub fn new() -> Self {
+        Self {
+            color: false,
+            prefix: String::new(),
+            suffix: String::new(),
+            width: None,
+            cap_mode: CapMode::Manual,
+            command_line: None,
+        }
+    }
+
+    /// Sets whether to apply terminal colors to the error.
+    pub fn color(mut self, enabled: bool) -> Self {
+        self.color = enabled;
+        self
+    }
+
+    /// Sets the text to come before the error message.
+    pub fn prefix<T: AsRef<str>>(mut self, prefix: T) -> Self {
+        self.prefix = String::from(prefix.as_ref());
+        self
+    }
+
+    /// Sets the text to come after the error message.
+    pub fn suffix<T: AsRef<str>>(mut self, suffix: T) -> Self {
+        self.suffix = String::from(suffix.as_ref());
+        self
+    }
+
+    /// Wraps the lines of the error to be no longer than `width` characters where possible.
+    pub fn wrap(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Echoes the `command_line` after the error message with the offending
+    /// argument underlined, when the error knows where it occurred.
+    ///
+    /// The `command_line` includes the program name, such as from [std::env::args].
+    pub fn point_to<T: AsRef<str>>(mut self, command_line: &[T]) -> Self {
+        self.command_line = Some(
+            command_line
+                .iter()
+                .map(|a| a.as_ref().to_string())
+                .collect(),
+        );
+        self
+    }
+
+    /// Uppercases the first character of the error message.
+    pub fn uppercase(mut self) -> Self {
+        self.cap_mode = CapMode::Upper;
+        self
+    }
+
+    /// Lowercases the first character of the error message.
+    pub fn lowercase(mut self) -> Self {
+        self.cap_mode = CapMode::Lower;
+        self
+    }
+}
+
+impl Default for ErrorStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 /// Errors related to command-line processing from [Cli][super::Cli].
 #[derive(Debug)]
@@ -74,6 +378,7 @@ pub struct Error {
     cap_mode: CapMode,
     help: Option<Help>,
     kind: ErrorKind,
+    span: Option<Span>,
 }
 
 impl From<Box<dyn std::error::Error>> for Error {
@@ -100,19 +405,33 @@ impl Error {
             kind: kind,
             context: context,
             cap_mode: cap_mode,
+            span: None,
         }
     }
 
+    /// Sets the location of the command-line argument that caused the error.
+    pub(crate) fn with_span(mut self, span: Option<Span>) -> Self {
+        self.span = span;
+        self
+    }
+
+    /// References the location of the command-line argument that caused the
+    /// error, if it is known.
+    pub fn span(&self) -> Option<&Span> {
+        self.span.as_ref()
+    }
+
     // Returns the kind of command-line error.
     pub fn kind(&self) -> ErrorKind {
         self.kind
     }
 
-    /// Returns `OKAY_CODE` for help error and `BAD_CODE` otherwise.
-    pub fn code(&self) -> u8 {
+    /// Returns [Exit::Success] for help and eager errors and [Exit::UsageError]
+    /// otherwise.
+    pub fn code(&self) -> Exit {
         match &self.kind {
-            ErrorKind::Help => exit_code::OKAY,
-            _ => exit_code::BAD,
+            ErrorKind::Help | ErrorKind::Eager => Exit::Success,
+            _ => Exit::UsageError,
         }
     }
 
@@ -133,16 +452,114 @@ impl Error {
             )),
         }
     }
+
+    /// Creates a custom rule error that is associated with the argument `arg`.
+    ///
+    /// Unlike [transform][Error::transform], the error is displayed with the
+    /// argument's name and a help tip when help information is available.
+    pub fn custom_for<S: ArgState, E: std::error::Error + 'static>(arg: Arg<S>, err: E) -> Self {
+        Self::new(
+            None,
+            ErrorKind::CustomRule,
+            ErrorContext::CustomArg(ArgType::from(arg), Box::new(err)),
+            CapMode::default(),
+        )
+    }
+
+    /// Creates an error for a command that was successfully interpreted but is
+    /// semantically invalid, such as during [Command::validate][crate::Command::validate].
+    ///
+    /// Unlike a custom rule error, this error is displayed with a help tip when
+    /// help information is available.
+    pub fn invalid<E: std::error::Error + 'static>(err: E) -> Self {
+        Self::new(
+            None,
+            ErrorKind::InvalidCommand,
+            ErrorContext::InvalidCommand(Box::new(err)),
+            CapMode::default(),
+        )
+    }
 }
 
 impl Error {
+    /// Renders the error into text with the given `style`.
+    ///
+    /// Unlike [Display], the rendered text does not depend on any global color
+    /// settings, so the same error can be rendered differently for logs and
+    /// terminals. Help information and the text of eager arguments are rendered
+    /// without the style's prefix and suffix.
+    pub fn render(&self, style: &ErrorStyle) -> String {
+        let mut msg = String::new();
+        // writing into a string cannot fail
+        self.write_message(&mut msg, Paint::new(style.color))
+            .unwrap();
+        let (text, suffix) = match self.kind {
+            ErrorKind::Help | ErrorKind::Eager => (msg, ""),
+            _ => (
+                format!(
+                    "{}{}",
+                    style.prefix,
+                    utils::format_err_msg(msg, style.cap_mode)
+                ),
+                style.suffix.as_str(),
+            ),
+        };
+        let text = match (self.kind, style.width) {
+            // help is wrapped according to its format (manual pages are not wrapped)
+            (ErrorKind::Help, Some(width)) => self
+                .help
+                .as_ref()
+                .unwrap_or(&Help::new())
+                .display(Some(width)),
+            (_, Some(width)) => utils::wrap(&text, width),
+            (_, None) => text,
+        };
+        // the echoed command-line is never wrapped to keep the carets aligned
+        let pointer = match (&style.command_line, &self.span) {
+            (Some(line), Some(span)) => span
+                .underline(line, Paint::new(style.color))
+                .map(|u| format!("{}{}", NEW_PARAGRAPH, u))
+                .unwrap_or_default(),
+            _ => String::new(),
+        };
+        format!("{}{}{}", text, pointer, suffix)
+    }
+}
+
+impl Error {
+    /// Fills in the help information, capitalization mode, and the `symbol` the
+    /// argument is displayed with for an error that was constructed outside of
+    /// the command-line processor.
+    pub(crate) fn attach(mut self, help: Option<Help>, cap_mode: CapMode, symbol: char) -> Self {
+        if self.help.is_none() {
+            self.help = help;
+        }
+        self.cap_mode = cap_mode;
+        if let ErrorContext::CustomArg(arg, _) = &mut self.context {
+            arg.set_symbol(symbol);
+        }
+        self
+    }
+
+    /// Constructs the usage line to insert into an error message if help has one.
+    ///
+    /// Any additional usage lines are aligned under the first line.
+    fn usage_line(&self) -> Option<String> {
+        let usage = self.help.as_ref()?.get_usage()?;
+        Some(format!(
+            "{}usage: {}",
+            NEW_PARAGRAPH,
+            usage.replace('\n', "\n       ")
+        ))
+    }
+
     /// Constructs a simple help tip to insert into an error message if help exists.
-    fn help_tip(&self) -> Option<String> {
-        let flag_str = ArgType::from(self.help.as_ref()?.get_arg()).to_string();
+    fn help_tip(&self, p: Paint) -> Option<String> {
+        let flag_str = self.help.as_ref()?.get_flag().to_string();
         Some(format!(
             "{}For more information, try \"{}\".",
             NEW_PARAGRAPH,
-            flag_str.green()
+            p.green(flag_str)
         ))
     }
 }
@@ -152,15 +569,36 @@ impl Error {
 #[allow(dead_code)]
 pub enum ErrorContext {
     ExceededThreshold(ArgType, CurCount, MaxCount),
+    ExceededLimit(Text, Unit, CurCount, MaxCount),
     OutsideRange(ArgType, CurCount, CurStart, CurEnd),
+    ValueOutsideRange(ArgType, Value, ValueBound, ValueBound),
     FailedArg(ArgType),
+    MissingUnless(ArgType, Vec<Argument>),
     UnexpectedValue(ArgType, Value),
-    FailedCast(ArgType, Value, SomeError),
+    FailedCast(ArgType, Value, Message),
     OutofContextArgSuggest(Argument, Subcommand),
-    UnexpectedArg(Argument),
-    SuggestWord(String, Suggestion),
-    UnknownSubcommand(ArgType, Subcommand),
+    RestrictedArg(Argument, Vec<Subcommand>),
+    UnstableArg(ArgType, Text, Option<Argument>),
+    UnexpectedArg(Argument, CommandPath, Expected),
+    SuggestWord(String, Vec<Suggestion>),
+    UnknownSubcommand(ArgType, Subcommand, CommandPath, Expected),
     CustomRule(SomeError),
+    CustomArg(ArgType, SomeError),
+    InvalidCommand(SomeError),
+    MismatchedPattern(ArgType, Value, Pattern),
+    UnreadableFile(ArgType, Value, std::io::Error),
+    UnresolvedPath(ArgType, Value, std::io::Error),
+    DisallowedScheme(ArgType, Scheme, Vec<Scheme>),
+    FlagLikeValue(ArgType, Argument),
+    DetachedValue(ArgType, Argument),
+    GroupedValue(ArgType, Argument),
+    StructuralHazard(Stage, Stage),
+    Eager(Text),
+    TerminatorArity(Argument, CurCount),
+    InvalidExpression(Message),
+    Getopt(Message),
+    UnusedArg(ArgType),
+    UnsupportedAction(ArgType, Action),
     Help,
 }
 
@@ -173,18 +611,44 @@ pub enum ErrorKind {
     DuplicateOptions,
     ExpectingValue,
     UnexpectedValue,
+    GroupedValue,
     OutOfContextArgSuggest,
+    RestrictedArg,
+    UnstableArg,
     UnexpectedArg,
     SuggestArg,
     SuggestSubcommand,
     UnknownSubcommand,
     CustomRule,
+    InvalidCommand,
+    MismatchedPattern,
+    UnreadableFile,
+    UnresolvedPath,
+    DisallowedScheme,
+    StructuralHazard,
+    Eager,
     Help,
     ExceedingMaxCount,
+    ExceededLimit,
     OutsideRange,
+    InvalidExpression,
+    UnusedArg,
+    UnsupportedAction,
 }
 
-impl std::error::Error for Error {}
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.context {
+            ErrorContext::CustomRule(err)
+            | ErrorContext::CustomArg(_, err)
+            | ErrorContext::InvalidCommand(err) => Some(err.as_ref()),
+            ErrorContext::UnreadableFile(_, _, err) | ErrorContext::UnresolvedPath(_, _, err) => {
+                Some(err)
+            }
+            _ => None,
+        }
+    }
+}
 
 pub mod utils {
     use super::*;
@@ -212,6 +676,141 @@ pub mod utils {
         }
     }
 
+    /// Describes where an argument was found by its command path, if one is known.
+    pub fn format_path(path: &CommandPath) -> String {
+        match path.is_empty() {
+            true => String::new(),
+            false => format!(" in \"{}\"", path.join(" ")),
+        }
+    }
+
+    /// Lists the chain of errors that caused `err`, one per line.
+    ///
+    /// The result is empty if `err` has no source.
+    /// Writes `s` as a quoted JSON string.
+    pub fn to_json_str(s: &str) -> String {
+        let escaped: String = s
+            .chars()
+            .map(|c| match c {
+                '"' => String::from("\\\""),
+                '\\' => String::from("\\\\"),
+                '\n' => String::from("\\n"),
+                '\t' => String::from("\\t"),
+                c if c.is_control() == true => format!("\\u{:04x}", c as u32),
+                c => c.to_string(),
+            })
+            .collect();
+        format!("\"{}\"", escaped)
+    }
+
+    #[cfg(feature = "runner")]
+    pub fn format_chain(err: &dyn std::error::Error) -> String {
+        let mut chain = String::new();
+        let mut source = err.source();
+        while let Some(e) = source {
+            chain.push_str(&format!("\n  caused by: {}", e));
+            source = e.source();
+        }
+        chain
+    }
+
+    /// Counts the characters of `s` that are visible on a terminal, ignoring
+    /// color escape sequences.
+    pub fn visible_len(s: &str) -> usize {
+        let mut len = 0;
+        let mut escaped = false;
+        for c in s.chars() {
+            match (escaped, c) {
+                (false, '\x1b') => escaped = true,
+                (false, _) => len += 1,
+                (true, 'm') => escaped = false,
+                (true, _) => (),
+            }
+        }
+        len
+    }
+
+    /// Wraps each line of `s` at spaces such that lines are no longer than `width`
+    /// visible characters, unless a single word is longer than `width`.
+    pub fn wrap(s: &str, width: usize) -> String {
+        s.split('\n')
+            .map(|line| {
+                let mut lines = Vec::<String>::new();
+                let mut cur = String::new();
+                let mut len = 0;
+                let mut first = true;
+                for word in line.split(' ') {
+                    let w = visible_len(word);
+                    if first == false && len + 1 + w > width {
+                        lines.push(std::mem::take(&mut cur));
+                        len = 0;
+                        first = true;
+                    }
+                    if first == false {
+                        cur.push(' ');
+                        len += 1;
+                    }
+                    cur.push_str(word);
+                    len += w;
+                    first = false;
+                }
+                lines.push(cur);
+                lines.join("\n")
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    /// Lists the `expected` words in a new paragraph, if there are any.
+    ///
+    /// At most `MAX_EXPECTED` words are listed, followed by the number of words
+    /// that were left out.
+    pub(crate) fn format_expected(expected: &Expected, p: Paint) -> String {
+        if expected.is_empty() == true {
+            return String::new();
+        }
+        let shown = expected
+            .iter()
+            .take(MAX_EXPECTED)
+            .map(|w| p.green(w))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!(
+            "{}expected one of: {}{}",
+            NEW_PARAGRAPH,
+            shown,
+            match expected.len().saturating_sub(MAX_EXPECTED) {
+                0 => String::new(),
+                n => format!(" … and {} more", n),
+            }
+        )
+    }
+
+    /// Describes the range of values between the bounds `start` and `end`.
+    pub fn format_value_range(start: &ValueBound, end: &ValueBound) -> String {
+        match (start, end) {
+            (Included(a), Included(b)) => format!("between {} and {}", a, b),
+            (Unbounded, Unbounded) => String::from("any value"),
+            _ => {
+                let lower = match start {
+                    Included(a) => Some(format!("at least {}", a)),
+                    Excluded(a) => Some(format!("greater than {}", a)),
+                    Unbounded => None,
+                };
+                let upper = match end {
+                    Included(b) => Some(format!("at most {}", b)),
+                    Excluded(b) => Some(format!("less than {}", b)),
+                    Unbounded => None,
+                };
+                [lower, upper]
+                    .into_iter()
+                    .flatten()
+                    .collect::<Vec<String>>()
+                    .join(" and ")
+            }
+        }
+    }
+
     pub fn format_range(start: &CurStart, end: &CurEnd) -> String {
         format!(
             "{} and {}",
@@ -230,13 +829,21 @@ pub mod utils {
 }
 
 impl Display for Error {
+    /// Writes the error message without colors, see [Error::render] for styled output.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> Result<(), std::fmt::Error> {
+        self.write_message(f, Paint::new(false))
+    }
+}
+
+impl Error {
+    /// Writes the error message into `f`, using `p` to apply colors.
+    fn write_message(&self, f: &mut dyn std::fmt::Write, p: Paint) -> std::fmt::Result {
         match self.context() {
             ErrorContext::OutsideRange(arg, count, start, end) => {
                 write!(
                     f,
                     "option \"{}\" can be used between {} times but was supplied {} times",
-                    arg.to_string().blue(),
+                    p.blue(arg.to_string()),
                     utils::format_range(start, end),
                     count,
                 )
@@ -245,113 +852,360 @@ impl Display for Error {
                 write!(
                     f,
                     "option \"{}\" can be used up to {} times but was supplied {} times",
-                    arg.to_string().blue(),
+                    p.blue(arg.to_string()),
                     max,
                     cur
                 )
             }
+            ErrorContext::ExceededLimit(subject, unit, cur, max) => {
+                write!(
+                    f,
+                    "exceeded resource limit: {} has {} {} but at most {} are allowed",
+                    subject, cur, unit, max
+                )
+            }
+            ErrorContext::Eager(text) => write!(f, "{}", text),
+            ErrorContext::TerminatorArity(terminator, cur) => {
+                write!(
+                    f,
+                    "expected one argument after \"{}\" but {} were supplied{}",
+                    p.blue(terminator),
+                    match cur {
+                        0 => String::from("zero"),
+                        n => n.to_string(),
+                    },
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
+            ErrorContext::InvalidExpression(err) => {
+                write!(
+                    f,
+                    "invalid expression: {}{}",
+                    err,
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
+            ErrorContext::Getopt(err) => {
+                write!(
+                    f,
+                    "{}{}{}",
+                    err,
+                    self.usage_line().unwrap_or(String::new()),
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
             ErrorContext::Help => {
                 write!(
                     f,
                     "{}",
-                    self.help.as_ref().unwrap_or(&Help::new()).get_text()
+                    self.help.as_ref().unwrap_or(&Help::new()).display(None)
                 )
             }
             ErrorContext::FailedCast(arg, val, err) => {
+                let example = match arg {
+                    ArgType::Optional(o) => o.get_positional().get_example(),
+                    ArgType::Positional(p) => p.get_example(),
+                    ArgType::Flag(_) => None,
+                };
                 write!(
                     f,
-                    "argument \"{}\" failed to process value \"{}\": {}",
-                    arg.to_string().blue(),
-                    val.to_string().yellow(),
-                    utils::format_err_msg(err.to_string(), self.cap_mode)
+                    "argument \"{}\" failed to process value \"{}\": {}{}",
+                    p.blue(arg.to_string()),
+                    p.yellow(val.to_string()),
+                    utils::format_err_msg(err.clone(), self.cap_mode),
+                    match example {
+                        Some(e) => format!("{}example: {}", NEW_PARAGRAPH, p.green(e)),
+                        None => String::new(),
+                    }
+                )
+            }
+            ErrorContext::MissingUnless(arg, unless) => {
+                let unless = unless
+                    .iter()
+                    .map(|u| format!("\"{}\"", p.blue(u)))
+                    .collect::<Vec<String>>()
+                    .join(" or ");
+                write!(
+                    f,
+                    "missing {} \"{}\" (required unless {} is supplied){}{}",
+                    match self.kind() {
+                        ErrorKind::MissingOption => "required option",
+                        _ => "positional argument",
+                    },
+                    p.blue(arg.to_string()),
+                    unless,
+                    self.usage_line().unwrap_or(String::new()),
+                    self.help_tip(p).unwrap_or(String::new())
                 )
             }
             ErrorContext::FailedArg(arg) => match self.kind() {
                 ErrorKind::MissingPositional => {
                     write!(
                         f,
-                        "missing positional argument \"{}\"{}",
-                        arg.to_string().blue(),
-                        self.help_tip().unwrap_or(String::new())
+                        "missing positional argument \"{}\"{}{}",
+                        p.blue(arg.to_string()),
+                        self.usage_line().unwrap_or(String::new()),
+                        self.help_tip(p).unwrap_or(String::new())
                     )
                 }
                 ErrorKind::MissingOption => {
                     write!(
                         f,
-                        "missing required option \"{}\"{}",
-                        arg.to_string().blue(),
-                        self.help_tip().unwrap_or(String::new())
+                        "missing required option \"{}\"{}{}",
+                        p.blue(arg.to_string()),
+                        self.usage_line().unwrap_or(String::new()),
+                        self.help_tip(p).unwrap_or(String::new())
                     )
                 }
                 ErrorKind::DuplicateOptions => {
                     write!(
                         f,
                         "argument \"{}\" can only be supplied once",
-                        arg.to_string().blue()
+                        p.blue(arg.to_string())
                     )
                 }
                 ErrorKind::ExpectingValue => {
                     write!(
                         f,
                         "option \"{}\" accepts one value but zero were supplied",
-                        arg.to_string().blue()
+                        p.blue(arg.to_string())
                     )
                 }
                 _ => panic!("reached unreachable error kind for a failed argument error context"),
             },
-            ErrorContext::SuggestWord(word, suggestion) => match self.kind() {
-                ErrorKind::SuggestArg => {
+            ErrorContext::SuggestWord(word, suggestions) => {
+                let suggestions = suggestions
+                    .iter()
+                    .map(|s| format!("\"{}\"", p.green(s)))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                match self.kind() {
+                    ErrorKind::SuggestArg => {
+                        write!(
+                            f,
+                            "invalid argument \"{}\"{}Did you mean {}?",
+                            p.yellow(word),
+                            NEW_PARAGRAPH,
+                            suggestions
+                        )
+                    }
+                    ErrorKind::SuggestSubcommand => {
+                        write!(
+                            f,
+                            "invalid subcommand \"{}\"{}Did you mean {}?",
+                            p.yellow(word),
+                            NEW_PARAGRAPH,
+                            suggestions
+                        )
+                    }
+                    _ => {
+                        panic!("reached unreachable error kind for a failed argument error context")
+                    }
+                }
+            }
+            ErrorContext::OutofContextArgSuggest(arg, subcommand) => {
+                write!(f, "argument \"{}\" is unknown or invalid in the current context{}Maybe move it after \"{}\"?", p.yellow(arg), NEW_PARAGRAPH, p.green(subcommand))
+            }
+            ErrorContext::RestrictedArg(arg, subcommands) => {
+                write!(
+                    f,
+                    "argument \"{}\" is only valid with {}",
+                    p.yellow(arg),
+                    subcommands
+                        .iter()
+                        .map(|s| format!("\"{}\"", p.green(s)))
+                        .collect::<Vec<String>>()
+                        .join(" or ")
+                )
+            }
+            ErrorContext::UnstableArg(arg, var, flag) => {
+                write!(
+                    f,
+                    "argument \"{}\" is experimental{}Set the environment variable \"{}\"{} to use it",
+                    p.blue(arg.to_string()),
+                    NEW_PARAGRAPH,
+                    p.green(var),
+                    match flag {
+                        Some(flag) => format!(" or supply \"{}\"", p.green(flag)),
+                        None => String::new(),
+                    }
+                )
+            }
+            ErrorContext::UnexpectedValue(flag, val) => {
+                write!(
+                    f,
+                    "flag \"{}\" cannot accept a value but was given \"{}\"",
+                    p.blue(flag.to_string()),
+                    p.yellow(val)
+                )
+            }
+            ErrorContext::UnexpectedArg(word, path, expected) => {
+                write!(
+                    f,
+                    "invalid argument \"{}\"{}{}{}",
+                    p.yellow(word),
+                    utils::format_path(path),
+                    utils::format_expected(expected, p),
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
+            ErrorContext::UnknownSubcommand(arg, subcommand, path, expected) => {
+                write!(
+                    f,
+                    "invalid subcommand \"{}\" for \"{}\"{}{}",
+                    p.yellow(subcommand),
+                    p.blue(arg.to_string()),
+                    utils::format_path(path),
+                    utils::format_expected(expected, p)
+                )
+            }
+            ErrorContext::CustomRule(err) => {
+                write!(
+                    f,
+                    "{}",
+                    utils::format_err_msg(err.to_string(), self.cap_mode)
+                )
+            }
+            ErrorContext::CustomArg(arg, err) => {
+                write!(
+                    f,
+                    "argument \"{}\" is invalid: {}{}",
+                    p.blue(arg.to_string()),
+                    utils::format_err_msg(err.to_string(), self.cap_mode),
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
+            ErrorContext::ValueOutsideRange(arg, val, start, end) => {
+                write!(
+                    f,
+                    "argument \"{}\" received value \"{}\" but must be {}{}",
+                    p.blue(arg.to_string()),
+                    p.yellow(val),
+                    utils::format_value_range(start, end),
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
+            ErrorContext::MismatchedPattern(arg, val, pattern) => {
+                write!(
+                    f,
+                    "argument \"{}\" received value \"{}\" that does not match the pattern \"{}\"",
+                    p.blue(arg.to_string()),
+                    p.yellow(val),
+                    pattern
+                )
+            }
+            ErrorContext::UnreadableFile(arg, path, err) => {
+                write!(
+                    f,
+                    "argument \"{}\" failed to read file \"{}\": {}{}",
+                    p.blue(arg.to_string()),
+                    p.yellow(path),
+                    utils::format_err_msg(err.to_string(), self.cap_mode),
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
+            ErrorContext::UnresolvedPath(arg, path, err) => {
+                write!(
+                    f,
+                    "argument \"{}\" failed to resolve path \"{}\": {}{}",
+                    p.blue(arg.to_string()),
+                    p.yellow(path),
+                    utils::format_err_msg(err.to_string(), self.cap_mode),
+                    self.help_tip(p).unwrap_or(String::new())
+                )
+            }
+            ErrorContext::DisallowedScheme(arg, scheme, accepted) => {
+                write!(
+                    f,
+                    "argument \"{}\" does not accept the URL scheme \"{}\" (accepted schemes: {})",
+                    p.blue(arg.to_string()),
+                    p.yellow(scheme),
+                    accepted.join(", ")
+                )
+            }
+            ErrorContext::FlagLikeValue(arg, word) => match self.kind() {
+                ErrorKind::MissingPositional => {
                     write!(
                         f,
-                        "invalid argument \"{}\"{}Did you mean \"{}\"?",
-                        word.yellow(),
+                        "missing positional argument \"{}\"{}Argument \"{}\" was interpreted as a flag and cannot be used as a value{}{}",
+                        p.blue(arg.to_string()),
                         NEW_PARAGRAPH,
-                        suggestion.green()
+                        p.yellow(word),
+                        self.usage_line().unwrap_or(String::new()),
+                        self.help_tip(p).unwrap_or(String::new())
                     )
                 }
-                ErrorKind::SuggestSubcommand => {
+                ErrorKind::ExpectingValue => {
+                    let flag = match arg {
+                        ArgType::Optional(o) => o.get_flag().to_string(),
+                        _ => arg.to_string(),
+                    };
                     write!(
                         f,
-                        "invalid subcommand \"{}\"{}Did you mean \"{}\"?",
-                        word.yellow(),
+                        "option \"{}\" accepts one value but zero were supplied{}If \"{}\" was meant as its value, try \"{}\"",
+                        p.blue(arg.to_string()),
                         NEW_PARAGRAPH,
-                        suggestion.green()
+                        p.yellow(word),
+                        p.green(format!("{}={}", flag, word))
                     )
                 }
-                _ => panic!("reached unreachable error kind for a failed argument error context"),
+                _ => panic!("reached unreachable error kind for a flag-like value error context"),
             },
-            ErrorContext::OutofContextArgSuggest(arg, subcommand) => {
-                write!(f, "argument \"{}\" is unknown or invalid in the current context{}Maybe move it after \"{}\"?", arg.yellow(), NEW_PARAGRAPH, subcommand.green())
+            ErrorContext::DetachedValue(arg, word) => {
+                let flag = match arg {
+                    ArgType::Optional(o) => o.get_flag().to_string(),
+                    _ => arg.to_string(),
+                };
+                write!(
+                    f,
+                    "option \"{}\" requires its value to be attached with \"=\"{}If \"{}\" was meant as its value, try \"{}\"",
+                    p.blue(arg.to_string()),
+                    NEW_PARAGRAPH,
+                    p.yellow(word),
+                    p.green(format!("{}={}", flag, word))
+                )
             }
-            ErrorContext::UnexpectedValue(flag, val) => {
+            ErrorContext::GroupedValue(arg, word) => {
+                // suggest separating the last switch, which takes the value, from the group
+                let (switches, value) = word.split_once('=').unwrap_or((word, ""));
+                let mut chars = switches.chars();
+                let last = chars.next_back().unwrap_or_default();
                 write!(
                     f,
-                    "flag \"{}\" cannot accept a value but was given \"{}\"",
-                    flag.to_string().blue(),
-                    val.yellow()
+                    "option \"{}\" cannot take a value attached to a group of switches \"{}\"{}Write the switch with its value separately, such as \"{}\"",
+                    p.blue(arg.to_string()),
+                    p.yellow(word),
+                    NEW_PARAGRAPH,
+                    p.green(format!("{} -{}={}", chars.as_str(), last, value))
                 )
             }
-            ErrorContext::UnexpectedArg(word) => {
+            ErrorContext::UnusedArg(arg) => {
                 write!(
                     f,
-                    "invalid argument \"{}\"{}",
-                    word.yellow(),
-                    self.help_tip().unwrap_or(String::new())
+                    "strict mode: argument \"{}\" was declared but never requested during interpretation",
+                    arg
                 )
             }
-            ErrorContext::UnknownSubcommand(arg, subcommand) => {
+            ErrorContext::UnsupportedAction(arg, action) => {
                 write!(
                     f,
-                    "invalid subcommand \"{}\" for \"{}\"",
-                    subcommand.yellow(),
-                    arg.to_string().blue()
+                    "argument \"{}\" cannot be processed with the action {:?}",
+                    arg, action
                 )
             }
-            ErrorContext::CustomRule(err) => {
+            ErrorContext::StructuralHazard(prev, next) => {
                 write!(
                     f,
-                    "{}",
-                    utils::format_err_msg(err.to_string(), self.cap_mode)
+                    "structural hazard: argument discovery is in an invalid order: {} cannot be requested after {} (arguments must be requested as flags, options, positionals, then subcommands)",
+                    next, prev
+                )
+            }
+            ErrorContext::InvalidCommand(err) => {
+                write!(
+                    f,
+                    "{}{}",
+                    utils::format_err_msg(err.to_string(), self.cap_mode),
+                    self.help_tip(p).unwrap_or(String::new())
                 )
             }
         }?;
@@ -0,0 +1,327 @@
+//! Expression-style operands that are interpreted in the order they appear on
+//! the command-line.
+//!
+//! Some tools, such as `find`, accept an expression where the order of the
+//! arguments is significant (ex: `\( -name *.rs -or -name *.toml \) -not -empty`).
+//! These arguments can be taken from the command-line as a stream of [Operand]s
+//! and grouped into an [Expr].
+
+use std::fmt::Display;
+
+mod symbol {
+    pub const SWITCH: char = '-';
+    pub const OPEN: &str = "(";
+    pub const CLOSE: &str = ")";
+    pub const NOT: [&str; 2] = ["!", "-not"];
+    pub const AND: [&str; 2] = ["-a", "-and"];
+    pub const OR: [&str; 2] = ["-o", "-or"];
+}
+
+/// A single argument within an expression.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Operand {
+    /// A word beginning with the switch symbol (ex: `-name`).
+    Flag(String),
+    /// Any other word (ex: `*.rs`).
+    Value(String),
+}
+
+impl Operand {
+    /// Classifies the raw command-line argument `word`.
+    ///
+    /// Words that begin with a switch symbol are flags, unless the symbol is
+    /// alone or followed by a digit, such as in a negative number (ex: `-5`).
+    pub fn new(word: &str) -> Self {
+        let mut chars = word.chars();
+        match (chars.next(), chars.next()) {
+            (Some(symbol::SWITCH), Some(c)) if c.is_ascii_digit() == false => {
+                Self::Flag(word.to_string())
+            }
+            _ => Self::Value(word.to_string()),
+        }
+    }
+
+    /// Checks if the operand begins an expression.
+    pub(crate) fn is_leading(word: &str) -> bool {
+        match Self::new(word) {
+            Self::Flag(_) => true,
+            Self::Value(v) => v == symbol::OPEN || symbol::NOT.contains(&v.as_str()),
+        }
+    }
+
+    /// References the raw command-line argument.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Flag(s) => s,
+            Self::Value(s) => s,
+        }
+    }
+}
+
+impl Display for Operand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A tree of operands grouped by the operators `(`, `)`, `!`/`-not`,
+/// `-a`/`-and`, and `-o`/`-or`.
+///
+/// Adjacent primaries without an operator between them are joined by `-and`,
+/// which binds tighter than `-or`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Expr {
+    /// A flag and the values that follow it (ex: `-name *.rs`).
+    Primary(String, Vec<String>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    /// Groups the list of `operands` into an expression.
+    ///
+    /// Errors if the operands are empty, the parentheses are unbalanced, an
+    /// operator is missing an operand, or a value is not preceded by a flag.
+    pub fn parse(operands: &[Operand]) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            operands: operands,
+            pos: 0,
+        };
+        let expr = parser.or()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(Operand::Value(v)) if v == symbol::CLOSE => Err(ExprError::UnmatchedClose),
+            Some(op) => Err(ExprError::UnexpectedValue(op.to_string())),
+        }
+    }
+}
+
+/// A recursive-descent parser over a list of operands.
+struct Parser<'a> {
+    operands: &'a [Operand],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&'a Operand> {
+        self.operands.get(self.pos)
+    }
+
+    /// Advances past the next operand if it is one of the words in `bank`.
+    fn accept(&mut self, bank: &[&str]) -> bool {
+        match self.peek() {
+            Some(op) if bank.contains(&op.as_str()) == true => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Checks if the next operand can begin a unary expression.
+    fn is_unary_next(&self) -> bool {
+        match self.peek() {
+            Some(op) => {
+                let word = op.as_str();
+                word != symbol::CLOSE
+                    && symbol::AND.contains(&word) == false
+                    && symbol::OR.contains(&word) == false
+            }
+            None => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.and()?;
+        while self.accept(&symbol::OR) == true {
+            let rhs = self.operand_after(symbol::OR[1], Self::and)?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, ExprError> {
+        let mut lhs = self.unary()?;
+        loop {
+            let rhs = if self.accept(&symbol::AND) == true {
+                self.operand_after(symbol::AND[1], Self::unary)?
+            } else if self.is_unary_next() == true {
+                self.unary()?
+            } else {
+                break;
+            };
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ExprError> {
+        if self.accept(&symbol::NOT) == true {
+            let inner = self.operand_after(symbol::NOT[0], Self::unary)?;
+            Ok(Expr::Not(Box::new(inner)))
+        } else if self.accept(&[symbol::OPEN]) == true {
+            let inner = self.operand_after(symbol::OPEN, Self::or)?;
+            match self.accept(&[symbol::CLOSE]) {
+                true => Ok(inner),
+                false => Err(ExprError::UnmatchedOpen),
+            }
+        } else {
+            self.primary()
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expr, ExprError> {
+        match self.peek() {
+            Some(Operand::Flag(op))
+                if symbol::AND.contains(&op.as_str()) || symbol::OR.contains(&op.as_str()) =>
+            {
+                Err(ExprError::LeadingOperator(op.clone()))
+            }
+            Some(Operand::Flag(name)) => {
+                self.pos += 1;
+                let mut values = Vec::new();
+                while let Some(Operand::Value(v)) = self.peek() {
+                    if v == symbol::CLOSE || v == symbol::OPEN || symbol::NOT.contains(&v.as_str())
+                    {
+                        break;
+                    }
+                    values.push(v.clone());
+                    self.pos += 1;
+                }
+                Ok(Expr::Primary(name.clone(), values))
+            }
+            Some(Operand::Value(v)) if v == symbol::CLOSE => Err(ExprError::UnmatchedClose),
+            Some(Operand::Value(v)) => Err(ExprError::UnexpectedValue(v.clone())),
+            None => Err(ExprError::Empty),
+        }
+    }
+
+    /// Parses the operand expected after the operator `op` using `rule`.
+    fn operand_after(
+        &mut self,
+        op: &str,
+        rule: fn(&mut Self) -> Result<Expr, ExprError>,
+    ) -> Result<Expr, ExprError> {
+        match self.is_unary_next() {
+            true => rule(self),
+            false => Err(ExprError::MissingOperand(op.to_string())),
+        }
+    }
+}
+
+/// The reasons an [Expr] can fail to be parsed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ExprError {
+    Empty,
+    UnmatchedOpen,
+    UnmatchedClose,
+    MissingOperand(String),
+    LeadingOperator(String),
+    UnexpectedValue(String),
+}
+
+impl Display for ExprError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "expression is empty"),
+            Self::UnmatchedOpen => write!(f, "missing closing \"{}\"", symbol::CLOSE),
+            Self::UnmatchedClose => write!(f, "missing opening \"{}\"", symbol::OPEN),
+            Self::MissingOperand(op) => write!(f, "expected an operand after \"{}\"", op),
+            Self::LeadingOperator(op) => write!(f, "expected an operand before \"{}\"", op),
+            Self::UnexpectedValue(v) => {
+                write!(f, "value \"{}\" is not preceded by a flag", v)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn operands(words: &[&str]) -> Vec<Operand> {
+        words.iter().map(|w| Operand::new(w)).collect()
+    }
+
+    fn primary(name: &str, values: &[&str]) -> Box<Expr> {
+        Box::new(Expr::Primary(
+            name.to_string(),
+            values.iter().map(|v| v.to_string()).collect(),
+        ))
+    }
+
+    #[test]
+    fn classify_operands() {
+        assert_eq!(Operand::new("-name"), Operand::Flag(String::from("-name")));
+        assert_eq!(Operand::new("-5"), Operand::Value(String::from("-5")));
+        assert_eq!(Operand::new("-"), Operand::Value(String::from("-")));
+        assert_eq!(Operand::new("("), Operand::Value(String::from("(")));
+    }
+
+    #[test]
+    fn parse_expression() {
+        // implicit and binds tighter than or
+        assert_eq!(
+            Expr::parse(&operands(&["-name", "*.rs", "-size", "-5", "-o", "-empty"])),
+            Ok(Expr::Or(
+                Box::new(Expr::And(
+                    primary("-name", &["*.rs"]),
+                    primary("-size", &["-5"])
+                )),
+                primary("-empty", &[]),
+            ))
+        );
+
+        // grouping and negation
+        assert_eq!(
+            Expr::parse(&operands(&[
+                "(", "-name", "a", "-or", "-name", "b", ")", "!", "-empty"
+            ])),
+            Ok(Expr::And(
+                Box::new(Expr::Or(primary("-name", &["a"]), primary("-name", &["b"]))),
+                Box::new(Expr::Not(primary("-empty", &[]))),
+            ))
+        );
+
+        // explicit and
+        assert_eq!(
+            Expr::parse(&operands(&["-empty", "-and", "-not", "-readable"])),
+            Ok(Expr::And(
+                primary("-empty", &[]),
+                Box::new(Expr::Not(primary("-readable", &[]))),
+            ))
+        );
+    }
+
+    #[test]
+    fn parse_expression_errors() {
+        assert_eq!(Expr::parse(&[]), Err(ExprError::Empty));
+        assert_eq!(
+            Expr::parse(&operands(&["(", "-empty"])),
+            Err(ExprError::UnmatchedOpen)
+        );
+        assert_eq!(
+            Expr::parse(&operands(&["-empty", ")"])),
+            Err(ExprError::UnmatchedClose)
+        );
+        assert_eq!(
+            Expr::parse(&operands(&["-empty", "-o"])),
+            Err(ExprError::MissingOperand(String::from("-or")))
+        );
+        assert_eq!(
+            Expr::parse(&operands(&["-a", "-empty"])),
+            Err(ExprError::LeadingOperator(String::from("-a")))
+        );
+        assert_eq!(
+            Expr::parse(&operands(&["(", ")"])),
+            Err(ExprError::MissingOperand(String::from("(")))
+        );
+        assert_eq!(
+            Expr::parse(&operands(&["*.rs"])),
+            Err(ExprError::UnexpectedValue(String::from("*.rs")))
+        );
+    }
+}
@@ -0,0 +1,378 @@
+//! Options interpreted with the semantics of the C library's `getopt_long`.
+//!
+//! Tools being ported from C can keep their exact flag behavior by describing
+//! their short options with an option string and their long options with a
+//! table, the same as they would for `getopt_long`:
+//!
+//! ```text
+//! optstring: "ab:c::"   -a takes no argument, -b requires one, -c accepts one
+//! longopts:  --all (a), --block=SIZE (b), --color[=WHEN] (c)
+//! ```
+//!
+//! Options and operands may be intermixed, and the terminator (`--`) ends the
+//! options. A required argument is either attached (`-b512`, `--block=512`) or
+//! supplied as the next argument (`-b 512`, `--block 512`), while an optional
+//! argument must always be attached (`-cauto`, `--color=auto`). Long options may
+//! be abbreviated to any unambiguous prefix (ex: `--bl=512`).
+
+use std::fmt::Display;
+
+mod symbol {
+    pub const SWITCH: char = '-';
+    pub const FLAG: &str = "--";
+    pub const ASSIGN: char = '=';
+    pub const ARGUMENT: char = ':';
+    pub const POSIX: char = '+';
+}
+
+/// Whether an option takes an argument.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum HasArg {
+    /// The option takes no argument (`no_argument`).
+    No,
+    /// The option requires an argument (`required_argument`).
+    Required,
+    /// The option accepts an attached argument (`optional_argument`).
+    Optional,
+}
+
+/// A long option within the table of a [Getopt].
+#[derive(Debug, PartialEq, Clone)]
+struct LongOpt {
+    name: String,
+    has_arg: HasArg,
+    val: char,
+}
+
+/// The description of the options to interpret from the command-line.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Getopt {
+    short: Vec<(char, HasArg)>,
+    long: Vec<LongOpt>,
+    posix: bool,
+}
+
+impl Getopt {
+    /// Creates the description of the short options from the `optstring`.
+    ///
+    /// Each character is an option, which requires an argument when followed by
+    /// `:` and accepts an attached argument when followed by `::`. A leading `+`
+    /// stops interpreting options at the first operand. A leading `:` is
+    /// accepted for compatibility, as errors are always returned rather than
+    /// printed.
+    pub fn new<T: AsRef<str>>(optstring: T) -> Self {
+        let mut optstring = optstring.as_ref();
+        let mut posix = false;
+        while let Some(c) = optstring.chars().next() {
+            match c {
+                symbol::POSIX => posix = true,
+                symbol::ARGUMENT => (),
+                _ => break,
+            }
+            optstring = &optstring[1..];
+        }
+        let mut short = Vec::<(char, HasArg)>::new();
+        let mut chars = optstring.chars().peekable();
+        while let Some(c) = chars.next() {
+            let mut colons = 0;
+            while chars.next_if_eq(&symbol::ARGUMENT).is_some() == true {
+                colons += 1;
+            }
+            short.push((
+                c,
+                match colons {
+                    0 => HasArg::No,
+                    1 => HasArg::Required,
+                    _ => HasArg::Optional,
+                },
+            ));
+        }
+        Self {
+            short: short,
+            long: Vec::new(),
+            posix: posix,
+        }
+    }
+
+    /// Adds the long option `name` to the table, which is reported as the
+    /// option `val` the same as its short counterpart.
+    pub fn long<T: AsRef<str>>(mut self, name: T, has_arg: HasArg, val: char) -> Self {
+        self.long.push(LongOpt {
+            name: String::from(name.as_ref()),
+            has_arg: has_arg,
+            val: val,
+        });
+        self
+    }
+
+    /// Interprets the `args` as options and operands.
+    ///
+    /// This function errors on the first option that is unknown, ambiguous, or
+    /// has an invalid argument.
+    pub fn parse<T: AsRef<str>>(&self, args: &[T]) -> Result<Matches, GetoptError> {
+        let mut opts = Vec::<Opt>::new();
+        let mut operands = Vec::<String>::new();
+        let mut args = args.iter().map(|a| a.as_ref());
+        while let Some(arg) = args.next() {
+            // all arguments after the terminator are operands
+            if arg == symbol::FLAG {
+                operands.extend(args.map(String::from));
+                break;
+            // handle a long option
+            } else if let Some(long) = arg.strip_prefix(symbol::FLAG) {
+                let (name, value) = match long.split_once(symbol::ASSIGN) {
+                    Some((n, v)) => (n, Some(v.to_string())),
+                    None => (long, None),
+                };
+                let opt = self.find_long(name)?;
+                let value = match (opt.has_arg, value) {
+                    (HasArg::No, Some(_)) => {
+                        return Err(GetoptError::UnexpectedArgument(opt.name.clone()))
+                    }
+                    (HasArg::Required, None) => match args.next() {
+                        Some(v) => Some(v.to_string()),
+                        None => {
+                            return Err(GetoptError::MissingArgument(format!(
+                                "{}{}",
+                                symbol::FLAG,
+                                opt.name
+                            )))
+                        }
+                    },
+                    (_, value) => value,
+                };
+                opts.push(Opt::new(opt.val, value));
+            // handle a group of short options
+            } else if arg.len() > 1 && arg.starts_with(symbol::SWITCH) == true {
+                for (k, c) in arg.char_indices().skip(1) {
+                    let has_arg = match self.short.iter().find(|(s, _)| *s == c) {
+                        Some((_, h)) => *h,
+                        None => return Err(GetoptError::InvalidOption(c)),
+                    };
+                    // the rest of the group is the option's attached argument
+                    let rest = &arg[k + c.len_utf8()..];
+                    let value = match has_arg {
+                        HasArg::No => {
+                            opts.push(Opt::new(c, None));
+                            continue;
+                        }
+                        HasArg::Required if rest.is_empty() == true => match args.next() {
+                            Some(v) => Some(v.to_string()),
+                            None => {
+                                return Err(GetoptError::MissingArgument(format!(
+                                    "{}{}",
+                                    symbol::SWITCH,
+                                    c
+                                )))
+                            }
+                        },
+                        HasArg::Optional if rest.is_empty() == true => None,
+                        _ => Some(rest.to_string()),
+                    };
+                    opts.push(Opt::new(c, value));
+                    break;
+                }
+            // handle an operand
+            } else {
+                operands.push(arg.to_string());
+                if self.posix == true {
+                    operands.extend(args.map(String::from));
+                    break;
+                }
+            }
+        }
+        Ok(Matches {
+            opts: opts,
+            operands: operands,
+        })
+    }
+
+    /// Finds the long option named `name` or uniquely abbreviated by `name`.
+    fn find_long(&self, name: &str) -> Result<&LongOpt, GetoptError> {
+        if let Some(opt) = self.long.iter().find(|o| o.name == name) {
+            return Ok(opt);
+        }
+        let candidates: Vec<&LongOpt> = self
+            .long
+            .iter()
+            .filter(|o| o.name.starts_with(name))
+            .collect();
+        match candidates.first() {
+            None => Err(GetoptError::UnrecognizedOption(name.to_string())),
+            // abbreviations are only ambiguous if they lead to different options
+            Some(first)
+                if candidates
+                    .iter()
+                    .all(|o| o.val == first.val && o.has_arg == first.has_arg)
+                    == true =>
+            {
+                Ok(first)
+            }
+            Some(_) => Err(GetoptError::AmbiguousOption(
+                name.to_string(),
+                candidates.iter().map(|o| o.name.clone()).collect(),
+            )),
+        }
+    }
+}
+
+/// An option found on the command-line.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Opt {
+    val: char,
+    arg: Option<String>,
+}
+
+impl Opt {
+    fn new(val: char, arg: Option<String>) -> Self {
+        Self { val: val, arg: arg }
+    }
+
+    /// Returns the character that identifies the option.
+    pub fn val(&self) -> char {
+        self.val
+    }
+
+    /// References the option's argument, if one was supplied.
+    pub fn arg(&self) -> Option<&str> {
+        self.arg.as_deref()
+    }
+}
+
+/// The options and operands interpreted by a [Getopt].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Matches {
+    opts: Vec<Opt>,
+    operands: Vec<String>,
+}
+
+impl Matches {
+    /// References the options in the order they were supplied.
+    pub fn opts(&self) -> &[Opt] {
+        &self.opts
+    }
+
+    /// References the operands in the order they were supplied.
+    pub fn operands(&self) -> &[String] {
+        &self.operands
+    }
+}
+
+/// The reasons the command-line can fail to be interpreted by a [Getopt].
+#[derive(Debug, PartialEq, Clone)]
+pub enum GetoptError {
+    /// A short option is not in the option string.
+    InvalidOption(char),
+    /// A long option is not in the table.
+    UnrecognizedOption(String),
+    /// A long option abbreviation matches multiple options.
+    AmbiguousOption(String, Vec<String>),
+    /// An option that requires an argument was the last argument.
+    MissingArgument(String),
+    /// A long option that takes no argument had one attached.
+    UnexpectedArgument(String),
+}
+
+impl Display for GetoptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidOption(c) => write!(f, "invalid option -- '{}'", c),
+            Self::UnrecognizedOption(name) => write!(f, "unrecognized option '--{}'", name),
+            Self::AmbiguousOption(name, candidates) => write!(
+                f,
+                "option '--{}' is ambiguous; possibilities: {}",
+                name,
+                candidates
+                    .iter()
+                    .map(|c| format!("'--{}'", c))
+                    .collect::<Vec<String>>()
+                    .join(" ")
+            ),
+            Self::MissingArgument(opt) => match opt.starts_with(symbol::FLAG) {
+                true => write!(f, "option '{}' requires an argument", opt),
+                false => write!(
+                    f,
+                    "option requires an argument -- '{}'",
+                    &opt[symbol::SWITCH.len_utf8()..]
+                ),
+            },
+            Self::UnexpectedArgument(name) => {
+                write!(f, "option '--{}' doesn't allow an argument", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GetoptError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn spec() -> Getopt {
+        Getopt::new("ab:c::")
+            .long("all", HasArg::No, 'a')
+            .long("block", HasArg::Required, 'b')
+            .long("color", HasArg::Optional, 'c')
+            .long("colour", HasArg::Optional, 'c')
+    }
+
+    fn opts(m: &Matches) -> Vec<(char, Option<&str>)> {
+        m.opts().iter().map(|o| (o.val(), o.arg())).collect()
+    }
+
+    #[test]
+    fn parse_options() {
+        let m = spec()
+            .parse(&[
+                "-ab", "512", "x", "-cauto", "-c", "--bl=4", "--col", "y", "--", "-a",
+            ])
+            .unwrap();
+        assert_eq!(
+            opts(&m),
+            vec![
+                ('a', None),
+                ('b', Some("512")),
+                ('c', Some("auto")),
+                ('c', None),
+                ('b', Some("4")),
+                ('c', None),
+            ]
+        );
+        assert_eq!(m.operands(), &["x", "y", "-a"]);
+
+        // a leading '+' stops at the first operand
+        let m = Getopt::new("+a").parse(&["-a", "-", "-a"]).unwrap();
+        assert_eq!(opts(&m), vec![('a', None)]);
+        assert_eq!(m.operands(), &["-", "-a"]);
+    }
+
+    #[test]
+    fn parse_errors() {
+        let spec = spec().long("blink", HasArg::No, 'k');
+        assert_eq!(
+            spec.parse(&["-ax"]).unwrap_err().to_string(),
+            "invalid option -- 'x'"
+        );
+        assert_eq!(
+            spec.parse(&["--bl"]).unwrap_err().to_string(),
+            "option '--bl' is ambiguous; possibilities: '--block' '--blink'"
+        );
+        assert_eq!(
+            spec.parse(&["-b"]).unwrap_err().to_string(),
+            "option requires an argument -- 'b'"
+        );
+        assert_eq!(
+            spec.parse(&["--block"]).unwrap_err().to_string(),
+            "option '--block' requires an argument"
+        );
+        assert_eq!(
+            spec.parse(&["--all=yes"]),
+            Err(GetoptError::UnexpectedArgument(String::from("all")))
+        );
+        assert_eq!(
+            spec.parse(&["--none"]),
+            Err(GetoptError::UnrecognizedOption(String::from("none")))
+        );
+    }
+}
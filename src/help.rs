@@ -1,16 +1,168 @@
-use crate::arg::{Arg, Flag, Raisable};
+use crate::arg::{Arg, ArgState, ArgType, Flag, Raisable};
+use std::fmt::Display;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
 
 mod tag {
     pub const FLAG: &str = "help";
     pub const SWITCH: char = 'h';
+    pub const ALL: &str = "all";
+}
+
+mod placeholder {
+    pub const BIN: &str = "{bin}";
+    pub const VERSION: &str = "{version}";
+    pub const USAGE: &str = "{usage}";
+    pub const OPTIONS: &str = "{options}";
+    pub const SUMMARY: &str = "{summary}";
+    pub const DESCRIPTION: &str = "{description}";
+    pub const COMMANDS: &str = "{commands}";
+}
+
+/// Informational text that is shared between clones.
+#[derive(Clone)]
+enum Text {
+    Eager(Arc<str>),
+    Lazy(Arc<Deferred>),
+}
+
+/// Text that is built the first time it is read.
+struct Deferred {
+    build: Box<dyn Fn() -> String + Send + Sync>,
+    text: OnceLock<String>,
+}
+
+impl Text {
+    fn new<T: AsRef<str>>(text: T) -> Self {
+        Self::Eager(Arc::from(text.as_ref()))
+    }
+
+    fn lazy<F: Fn() -> String + Send + Sync + 'static>(build: F) -> Self {
+        Self::Lazy(Arc::new(Deferred {
+            build: Box::new(build),
+            text: OnceLock::new(),
+        }))
+    }
+
+    /// References the text, building it if it was deferred.
+    fn get(&self) -> &str {
+        match self {
+            Self::Eager(text) => text,
+            Self::Lazy(d) => d.text.get_or_init(|| (d.build)()),
+        }
+    }
+}
+
+impl PartialEq for Text {
+    /// Deferred text is only equal to itself, so comparing never builds it.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Eager(a), Self::Eager(b)) => a == b,
+            (Self::Lazy(a), Self::Lazy(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+impl std::fmt::Debug for Text {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Eager(text) => write!(f, "{:?}", text),
+            Self::Lazy(d) => match d.text.get() {
+                Some(text) => write!(f, "{:?}", text),
+                None => write!(f, "Lazy"),
+            },
+        }
+    }
+}
+
+/// A headed section of informational text.
+#[derive(Debug, PartialEq, Clone)]
+struct Category {
+    name: Arc<str>,
+    text: Text,
+    hidden: bool,
+}
+
+/// The output formats of the [Help] flag's informational text, as requested on
+/// the command-line through `--help=<format>`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HelpFormat {
+    /// The text is written as it is, including any color escape sequences.
+    #[default]
+    Ansi,
+    /// The text is written without any color escape sequences.
+    Plain,
+    /// The text is written as a roff manual page.
+    Man,
+}
+
+impl FromStr for HelpFormat {
+    type Err = HelpFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansi" => Ok(Self::Ansi),
+            "plain" => Ok(Self::Plain),
+            "man" => Ok(Self::Man),
+            _ => Err(HelpFormatError),
+        }
+    }
+}
+
+impl Display for HelpFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Ansi => write!(f, "ansi"),
+            Self::Plain => write!(f, "plain"),
+            Self::Man => write!(f, "man"),
+        }
+    }
+}
+
+/// The error for a [HelpFormat] that is not one of the accepted values.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct HelpFormatError;
+
+impl Display for HelpFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected one of \"plain\", \"ansi\", or \"man\"")
+    }
+}
+
+impl std::error::Error for HelpFormatError {}
+
+/// How the [Help] of a nested command relates to the help of the command it is
+/// nested within, see [push_help][crate::Cli::push_help].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum HelpInheritance {
+    /// The parent's help is replaced entirely.
+    #[default]
+    Replace,
+    /// The parent's text, categories, and usage line are kept for anything this
+    /// help does not set itself.
+    Text,
+    /// The usage line is written after the parent's usage line.
+    Usage,
 }
 
 /// A special flag that can have priority over other arguments in command-line
 /// processing.
+///
+/// The informational text is shared between clones, so cloning a [Help] into
+/// every error is cheap regardless of the text's length.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Help {
     arg: Flag,
-    text: String,
+    /// The informational text, which is `None` until it is set.
+    text: Option<Text>,
+    usage: Option<Arc<str>>,
+    categories: Vec<Category>,
+    show_all: bool,
+    inheritance: HelpInheritance,
+    selector: bool,
+    format: HelpFormat,
+    title: Option<Arc<str>>,
 }
 
 impl Help {
@@ -21,7 +173,14 @@ impl Help {
     pub fn new() -> Self {
         Self {
             arg: Flag::new(tag::FLAG).switch(tag::SWITCH),
-            text: String::new(),
+            text: None,
+            usage: None,
+            categories: Vec::new(),
+            show_all: false,
+            inheritance: HelpInheritance::Replace,
+            selector: false,
+            format: HelpFormat::Ansi,
+            title: None,
         }
     }
 
@@ -32,7 +191,48 @@ impl Help {
     pub fn with<T: AsRef<str>>(text: T) -> Self {
         Self {
             arg: Flag::new(tag::FLAG).switch(tag::SWITCH),
-            text: String::from(text.as_ref()),
+            text: Some(Text::new(text)),
+            usage: None,
+            categories: Vec::new(),
+            show_all: false,
+            inheritance: HelpInheritance::Replace,
+            selector: false,
+            format: HelpFormat::Ansi,
+            title: None,
+        }
+    }
+
+    /// Create a new [Help] flag whose informational text is built by `build`.
+    ///
+    /// The text is only built the first time it is displayed, which avoids the
+    /// cost of composing a large manual when help is never requested.
+    pub fn lazy<F: Fn() -> String + Send + Sync + 'static>(build: F) -> Self {
+        Self {
+            arg: Flag::new(tag::FLAG).switch(tag::SWITCH),
+            text: Some(Text::lazy(build)),
+            usage: None,
+            categories: Vec::new(),
+            show_all: false,
+            inheritance: HelpInheritance::Replace,
+            selector: false,
+            format: HelpFormat::Ansi,
+            title: None,
+        }
+    }
+
+    /// Create a new [Help] flag whose informational text is the `template`
+    /// expanded with the metadata in `spec`.
+    ///
+    /// The placeholders `{bin}`, `{version}`, `{usage}`, `{summary}`,
+    /// `{description}`, `{options}`, and `{commands}` are replaced the first time
+    /// the text is displayed, so hand-written prose can be mixed with generated
+    /// tables. The usage line is taken from `spec`.
+    pub fn from_spec<T: AsRef<str>>(spec: HelpSpec, template: T) -> Self {
+        let usage = spec.usage.as_deref().map(Arc::from);
+        let template = template.as_ref().to_string();
+        Self {
+            usage: usage,
+            ..Self::lazy(move || spec.render(&template))
         }
     }
 
@@ -53,10 +253,233 @@ impl Help {
 
     /// Set the [Help] flag's informational text to `t`.
     pub fn text<T: AsRef<str>>(mut self, t: T) -> Self {
-        self.text = t.as_ref().to_string();
+        self.text = Some(Text::new(t));
+        self
+    }
+
+    /// Set the [Help] flag's usage line to `line` (ex: `app [options] <file>`).
+    ///
+    /// The usage line is displayed in errors for missing options and positionals
+    /// to show the expected shape of the command.
+    pub fn usage<T: AsRef<str>>(mut self, line: T) -> Self {
+        self.usage = Some(Arc::from(line.as_ref()));
+        self
+    }
+
+    /// Add a category of informational text `text` under the heading `name`.
+    ///
+    /// Categories are displayed after the [Help] flag's text in the order they are added.
+    pub fn category<T: AsRef<str>, U: AsRef<str>>(mut self, name: T, text: U) -> Self {
+        self.categories.push(Category {
+            name: Arc::from(name.as_ref()),
+            text: Text::new(text),
+            hidden: false,
+        });
+        self
+    }
+
+    /// Add a category of informational text `text` under the heading `name` that
+    /// is only displayed when `--all` is raised along with the [Help] flag
+    /// (ex: `--help --all`).
+    pub fn hidden_category<T: AsRef<str>, U: AsRef<str>>(mut self, name: T, text: U) -> Self {
+        self.categories.push(Category {
+            name: Arc::from(name.as_ref()),
+            text: Text::new(text),
+            hidden: true,
+        });
         self
     }
 
+    /// Set how the [Help] flag relates to the help of the command it is nested
+    /// within.
+    ///
+    /// The parent's help is [replaced][HelpInheritance::Replace] by default.
+    pub fn inherit(mut self, inheritance: HelpInheritance) -> Self {
+        self.inheritance = inheritance;
+        self
+    }
+
+    /// Combines the [Help] flag with the help of the command it is nested within,
+    /// `parent`, according to its inheritance.
+    pub(crate) fn nest_within(mut self, parent: &Help) -> Self {
+        match self.inheritance {
+            HelpInheritance::Replace => (),
+            HelpInheritance::Text => {
+                if self.text.is_none() == true {
+                    self.text = parent.text.clone();
+                }
+                if self.usage.is_none() == true {
+                    self.usage = parent.usage.clone();
+                }
+                self.categories = parent
+                    .categories
+                    .iter()
+                    .cloned()
+                    .chain(self.categories)
+                    .collect();
+            }
+            HelpInheritance::Usage => {
+                self.usage = match (&parent.usage, self.usage) {
+                    (Some(p), Some(u)) => Some(Arc::from(format!("{}\n{}", p, u))),
+                    (p, u) => u.or(p.clone()),
+                }
+            }
+        }
+        self
+    }
+
+    /// Allow the output format to be selected on the command-line by attaching it
+    /// to the [Help] flag (ex: `--help=man`), see [HelpFormat].
+    pub fn format_selector(mut self) -> Self {
+        self.selector = true;
+        self
+    }
+
+    /// Checks if the output format can be selected on the command-line.
+    pub(crate) fn has_format_selector(&self) -> bool {
+        self.selector
+    }
+
+    /// Displays the text in the `format` selected on the command-line for the
+    /// command named `title`.
+    pub(crate) fn select(&mut self, format: HelpFormat, title: String) -> () {
+        self.format = format;
+        self.title = Some(Arc::from(title));
+    }
+
+    /// Checks if any categories are only displayed with `--all`.
+    pub(crate) fn has_hidden(&self) -> bool {
+        self.categories.iter().any(|c| c.hidden == true)
+    }
+
+    /// Displays the hidden categories along with the visible ones.
+    pub(crate) fn show_all(&mut self) -> () {
+        self.show_all = true;
+    }
+
+    /// The flag that reveals the hidden categories.
+    pub(crate) fn get_all_arg(&self) -> Arg<Raisable> {
+        Arg::flag(tag::ALL)
+    }
+
+    /// Composes the informational text with its categories that are displayed.
+    pub(crate) fn compose(&self) -> String {
+        self.categories
+            .iter()
+            .filter(|c| c.hidden == false || self.show_all == true)
+            .fold(self.get_text().to_string(), |acc, c| {
+                let text = format!("{}:\n{}", c.name, c.text.get());
+                match acc.is_empty() {
+                    true => text,
+                    false => format!("{}\n\n{}", acc, text),
+                }
+            })
+    }
+
+    /// Renders the informational text with its categories that are displayed in
+    /// the `format`, wrapping lines at `width` visible characters if one is given.
+    ///
+    /// Manual pages are never wrapped because the pager wraps them instead.
+    pub fn render<T: AsRef<str>>(
+        &self,
+        format: HelpFormat,
+        title: T,
+        width: Option<usize>,
+    ) -> String {
+        let text = match format {
+            HelpFormat::Ansi => self.compose(),
+            HelpFormat::Plain => strip_ansi(&self.compose()),
+            HelpFormat::Man => return self.to_man(title),
+        };
+        match width {
+            Some(w) => crate::error::utils::wrap(&text, w),
+            None => text,
+        }
+    }
+
+    /// Renders the text in the format selected on the command-line, wrapping
+    /// lines at `width` visible characters if one is given.
+    pub(crate) fn display(&self, width: Option<usize>) -> String {
+        self.render(
+            self.format,
+            self.title.as_deref().unwrap_or_default(),
+            width,
+        )
+    }
+
+    /// Renders the informational text and every category, including hidden ones,
+    /// as a roff manual page in section 1 titled `title` (ex: the command path).
+    ///
+    /// The text is written in no-fill mode to preserve its formatting.
+    pub fn to_man<T: AsRef<str>>(&self, title: T) -> String {
+        let title = title.as_ref().to_uppercase();
+        self.sections().into_iter().fold(
+            format!(".TH \"{}\" 1\n", escape_roff(&title)),
+            |acc, (name, text)| {
+                let heading = name.unwrap_or("DESCRIPTION").to_uppercase();
+                format!(
+                    "{}.SH \"{}\"\n.nf\n{}\n.fi\n",
+                    acc,
+                    escape_roff(&heading),
+                    escape_roff(&strip_ansi(text))
+                )
+            },
+        )
+    }
+
+    /// Renders the informational text and every category, including hidden ones,
+    /// as a Markdown document under the heading `title` (ex: the command path).
+    ///
+    /// The text is written in code blocks to preserve its formatting.
+    pub fn to_markdown<T: AsRef<str>>(&self, title: T) -> String {
+        self.sections()
+            .into_iter()
+            .fold(format!("# {}\n", title.as_ref()), |acc, (name, text)| {
+                let fence = "`".repeat(longest_run(text, '`').max(2) + 1);
+                let heading = match name {
+                    Some(n) => format!("## {}\n\n", n),
+                    None => String::new(),
+                };
+                format!("{}\n{}{}text\n{}\n{}\n", acc, heading, fence, text, fence)
+            })
+    }
+
+    /// Renders the informational text and every category, including hidden ones,
+    /// as a standalone HTML page titled `title` (ex: the command path).
+    ///
+    /// The text is written in preformatted blocks to preserve its formatting.
+    pub fn to_html<T: AsRef<str>>(&self, title: T) -> String {
+        let title = escape_html(title.as_ref());
+        let body = self
+            .sections()
+            .into_iter()
+            .map(|(name, text)| {
+                let heading = match name {
+                    Some(n) => format!("<h2>{}</h2>\n", escape_html(n)),
+                    None => String::new(),
+                };
+                format!("{}<pre>{}</pre>\n", heading, escape_html(text))
+            })
+            .collect::<String>();
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n<h1>{}</h1>\n{}</body>\n</html>\n",
+            title, title, body
+        )
+    }
+
+    /// Lists the informational text followed by every category as pairs of
+    /// headings and text, skipping any empty informational text.
+    fn sections(&self) -> Vec<(Option<&str>, &str)> {
+        std::iter::once((None, self.get_text()))
+            .filter(|(_, t)| t.is_empty() == false)
+            .chain(
+                self.categories
+                    .iter()
+                    .map(|c| (Some(c.name.as_ref()), c.text.get())),
+            )
+            .collect()
+    }
+
     /// Transform the [Help] flag into its [Arg].
     pub fn get_arg(&self) -> Arg<Raisable> {
         match self.arg.get_switch() {
@@ -65,8 +488,445 @@ impl Help {
         }
     }
 
+    /// Sets the `symbol` the [Help] flag is displayed with, see
+    /// [switch_symbol][crate::Cli::switch_symbol].
+    pub(crate) fn set_symbol(&mut self, symbol: char) -> () {
+        self.arg.set_symbol(symbol);
+    }
+
+    /// References the [Help] flag as it is displayed.
+    pub(crate) fn get_flag(&self) -> &Flag {
+        &self.arg
+    }
+
+    /// Access the [Help] flag's usage line, if one exists.
+    pub fn get_usage(&self) -> Option<&str> {
+        self.usage.as_deref()
+    }
+
     /// Access the [Help] flag's informational text.
+    ///
+    /// Text created with [lazy][Help::lazy] is built on its first access.
     pub fn get_text(&self) -> &str {
-        self.text.as_ref()
+        self.text.as_ref().map_or("", |t| t.get())
+    }
+}
+
+/// The metadata of a command that fills in the placeholders of a [Help]
+/// template, see [from_spec][Help::from_spec].
+#[derive(Debug, PartialEq, Clone)]
+pub struct HelpSpec {
+    bin: String,
+    version: Option<String>,
+    usage: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    /// The flags and options of the options table along with their summaries
+    options: Vec<(ArgType, String)>,
+    commands: Vec<(String, String)>,
+    unstable: bool,
+    symbol: char,
+}
+
+impl HelpSpec {
+    /// Create the metadata for the program named `bin`.
+    pub fn new<T: AsRef<str>>(bin: T) -> Self {
+        Self {
+            bin: bin.as_ref().to_string(),
+            version: None,
+            usage: None,
+            summary: None,
+            description: None,
+            options: Vec::new(),
+            commands: Vec::new(),
+            unstable: false,
+            symbol: '-',
+        }
+    }
+
+    /// Set the program's `version` (ex: `1.2.0`).
+    pub fn version<T: AsRef<str>>(mut self, version: T) -> Self {
+        self.version = Some(version.as_ref().to_string());
+        self
+    }
+
+    /// Set the usage line to `line` (ex: `app [options] <file>`).
+    pub fn usage<T: AsRef<str>>(mut self, line: T) -> Self {
+        self.usage = Some(line.as_ref().to_string());
+        self
+    }
+
+    /// Set the one-line `summary` of the command, which is also the text shown
+    /// when the command is listed in its parent's help.
+    pub fn summary<T: AsRef<str>>(mut self, summary: T) -> Self {
+        self.summary = Some(summary.as_ref().to_string());
+        self
+    }
+
+    /// Set the long `description` of the command, which is only shown in the
+    /// command's own help.
+    ///
+    /// The `{description}` placeholder expands to the summary when no
+    /// description is set.
+    pub fn description<T: AsRef<str>>(mut self, description: T) -> Self {
+        self.description = Some(description.as_ref().to_string());
+        self
+    }
+
+    /// Add the subcommand `name` to the commands table along with its one-line
+    /// `summary` (ex: from a [Node][crate::proc::Node] of a router).
+    pub fn command<T: AsRef<str>, U: AsRef<str>>(mut self, name: T, summary: U) -> Self {
+        self.commands
+            .push((name.as_ref().to_string(), summary.as_ref().to_string()));
+        self
+    }
+
+    /// Add the flag or option `arg` to the options table along with a one-line
+    /// `summary`.
+    ///
+    /// Positionals are not listed in the options table. Experimental arguments
+    /// are only listed while they are enabled, see [unstable][HelpSpec::unstable].
+    pub fn arg<S: ArgState, T: AsRef<str>>(mut self, arg: Arg<S>, summary: T) -> Self {
+        match ArgType::from(arg) {
+            ArgType::Positional(_) => (),
+            arg => self.options.push((arg, summary.as_ref().to_string())),
+        }
+        self
+    }
+
+    /// Set the `symbol` that flags and switches are written with in the options
+    /// table, see [switch_symbol][crate::Cli::switch_symbol].
+    pub fn switch_symbol(mut self, symbol: char) -> Self {
+        self.symbol = symbol;
+        self
+    }
+
+    /// List every experimental argument in the options table when `enabled`
+    /// (ex: the result of [check_unstable][crate::Cli::check_unstable]).
+    ///
+    /// Otherwise, an experimental argument is only listed when the environment
+    /// variable that enables it is set.
+    pub fn unstable(mut self, enabled: bool) -> Self {
+        self.unstable = enabled;
+        self
+    }
+
+    /// Expands the placeholders in `template` with the metadata.
+    ///
+    /// Missing metadata expands to nothing, and unknown placeholders are left as
+    /// they are written.
+    pub fn render<T: AsRef<str>>(&self, template: T) -> String {
+        template
+            .as_ref()
+            .replace(placeholder::BIN, &self.bin)
+            .replace(
+                placeholder::VERSION,
+                self.version.as_deref().unwrap_or_default(),
+            )
+            .replace(
+                placeholder::USAGE,
+                self.usage.as_deref().unwrap_or_default(),
+            )
+            .replace(
+                placeholder::SUMMARY,
+                self.summary.as_deref().unwrap_or_default(),
+            )
+            .replace(
+                placeholder::DESCRIPTION,
+                self.description
+                    .as_deref()
+                    .or(self.summary.as_deref())
+                    .unwrap_or_default(),
+            )
+            .replace(placeholder::OPTIONS, &Self::table(&self.listed_options()))
+            .replace(placeholder::COMMANDS, &Self::table(&self.commands))
+    }
+
+    /// Collects the rows of the options table, excluding the experimental
+    /// arguments that are not enabled.
+    fn listed_options(&self) -> Vec<(String, String)> {
+        self.options
+            .iter()
+            .filter_map(|(arg, summary)| {
+                let mut arg = arg.clone();
+                arg.set_symbol(self.symbol);
+                let flag = match &arg {
+                    ArgType::Flag(f) => f,
+                    ArgType::Optional(o) => o.get_flag(),
+                    ArgType::Positional(_) => return None,
+                };
+                if let Some(var) = flag.get_unstable() {
+                    if self.unstable == false && std::env::var_os(var).is_none() == true {
+                        return None;
+                    }
+                }
+                // a flag without a long name is written only in the switch column
+                let label = match (flag.is_switch_only(), flag.get_switch()) {
+                    (true, _) => arg.to_string(),
+                    (false, Some(c)) => format!("{}{}, {}", self.symbol, c, arg),
+                    (false, None) => format!("    {}", arg),
+                };
+                Some((label, summary.clone()))
+            })
+            .collect()
+    }
+
+    /// Writes the table of `rows` with each summary aligned after the widest
+    /// label.
+    fn table(rows: &[(String, String)]) -> String {
+        let width = rows.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+        rows.iter()
+            .map(|(label, summary)| {
+                format!("  {:<width$}  {}", label, summary, width = width)
+                    .trim_end()
+                    .to_string()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+/// Removes the terminal escape sequences from `text`, such as colors (SGR),
+/// cursor movements (CSI), and hyperlinks (OSC).
+fn strip_ansi(text: &str) -> String {
+    /// The position within an escape sequence.
+    enum State {
+        Text,
+        Escape,
+        Csi,
+        Osc,
+        OscEscape,
+    }
+    let mut state = State::Text;
+    text.chars()
+        .filter(|c| {
+            let (next, keep) = match (&state, c) {
+                (State::Text, '\x1b') => (State::Escape, false),
+                (State::Text, _) => (State::Text, true),
+                (State::Escape, '[') => (State::Csi, false),
+                (State::Escape, ']') => (State::Osc, false),
+                // any other escape is a single character long
+                (State::Escape, _) => (State::Text, false),
+                // a control sequence ends at its final byte
+                (State::Csi, '\x40'..='\x7e') => (State::Text, false),
+                (State::Csi, _) => (State::Csi, false),
+                // an operating system command ends at BEL or ST (`ESC \`)
+                (State::Osc, '\x07') => (State::Text, false),
+                (State::Osc, '\x1b') => (State::OscEscape, false),
+                (State::Osc, _) => (State::Osc, false),
+                (State::OscEscape, '\\') => (State::Text, false),
+                (State::OscEscape, _) => (State::Osc, false),
+            };
+            state = next;
+            keep
+        })
+        .collect()
+}
+
+/// Escapes the characters in `text` that are reserved in roff, including
+/// lines that would otherwise be read as requests.
+fn escape_roff(text: &str) -> String {
+    text.replace('\\', "\\e")
+        .split('\n')
+        .map(
+            |line| match line.starts_with('.') || line.starts_with('\'') {
+                true => format!("\\&{}", line),
+                false => line.to_string(),
+            },
+        )
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Counts the longest consecutive run of the character `c` in `text`.
+fn longest_run(text: &str, c: char) -> usize {
+    text.split(|x| x != c).map(|r| r.len()).max().unwrap_or(0)
+}
+
+/// Escapes the characters in `text` that are reserved in HTML.
+fn escape_html(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '&' => String::from("&amp;"),
+            '<' => String::from("&lt;"),
+            '>' => String::from("&gt;"),
+            '"' => String::from("&quot;"),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn render_markdown() {
+        let help = Help::with("Usage: app [options] <file>")
+            .category("Options", "--verbose  print more")
+            .hidden_category("Debug", "--trace  use ```trace```");
+        assert_eq!(
+            help.to_markdown("app"),
+            "# app\n\n```text\nUsage: app [options] <file>\n```\n\n## Options\n\n```text\n--verbose  print more\n```\n\n## Debug\n\n````text\n--trace  use ```trace```\n````\n"
+        );
+        assert_eq!(Help::new().to_markdown("app"), "# app\n");
+    }
+
+    #[test]
+    fn lazy_text() {
+        let built = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = built.clone();
+        let help = Help::lazy(move || {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            String::from("Usage: app <file>")
+        })
+        .category("Notes", "none");
+        let copy = help.clone();
+        assert_eq!(help, copy);
+        assert_eq!(built.load(std::sync::atomic::Ordering::SeqCst), 0);
+
+        // the text is built once and shared between clones
+        assert_eq!(copy.compose(), "Usage: app <file>\n\nNotes:\nnone");
+        assert_eq!(help.get_text(), "Usage: app <file>");
+        assert_eq!(built.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_ne!(help, Help::with("Usage: app <file>"));
+    }
+
+    #[test]
+    fn nest_within_parent() {
+        let parent = Help::with("Usage: app <command>")
+            .usage("app <command>")
+            .category("Options", "--verbose  print more");
+        let child = Help::new().category("Targets", "lib  the library");
+        assert_eq!(child.clone().nest_within(&parent), child);
+
+        let child = child.inherit(HelpInheritance::Text).nest_within(&parent);
+        assert_eq!(child.get_usage(), Some("app <command>"));
+        assert_eq!(
+            child.compose(),
+            "Usage: app <command>\n\nOptions:\n--verbose  print more\n\nTargets:\nlib  the library"
+        );
+
+        let child = Help::with("Usage: app build <target>")
+            .usage("app build <target>")
+            .inherit(HelpInheritance::Usage)
+            .nest_within(&parent);
+        assert_eq!(child.get_usage(), Some("app <command>\napp build <target>"));
+        assert_eq!(child.get_text(), "Usage: app build <target>");
+
+        // lazy text is kept without being built
+        let built = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = built.clone();
+        let child = Help::lazy(move || {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            String::from("Usage: app build <target>")
+        })
+        .inherit(HelpInheritance::Text)
+        .nest_within(&parent);
+        assert_eq!(child.get_usage(), Some("app <command>"));
+        assert_eq!(built.load(std::sync::atomic::Ordering::SeqCst), 0);
+        assert_eq!(child.get_text(), "Usage: app build <target>");
+        assert_eq!(built.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn expand_template() {
+        let spec = HelpSpec::new("app")
+            .version("1.2.0")
+            .usage("app [options] <file>")
+            .arg(Arg::flag("verbose").switch('v'), "Print more output")
+            .arg(Arg::option("jobs").value("n"), "Run n jobs at once")
+            .arg(Arg::switch_only('p'), "Preserve attributes")
+            .arg(Arg::positional("file"), "The file to read");
+        let help = Help::from_spec(
+            spec,
+            "{bin} {version}\n\nUsage: {usage}\n\nOptions:\n{options}\n\n{unknown}",
+        );
+        assert_eq!(help.get_usage(), Some("app [options] <file>"));
+        assert_eq!(
+            help.get_text(),
+            "app 1.2.0\n\nUsage: app [options] <file>\n\nOptions:\n  -v, --verbose   Print more output\n      --jobs <n>  Run n jobs at once\n  -p              Preserve attributes\n\n{unknown}"
+        );
+        assert_eq!(HelpSpec::new("app").render("{bin} {version}"), "app ");
+
+        let spec = HelpSpec::new("app")
+            .switch_symbol('/')
+            .arg(Arg::flag("verbose").switch('v'), "Print more output")
+            .arg(Arg::switch_only('p'), "Preserve attributes");
+        assert_eq!(
+            spec.render("{options}"),
+            "  /v, //verbose  Print more output\n  /p             Preserve attributes"
+        );
+    }
+
+    #[test]
+    fn hide_unstable_options() {
+        let spec = HelpSpec::new("app")
+            .arg(Arg::flag("verbose"), "Print more output")
+            .arg(
+                Arg::flag("new-resolver").unstable("CLIPROC_TEST_HIDE_UNSTABLE"),
+                "Use the new resolver",
+            );
+        assert_eq!(
+            spec.render("{options}"),
+            "      --verbose  Print more output"
+        );
+        assert_eq!(
+            spec.unstable(true).render("{options}"),
+            "      --verbose       Print more output\n      --new-resolver  Use the new resolver"
+        );
+    }
+
+    #[test]
+    fn expand_summary_and_description() {
+        let parent = HelpSpec::new("app")
+            .command("build", "Compile the project")
+            .command("run", "Compile and run the project");
+        assert_eq!(
+            parent.render("Commands:\n{commands}"),
+            "Commands:\n  build  Compile the project\n  run    Compile and run the project"
+        );
+
+        let spec = HelpSpec::new("app build").summary("Compile the project");
+        assert_eq!(spec.render("{description}"), "Compile the project");
+        let spec = spec.description("Compile the project.\n\nArtifacts are written to 'target/'.");
+        assert_eq!(
+            spec.render("{bin}: {summary}\n\n{description}"),
+            "app build: Compile the project\n\nCompile the project.\n\nArtifacts are written to 'target/'."
+        );
+    }
+
+    #[test]
+    fn render_formats() {
+        let help = Help::with("Usage: \x1b[1mapp\x1b[0m <file>\n.hidden \\ text")
+            .hidden_category("Notes", "see 'man app'");
+        assert_eq!(
+            help.render(HelpFormat::Plain, "app", Some(10)),
+            "Usage: app\n<file>\n.hidden \\\ntext"
+        );
+        assert_eq!(
+            help.render(HelpFormat::Man, "app", Some(10)),
+            ".TH \"APP\" 1\n.SH \"DESCRIPTION\"\n.nf\nUsage: app <file>\n\\&.hidden \\e text\n.fi\n.SH \"NOTES\"\n.nf\nsee 'man app'\n.fi\n"
+        );
+        // sequences other than colors are removed without their surrounding text
+        assert_eq!(
+            strip_ansi("\x1b[2KUsage: my app\x1b[1;31m!\x1b[0m"),
+            "Usage: my app!"
+        );
+        assert_eq!(
+            strip_ansi("see \x1b]8;;https://example.com\x07docs\x1b]8;;\x1b\\ here"),
+            "see docs here"
+        );
+        assert_eq!("man".parse::<HelpFormat>(), Ok(HelpFormat::Man));
+        assert_eq!("roff".parse::<HelpFormat>(), Err(HelpFormatError));
+    }
+
+    #[test]
+    fn render_html() {
+        let help = Help::with("Usage: app <file>").category("Notes", "a & b");
+        assert_eq!(
+            help.to_html("app"),
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>app</title>\n</head>\n<body>\n<h1>app</h1>\n<pre>Usage: app &lt;file&gt;</pre>\n<h2>Notes</h2>\n<pre>a &amp; b</pre>\n</body>\n</html>\n"
+        );
     }
 }
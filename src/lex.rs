@@ -0,0 +1,130 @@
+//! Splitting a line of text into arguments the way a POSIX shell does.
+//!
+//! Arguments are separated by unquoted whitespace. Quoting follows the shell's
+//! rules without any expansions:
+//!
+//! - text within single quotes (`'...'`) is taken literally
+//! - text within double quotes (`"..."`) is taken literally, except a backslash
+//!   escapes a following `"`, `\`, `$`, or `` ` ``
+//! - outside of quotes, a backslash escapes the following character
+
+use std::fmt::Display;
+
+mod symbol {
+    pub const SINGLE_QUOTE: char = '\'';
+    pub const DOUBLE_QUOTE: char = '"';
+    pub const ESCAPE: char = '\\';
+    /// The characters a backslash escapes within double quotes.
+    pub const ESCAPABLE: [char; 4] = ['"', '\\', '$', '`'];
+}
+
+/// Splits the `line` into its arguments.
+///
+/// This function errors if a quote is left open or the line ends with an
+/// unescaped backslash.
+pub fn split<T: AsRef<str>>(line: T) -> Result<Vec<String>, SplitError> {
+    let mut words = Vec::new();
+    // the word being built, which exists once any character (even an empty quote) is seen
+    let mut word: Option<String> = None;
+    let mut chars = line.as_ref().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() == true => {
+                if let Some(w) = word.take() {
+                    words.push(w);
+                }
+            }
+            symbol::SINGLE_QUOTE => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some(symbol::SINGLE_QUOTE) => break,
+                        Some(c) => w.push(c),
+                        None => return Err(SplitError::OpenQuote(symbol::SINGLE_QUOTE)),
+                    }
+                }
+            }
+            symbol::DOUBLE_QUOTE => {
+                let w = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some(symbol::DOUBLE_QUOTE) => break,
+                        Some(symbol::ESCAPE) => match chars.next() {
+                            Some(c) if symbol::ESCAPABLE.contains(&c) == true => w.push(c),
+                            Some(c) => {
+                                w.push(symbol::ESCAPE);
+                                w.push(c);
+                            }
+                            None => return Err(SplitError::OpenQuote(symbol::DOUBLE_QUOTE)),
+                        },
+                        Some(c) => w.push(c),
+                        None => return Err(SplitError::OpenQuote(symbol::DOUBLE_QUOTE)),
+                    }
+                }
+            }
+            symbol::ESCAPE => match chars.next() {
+                Some(c) => word.get_or_insert_with(String::new).push(c),
+                None => return Err(SplitError::TrailingEscape),
+            },
+            c => word.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if let Some(w) = word {
+        words.push(w);
+    }
+    Ok(words)
+}
+
+/// The reasons a line can fail to be split into arguments.
+#[derive(Debug, PartialEq, Clone)]
+pub enum SplitError {
+    /// A quote was never closed.
+    OpenQuote(char),
+    /// The line ended with a backslash that has nothing to escape.
+    TrailingEscape,
+}
+
+impl Display for SplitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OpenQuote(q) => write!(f, "missing closing quote ({})", q),
+            Self::TrailingEscape => write!(f, "line ends with an escape character (\\)"),
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn split_words() {
+        assert_eq!(
+            split("myapp add  1\t2").unwrap(),
+            vec!["myapp", "add", "1", "2"]
+        );
+        assert_eq!(split("").unwrap(), Vec::<String>::new());
+        assert_eq!(
+            split(r#"git commit -m "fix \"quoted\" \$HOME" 'lit\eral'"#).unwrap(),
+            vec!["git", "commit", "-m", "fix \"quoted\" $HOME", "lit\\eral"]
+        );
+        // quotes join with adjacent text and can create empty arguments
+        assert_eq!(
+            split(r#"--name=" a b" '' x\ y "\n""#).unwrap(),
+            vec!["--name= a b", "", "x y", "\\n"]
+        );
+    }
+
+    #[test]
+    fn split_errors() {
+        assert_eq!(split("say 'hello"), Err(SplitError::OpenQuote('\'')));
+        assert_eq!(split("say \"hello\\\""), Err(SplitError::OpenQuote('"')));
+        assert_eq!(split("say hello\\"), Err(SplitError::TrailingEscape));
+        assert_eq!(
+            SplitError::OpenQuote('"').to_string(),
+            "missing closing quote (\")"
+        );
+    }
+}
@@ -1,16 +1,26 @@
 mod arg;
 mod error;
 mod help;
+mod macros;
 mod seqalin;
 
 pub mod cli;
+pub mod complete;
+pub mod expr;
+pub mod getopt;
+pub mod lex;
+pub mod plugin;
 pub mod proc;
+pub mod testing;
+pub mod value;
 
-pub use arg::Arg;
+pub use arg::{Action, Arg, ValueHint};
 pub use cli::stage;
 pub use cli::Cli;
-pub use help::Help;
-pub use proc::{Command, Subcommand};
+pub use error::Exit;
+pub use help::{Help, HelpFormat, HelpFormatError, HelpInheritance, HelpSpec};
+pub use proc::{Command, CommandFrom, Subcommand};
+#[cfg(feature = "runner")]
 pub use std::process::ExitCode;
 
 #[cfg(test)]
@@ -131,4 +141,91 @@ mod tests {
             }
         }
     }
+
+    mod validate {
+        use super::*;
+
+        #[derive(Debug, PartialEq)]
+        struct InvertedSpan;
+
+        impl std::fmt::Display for InvertedSpan {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "--start must be earlier than --end")
+            }
+        }
+
+        impl std::error::Error for InvertedSpan {}
+
+        #[derive(PartialEq, Debug)]
+        struct Span {
+            start: u8,
+            end: u8,
+        }
+
+        impl Command for Span {
+            fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+                cli.help(Help::with(String::new()))?;
+                Ok(Span {
+                    start: cli.require(Arg::option("start"))?,
+                    end: cli.require(Arg::option("end"))?,
+                })
+            }
+
+            fn validate(self) -> cli::Result<Self> {
+                match self.start < self.end {
+                    true => Ok(self),
+                    false => Err(cli::Error::invalid(InvertedSpan)),
+                }
+            }
+
+            fn execute(self) -> proc::Result {
+                Ok(())
+            }
+        }
+
+        #[test]
+        #[cfg(feature = "runner")]
+        fn it_validates_before_execute() {
+            let code = Cli::new()
+                .parse(args(vec!["span", "--start", "1", "--end", "9"]))
+                .go::<Span>();
+            assert_eq!(code, ExitCode::from(0));
+
+            let code = Cli::new()
+                .parse(args(vec!["span", "--start", "9", "--end", "1"]))
+                .go::<Span>();
+            assert_eq!(code, ExitCode::from(101));
+        }
+
+        #[test]
+        fn it_reports_invalid_command() {
+            let mut cli = Cli::new()
+                .parse(args(vec!["span", "--start", "9", "--end", "1"]))
+                .save();
+            let err = Span::interpret(&mut cli).unwrap().validate().unwrap_err();
+            assert_eq!(err.kind(), cli::ErrorKind::InvalidCommand);
+            assert_eq!(err.to_string(), "--start must be earlier than --end");
+        }
+
+        #[test]
+        fn it_builds_without_execute() {
+            let span = Cli::new()
+                .parse(args(vec!["span", "--start", "1", "--end", "9"]))
+                .build::<Span>()
+                .unwrap();
+            assert_eq!(span, Span { start: 1, end: 9 });
+
+            let err = Cli::new()
+                .parse(args(vec!["span", "--start", "9", "--end", "1"]))
+                .build::<Span>()
+                .unwrap_err();
+            assert_eq!(err.kind(), cli::ErrorKind::InvalidCommand);
+
+            let err = Cli::new()
+                .parse(args(vec!["span", "--start", "1", "--end", "9", "extra"]))
+                .build::<Span>()
+                .unwrap_err();
+            assert_eq!(err.kind(), cli::ErrorKind::UnexpectedArg);
+        }
+    }
 }
@@ -0,0 +1,157 @@
+//! Shorthand for mapping the command-line into a command's fields.
+
+/// Constructs the struct `$name` by requesting each of its fields from the
+/// [Cli][crate::Cli] `$cli` as listed.
+///
+/// Each field is written as its kind followed by its name, an optional switch,
+/// and an optional type:
+///
+/// - `flag verbose -v` checks the flag `--verbose` (`-v`) into a `bool`
+/// - `option count: u8` gets the option `--count` into an `Option<u8>`
+/// - `positional name` requires the positional `<name>`
+/// - `subcommand command` nests the subcommand `command` into an `Option`
+///
+/// Underscores in a field's name are written as hyphens on the command-line
+/// (ex: `dry_run` is `--dry-run`). The fields are requested in the order they
+/// are listed, so the _argument discovery order_ is upheld (and enforced by
+/// the [Cli][crate::Cli]) the same as when each request is written by hand.
+/// The macro must be used where errors can be returned with `?`:
+///
+/// ```
+/// use cliproc::{cli, interpret_fields, proc, stage::Memory, Cli, Command};
+///
+/// struct Add {
+///     verbose: bool,
+///     base: Option<u8>,
+///     lhs: u16,
+///     rhs: u16,
+/// }
+///
+/// impl Command for Add {
+///     fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+///         Ok(interpret_fields!(cli, Add {
+///             flag verbose -v,
+///             option base: u8,
+///             positional lhs,
+///             positional rhs,
+///         }))
+///     }
+///
+///     fn execute(self) -> proc::Result {
+///         println!("{}", self.lhs + self.rhs);
+///         Ok(())
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! interpret_fields {
+    ($cli:expr, $name:ident { $($kind:ident $field:ident $(- $switch:ident)? $(: $ty:ty)?),* $(,)? }) => {
+        $name {
+            $($field: $crate::interpret_fields!(@field $cli, $kind $field $(- $switch)? $(: $ty)?)),*
+        }
+    };
+    (@field $cli:expr, flag $field:ident $(- $switch:ident)?) => {
+        $cli.check($crate::interpret_fields!(@arg flag $field $(- $switch)?))?
+    };
+    (@field $cli:expr, option $field:ident $(- $switch:ident)? $(: $ty:ty)?) => {
+        $cli.get$(::<$ty>)?($crate::interpret_fields!(@arg option $field $(- $switch)?))?
+    };
+    (@field $cli:expr, positional $field:ident $(: $ty:ty)?) => {
+        $cli.require$(::<$ty>)?($crate::Arg::positional($crate::interpret_fields!(@name $field)))?
+    };
+    (@field $cli:expr, subcommand $field:ident) => {
+        $cli.nest($crate::Arg::subcommand($crate::interpret_fields!(@name $field)))?
+    };
+    (@arg $kind:ident $field:ident $(- $switch:ident)?) => {{
+        let arg = $crate::Arg::$kind($crate::interpret_fields!(@name $field));
+        $(let arg = arg.switch(stringify!($switch).chars().next().unwrap());)?
+        arg
+    }};
+    (@name $field:ident) => {
+        stringify!($field).trim_start_matches("r#").replace('_', "-")
+    };
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cli::{self, ErrorKind};
+    use crate::stage::Memory;
+    use crate::{Cli, Subcommand};
+
+    /// Helper test fn to write vec of &str as iterator for Cli parameter.
+    fn args<'a>(args: Vec<&'a str>) -> Box<dyn Iterator<Item = String> + 'a> {
+        Box::new(args.into_iter().map(|f| f.to_string()))
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Transfer {
+        dry_run: bool,
+        force: bool,
+        mode: Option<u16>,
+        src: String,
+        dest: String,
+        then: Option<Then>,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Then(String);
+
+    impl Subcommand<()> for Then {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Then(cli.select(&["sync"])?))
+        }
+
+        fn execute(self, _: &()) -> crate::proc::Result {
+            Ok(())
+        }
+    }
+
+    fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Transfer> {
+        Ok(interpret_fields!(cli, Transfer {
+            flag dry_run,
+            flag force -f,
+            option mode: u16,
+            positional src,
+            positional dest: String,
+            subcommand then,
+        }))
+    }
+
+    #[test]
+    fn interpret_listed_fields() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "cp",
+                "-f",
+                "a",
+                "--mode",
+                "644",
+                "b",
+                "--dry-run",
+            ]))
+            .save();
+        assert_eq!(
+            interpret(&mut cli).unwrap(),
+            Transfer {
+                dry_run: true,
+                force: true,
+                mode: Some(644),
+                src: String::from("a"),
+                dest: String::from("b"),
+                then: None,
+            }
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["cp", "a", "b", "sync"])).save();
+        assert_eq!(
+            interpret(&mut cli).unwrap().then,
+            Some(Then(String::from("sync")))
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["cp", "a"])).save();
+        assert_eq!(
+            interpret(&mut cli).unwrap_err().kind(),
+            ErrorKind::MissingPositional
+        );
+    }
+}
@@ -0,0 +1,356 @@
+//! Subcommands provided by third-party executables that are discovered at
+//! runtime.
+//!
+//! Each plugin is described by a manifest file ending in `.plugin` with one
+//! `key = value` pair per line:
+//!
+//! ```text
+//! # deploy.plugin
+//! name = deploy
+//! summary = Deploy the project to a remote host
+//! entry = bin/myapp-deploy
+//! ```
+//!
+//! A directory of manifests is loaded into [Plugins], which can be given to a
+//! [Router][super::proc::Router] so the plugins are selected alongside its routes.
+
+use std::path::{Path, PathBuf};
+#[cfg(feature = "runner")]
+use {
+    crate::proc::{self, Halt},
+    std::fmt::Display,
+};
+
+#[cfg(feature = "runner")]
+mod symbol {
+    pub const EXTENSION: &str = "plugin";
+    pub const COMMENT: char = '#';
+    pub const ASSIGN: char = '=';
+    pub const NAME: &str = "name";
+    pub const SUMMARY: &str = "summary";
+    pub const ENTRY: &str = "entry";
+}
+
+/// A subcommand that is run as an external executable.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Plugin {
+    name: String,
+    summary: Option<String>,
+    entry: PathBuf,
+}
+
+impl Plugin {
+    /// Creates a plugin named `name` that runs the executable at `entry`.
+    pub fn new<T: AsRef<str>, P: AsRef<Path>>(name: T, entry: P) -> Self {
+        Self {
+            name: String::from(name.as_ref()),
+            summary: None,
+            entry: entry.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Describes the plugin with a one-line `summary`.
+    pub fn with_summary<T: AsRef<str>>(mut self, summary: T) -> Self {
+        self.summary = Some(String::from(summary.as_ref()));
+        self
+    }
+
+    /// Reads the plugin from the manifest file at `path`.
+    ///
+    /// A relative entry is resolved against the directory of the manifest.
+    #[cfg(feature = "runner")]
+    pub fn from_manifest<P: AsRef<Path>>(path: P) -> Result<Self, PluginError> {
+        let path = path.as_ref();
+        let text =
+            std::fs::read_to_string(path).map_err(|e| PluginError::Io(path.to_path_buf(), e))?;
+        let mut name: Option<String> = None;
+        let mut summary: Option<String> = None;
+        let mut entry: Option<String> = None;
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() == true || line.starts_with(symbol::COMMENT) == true {
+                continue;
+            }
+            let (key, value) = match line.split_once(symbol::ASSIGN) {
+                Some((k, v)) => (k.trim(), v.trim().to_string()),
+                None => return Err(PluginError::InvalidLine(path.to_path_buf(), n + 1)),
+            };
+            match key {
+                symbol::NAME => name = Some(value),
+                symbol::SUMMARY => summary = Some(value),
+                symbol::ENTRY => entry = Some(value),
+                _ => return Err(PluginError::UnknownKey(path.to_path_buf(), key.to_string())),
+            }
+        }
+        let name = name.ok_or(PluginError::MissingKey(path.to_path_buf(), symbol::NAME))?;
+        let entry = entry.ok_or(PluginError::MissingKey(path.to_path_buf(), symbol::ENTRY))?;
+        let entry = match path.parent() {
+            Some(dir) => dir.join(entry),
+            None => PathBuf::from(entry),
+        };
+        Ok(Self {
+            name: name,
+            summary: summary,
+            entry: entry,
+        })
+    }
+
+    /// References the name the plugin is selected by.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// References the one-line summary of the plugin, if one exists.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// References the path to the plugin's executable.
+    pub fn entry(&self) -> &Path {
+        &self.entry
+    }
+}
+
+/// A collection of [Plugin]s.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct Plugins {
+    plugins: Vec<Plugin>,
+}
+
+impl Plugins {
+    /// Creates a new collection with no plugins.
+    pub fn new() -> Self {
+        Self {
+            plugins: Vec::new(),
+        }
+    }
+
+    /// Adds the `plugin` to the collection.
+    pub fn plugin(mut self, plugin: Plugin) -> Self {
+        self.plugins.push(plugin);
+        self
+    }
+
+    /// Reads every manifest file ending in `.plugin` within the directory `dir`,
+    /// in order of their file names.
+    ///
+    /// A directory that does not exist has no plugins. This function errors if a
+    /// manifest cannot be read or two plugins share the same name.
+    #[cfg(feature = "runner")]
+    pub fn load<P: AsRef<Path>>(dir: P) -> Result<Self, PluginError> {
+        let dir = dir.as_ref();
+        let entries = match std::fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::new()),
+            Err(e) => return Err(PluginError::Io(dir.to_path_buf(), e)),
+        };
+        let mut manifests = Vec::<PathBuf>::new();
+        for entry in entries {
+            let path = entry
+                .map_err(|e| PluginError::Io(dir.to_path_buf(), e))?
+                .path();
+            if path.extension().is_some_and(|e| e == symbol::EXTENSION) == true {
+                manifests.push(path);
+            }
+        }
+        manifests.sort();
+        let mut plugins = Self::new();
+        for path in manifests {
+            let plugin = Plugin::from_manifest(&path)?;
+            if plugins.find(plugin.name()).is_some() == true {
+                return Err(PluginError::Duplicate(path, plugin.name));
+            }
+            plugins = plugins.plugin(plugin);
+        }
+        Ok(plugins)
+    }
+
+    /// Finds the plugin named `name`.
+    pub fn find(&self, name: &str) -> Option<&Plugin> {
+        self.plugins.iter().find(|p| p.name == name)
+    }
+
+    /// Iterates through the plugins in the order they were added.
+    pub fn iter(&self) -> impl Iterator<Item = &Plugin> {
+        self.plugins.iter()
+    }
+
+    /// Returns the number of plugins.
+    pub fn len(&self) -> usize {
+        self.plugins.len()
+    }
+
+    /// Checks if there are no plugins.
+    pub fn is_empty(&self) -> bool {
+        self.plugins.is_empty()
+    }
+}
+
+/// A [Plugin] that was selected on the command-line along with the arguments
+/// to forward to it.
+#[derive(Debug, PartialEq, Clone)]
+pub struct External {
+    plugin: Plugin,
+    args: Vec<String>,
+}
+
+impl External {
+    pub(crate) fn new(plugin: Plugin, args: Vec<String>) -> Self {
+        Self {
+            plugin: plugin,
+            args: args,
+        }
+    }
+
+    /// References the selected plugin.
+    pub fn plugin(&self) -> &Plugin {
+        &self.plugin
+    }
+
+    /// References the arguments supplied after the plugin's name.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// Creates the process that runs the plugin's executable with its arguments.
+    #[cfg(feature = "runner")]
+    pub fn command(&self) -> std::process::Command {
+        let mut command = std::process::Command::new(&self.plugin.entry);
+        command.args(&self.args);
+        command
+    }
+
+    /// Runs the plugin's executable and waits for it to finish.
+    ///
+    /// A plugin that exits unsuccessfully returns a [Halt] with its exit code.
+    #[cfg(feature = "runner")]
+    pub fn run(&self) -> proc::Result {
+        let status = self.command().status()?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(Box::new(Halt::from(status))),
+        }
+    }
+}
+
+/// The reasons a [Plugin] can fail to be loaded.
+#[cfg(feature = "runner")]
+#[derive(Debug)]
+pub enum PluginError {
+    Io(PathBuf, std::io::Error),
+    InvalidLine(PathBuf, usize),
+    UnknownKey(PathBuf, String),
+    MissingKey(PathBuf, &'static str),
+    Duplicate(PathBuf, String),
+}
+
+#[cfg(feature = "runner")]
+impl Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(path, e) => write!(f, "failed to read \"{}\": {}", path.display(), e),
+            Self::InvalidLine(path, n) => write!(
+                f,
+                "line {} of \"{}\" is not a key-value pair",
+                n,
+                path.display()
+            ),
+            Self::UnknownKey(path, key) => {
+                write!(f, "unknown key \"{}\" in \"{}\"", key, path.display())
+            }
+            Self::MissingKey(path, key) => {
+                write!(f, "missing key \"{}\" in \"{}\"", key, path.display())
+            }
+            Self::Duplicate(path, name) => write!(
+                f,
+                "plugin \"{}\" in \"{}\" is already defined",
+                name,
+                path.display()
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "runner")]
+impl std::error::Error for PluginError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(_, e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "runner"))]
+mod test {
+    use super::*;
+
+    /// Creates an empty directory unique to the test named `name`.
+    fn scratch(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cliproc-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn load_manifests() {
+        let dir = scratch("load_manifests");
+        std::fs::write(
+            dir.join("deploy.plugin"),
+            "# ships the project\nname = deploy\nsummary = Deploy the project\n\nentry = bin/deploy\n",
+        )
+        .unwrap();
+        // an absolute entry on any platform is used as written
+        let audit = std::env::temp_dir().join("audit");
+        std::fs::write(
+            dir.join("audit.plugin"),
+            format!("name = audit\nentry = {}\n", audit.display()),
+        )
+        .unwrap();
+        std::fs::write(dir.join("notes.txt"), "name = notes\n").unwrap();
+
+        let plugins = Plugins::load(&dir).unwrap();
+        assert_eq!(
+            plugins.iter().map(|p| p.name()).collect::<Vec<&str>>(),
+            vec!["audit", "deploy"]
+        );
+        let deploy = plugins.find("deploy").unwrap();
+        assert_eq!(deploy.summary(), Some("Deploy the project"));
+        assert_eq!(deploy.entry(), dir.join("bin/deploy"));
+        assert_eq!(plugins.find("audit").unwrap().entry(), audit);
+
+        // a missing directory has no plugins
+        assert!(Plugins::load(dir.join("missing")).unwrap().is_empty());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_manifest_errors() {
+        let dir = scratch("load_manifest_errors");
+        let path = dir.join("bad.plugin");
+        std::fs::write(&path, "name = bad\nentry\n").unwrap();
+        assert!(matches!(
+            Plugin::from_manifest(&path),
+            Err(PluginError::InvalidLine(_, 2))
+        ));
+        std::fs::write(&path, "name = bad\ncolor = red\n").unwrap();
+        assert!(matches!(
+            Plugin::from_manifest(&path),
+            Err(PluginError::UnknownKey(_, key)) if key == "color"
+        ));
+        std::fs::write(&path, "name = bad\n").unwrap();
+        assert_eq!(
+            Plugin::from_manifest(&path).unwrap_err().to_string(),
+            format!("missing key \"entry\" in \"{}\"", path.display())
+        );
+
+        std::fs::write(&path, "name = twin\nentry = a\n").unwrap();
+        std::fs::write(dir.join("copy.plugin"), "name = twin\nentry = b\n").unwrap();
+        assert!(matches!(
+            Plugins::load(&dir),
+            Err(PluginError::Duplicate(_, name)) if name == "twin"
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
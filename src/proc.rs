@@ -1,9 +1,115 @@
 use crate::cli;
 use crate::cli::{stage::Memory, Cli};
+use crate::plugin::{External, Plugins};
+use crate::Exit;
+use std::marker::PhantomData;
+
+#[cfg(feature = "runner")]
+pub mod prompt;
+pub mod stash;
+#[cfg(feature = "runner")]
+pub mod status;
+#[cfg(feature = "runner")]
+use std::process::ExitCode;
+#[cfg(feature = "runner")]
+use {
+    crate::cli::stage::Ready,
+    crate::{Arg, Help},
+    std::io::Write,
+    std::path::Path,
+};
 
 /// The return type for a [Command]'s execution process.
 pub type Result = std::result::Result<(), Box<dyn std::error::Error>>;
 
+/// An error that ends the program with a specific [Exit] when returned from a
+/// [Command]'s or [Subcommand]'s execution process.
+///
+/// Unlike other errors, which exit with [Exit::RuntimeError] and report their
+/// message, a [Halt] only reports its message if it has one. This is useful for
+/// passing through the exit status of a child process. An [Exit] returned with
+/// `?` is converted into a [Halt] that reports nothing.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Halt {
+    exit: Exit,
+    message: Option<String>,
+}
+
+impl Halt {
+    /// Creates a halt with the given `exit` that reports nothing.
+    pub fn new(exit: Exit) -> Self {
+        Self {
+            exit: exit,
+            message: None,
+        }
+    }
+
+    /// Creates a halt with the given `exit` that reports the error `message`.
+    pub fn with_message<T: AsRef<str>>(exit: Exit, message: T) -> Self {
+        Self {
+            exit: exit,
+            message: Some(String::from(message.as_ref())),
+        }
+    }
+
+    /// Returns the exit the program ends with.
+    pub fn exit(&self) -> Exit {
+        self.exit
+    }
+
+    /// Returns the exit code.
+    pub fn code(&self) -> u8 {
+        self.exit.code()
+    }
+
+    /// References the error message to report, if one exists.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+}
+
+impl From<Exit> for Halt {
+    fn from(value: Exit) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<Exit> for Box<dyn std::error::Error> {
+    /// Returns the `value` from an execution process as a [Halt] that reports
+    /// nothing.
+    fn from(value: Exit) -> Self {
+        Box::new(Halt::new(value))
+    }
+}
+
+#[cfg(feature = "runner")]
+impl From<std::process::ExitStatus> for Halt {
+    /// Passes through the exit status of a child process.
+    ///
+    /// A status without a code (such as a process terminated by a signal) or
+    /// with a code outside of the range of `u8` exits with [Exit::RuntimeError].
+    fn from(value: std::process::ExitStatus) -> Self {
+        Self::new(
+            value
+                .code()
+                .and_then(|c| u8::try_from(c).ok())
+                .map(Exit::Custom)
+                .unwrap_or(Exit::RuntimeError),
+        )
+    }
+}
+
+impl std::fmt::Display for Halt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.message {
+            Some(msg) => write!(f, "{}", msg),
+            None => write!(f, "exited with code {}", self.code()),
+        }
+    }
+}
+
+impl std::error::Error for Halt {}
+
 pub trait Command: Sized {
     /// Constructs the given struct by mapping the parsed representation
     /// of command-line inputs (tokens) into the appropriate data fields.
@@ -19,6 +125,19 @@ pub trait Command: Sized {
     /// a programmer's error and will result in a panic!.
     fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self>;
 
+    /// Verifies the constructed struct is semantically valid before it is
+    /// executed.
+    ///
+    /// This stage is called after the command is interpreted and the command-line
+    /// is verified to be empty. It is intended for checks across multiple fields
+    /// (such as "--start must be earlier than --end"). Errors are reported through
+    /// the [Cli][super::Cli]'s error pipeline, see [Error::invalid][super::cli::Error::invalid].
+    ///
+    /// By default, the struct is accepted as-is.
+    fn validate(self) -> cli::Result<Self> {
+        Ok(self)
+    }
+
     /// Processes the initialized struct and its defined data for an arbitrary
     /// task.
     ///
@@ -28,6 +147,35 @@ pub trait Command: Sized {
     fn execute(self) -> Result;
 }
 
+/// A top-level command that is interpreted and executed with a context `C`
+/// provided by the program (such as the current directory or an already loaded
+/// configuration).
+///
+/// Unlike a [Command], the context is available while the command-line is being
+/// interpreted, so it can decide how arguments are requested. A [CommandFrom] is
+/// run with [go_with][super::Cli::go_with].
+pub trait CommandFrom<C>: Sized {
+    /// Constructs the given struct by mapping the parsed representation
+    /// of command-line inputs (tokens) into the appropriate data fields, with
+    /// access to the `context`.
+    ///
+    /// The _argument discovery order_ must be upheld the same as in
+    /// [Command::interpret].
+    fn interpret(cli: &mut Cli<Memory>, context: &C) -> cli::Result<Self>;
+
+    /// Verifies the constructed struct is semantically valid before it is
+    /// executed, see [Command::validate].
+    ///
+    /// By default, the struct is accepted as-is.
+    fn validate(self) -> cli::Result<Self> {
+        Ok(self)
+    }
+
+    /// Processes the initialized struct and its defined data for an arbitrary
+    /// task with the same `context` it was interpreted with.
+    fn execute(self, context: &C) -> Result;
+}
+
 pub trait Subcommand<T>: Sized {
     /// Constructs the given struct by mapping the parsed representation
     /// of command-line inputs (tokens) into the appropriate data fields.
@@ -52,9 +200,514 @@ pub trait Subcommand<T>: Sized {
     fn execute(self, context: &T) -> Result;
 }
 
+/// A [Subcommand] whose context is derived from its parent's context.
+///
+/// This allows deeper levels of a command tree to receive an enriched context
+/// (such as a resource opened by an intermediate command) without sharing a
+/// single context type across the entire tree. A [Nested] subcommand is created
+/// from [Cli::nest_with].
+pub struct Nested<T, P, C> {
+    command: T,
+    map: Box<dyn FnOnce(&P) -> C>,
+}
+
+impl<T: Subcommand<C>, P, C> Nested<T, P, C> {
+    /// Pairs the `command` with the function `map` to create its context.
+    pub fn new<F: FnOnce(&P) -> C + 'static>(command: T, map: F) -> Self {
+        Self {
+            command: command,
+            map: Box::new(map),
+        }
+    }
+
+    /// References the underlying subcommand.
+    pub fn get(&self) -> &T {
+        &self.command
+    }
+
+    /// Maps the parent's `context` into the subcommand's context and then
+    /// executes the subcommand with it.
+    pub fn execute(self, context: &P) -> Result {
+        let inner = (self.map)(context);
+        self.command.execute(&inner)
+    }
+}
+
+impl<T: std::fmt::Debug, P, C> std::fmt::Debug for Nested<T, P, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Nested")
+            .field("command", &self.command)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: PartialEq, P, C> PartialEq for Nested<T, P, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.command == other.command
+    }
+}
+
+/// The function that interprets a routed subcommand into the common type `T`.
+type Interpreter<T> = fn(&mut Cli<Memory>) -> cli::Result<T>;
+
+/// A typed registry of subcommands that dispatches to the subcommand named on
+/// the command-line.
+///
+/// Each route pairs a name with a [Subcommand] that converts into the common
+/// type `T` (typically an enum wrapping each subcommand). The list of names given
+/// to [Cli::select] is built from the routes, so the names and the subcommands they
+/// interpret cannot fall out of sync.
+pub struct Router<T, C> {
+    routes: Vec<(String, Interpreter<T>)>,
+    nested: Vec<String>,
+    summaries: Vec<(String, String)>,
+    descriptions: Vec<(String, String)>,
+    plugins: Plugins,
+    external: Option<fn(External) -> T>,
+    _marker: PhantomData<C>,
+}
+
+impl<T, C> Router<T, C> {
+    /// Creates a new router with no routes.
+    pub fn new() -> Self {
+        Self {
+            routes: Vec::new(),
+            nested: Vec::new(),
+            summaries: Vec::new(),
+            descriptions: Vec::new(),
+            plugins: Plugins::new(),
+            external: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Adds the subcommand `S` to be interpreted when `name` is selected.
+    pub fn route<S>(mut self, name: &str) -> Self
+    where
+        S: Subcommand<C> + Into<T>,
+    {
+        self.routes
+            .push((name.to_string(), |cli| Ok(S::interpret(cli)?.into())));
+        self
+    }
+
+    /// Declares the `names` of the subcommands nested under the most recently
+    /// added route.
+    ///
+    /// Nested names are only used to suggest the full invocation when a name
+    /// does not match a route (ex: "install-deps" suggests "deps install").
+    /// A nested name can describe a deeper path by separating its subcommands
+    /// with spaces.
+    pub fn nested(mut self, names: &[&str]) -> Self {
+        let route = self
+            .routes
+            .last()
+            .map(|(name, _)| name.clone())
+            .expect("a route must be added before its nested subcommands");
+        self.nested
+            .extend(names.iter().map(|n| format!("{} {}", route, n)));
+        self
+    }
+
+    /// Describes the most recently added route with a one-line `summary`.
+    ///
+    /// Summaries are only used when walking through the routes, see [walk][Router::walk].
+    /// They are intended for listing the routes in the parent's help, see
+    /// [command][crate::HelpSpec::command].
+    pub fn summary<S: AsRef<str>>(mut self, summary: S) -> Self {
+        let route = self
+            .routes
+            .last()
+            .map(|(name, _)| name.clone())
+            .expect("a route must be added before its summary");
+        self.summaries.push((route, String::from(summary.as_ref())));
+        self
+    }
+
+    /// Describes the most recently added route with a long `description`.
+    ///
+    /// Descriptions are only used when walking through the routes, see [walk][Router::walk].
+    /// They are intended for the route's own help, see
+    /// [description][crate::HelpSpec::description].
+    pub fn description<S: AsRef<str>>(mut self, description: S) -> Self {
+        let route = self
+            .routes
+            .last()
+            .map(|(name, _)| name.clone())
+            .expect("a route must be added before its description");
+        self.descriptions
+            .push((route, String::from(description.as_ref())));
+        self
+    }
+
+    /// Adds the subcommands provided by the `plugins`, which are interpreted as an
+    /// [External] subcommand when selected.
+    ///
+    /// Every argument after the plugin's name is forwarded to the plugin, see
+    /// [args][External::args]. A route takes priority over a plugin with the same name.
+    pub fn plugins(mut self, plugins: Plugins) -> Self
+    where
+        External: Into<T>,
+    {
+        self.plugins = plugins;
+        self.external = Some(|external| external.into());
+        self
+    }
+
+    /// Lists every route followed by the subcommands nested under it, in the
+    /// order they were added, and then every plugin.
+    ///
+    /// This is useful for generating documentation or checking naming conventions
+    /// across every subcommand.
+    pub fn walk(&self) -> Vec<Node> {
+        self.routes
+            .iter()
+            .flat_map(|(name, _)| {
+                let summary = self
+                    .summaries
+                    .iter()
+                    .rev()
+                    .find(|(route, _)| route == name)
+                    .map(|(_, s)| s.clone());
+                let description = self
+                    .descriptions
+                    .iter()
+                    .rev()
+                    .find(|(route, _)| route == name)
+                    .map(|(_, d)| d.clone());
+                std::iter::once(Node {
+                    path: vec![name.clone()],
+                    summary: summary,
+                    description: description,
+                })
+                .chain(
+                    self.nested
+                        .iter()
+                        .filter(move |n| n.split(' ').next() == Some(name.as_str()))
+                        .map(|n| Node {
+                            path: n.split(' ').map(|w| w.to_string()).collect(),
+                            summary: None,
+                            description: None,
+                        }),
+                )
+            })
+            .chain(self.plugins.iter().map(|p| Node {
+                path: vec![p.name().to_string()],
+                summary: p.summary().map(|s| s.to_string()),
+                description: None,
+            }))
+            .collect()
+    }
+
+    /// Checks for the hidden flag `--list-commands` and stops interpretation to
+    /// display the path of every subcommand in the router, one per line, if it
+    /// was raised.
+    ///
+    /// Raising the flag as `--list-commands=json` instead displays a JSON array of
+    /// objects with each subcommand's `path` and `summary`, see [walk][Router::walk].
+    /// This is useful for external completion engines and launcher tools.
+    ///
+    /// Like an eager flag, it is reported as an error that exits successfully.
+    /// Since the router only dispatches once a subcommand is supplied, this function
+    /// should be called during the flag discovery of the command that nests the router.
+    pub fn list_commands(&self, cli: &mut Cli<Memory>) -> cli::Result<()> {
+        cli.list_commands(&self.walk())
+    }
+
+    /// Selects the next positional argument against the names of the routes and
+    /// interprets the matching subcommand.
+    ///
+    /// This function errors with a spelling suggestion or an unknown subcommand
+    /// error if the name does not match a route or plugin, see [Cli::select].
+    pub fn dispatch(&self, cli: &mut Cli<Memory>) -> cli::Result<T> {
+        let bank: Vec<&str> = self
+            .routes
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .chain(self.plugins.iter().map(|p| p.name()))
+            .collect();
+        let command = cli
+            .select(&bank)
+            .map_err(|e| cli.suggest_nested(e, &self.nested))?;
+        match self.routes.iter().find(|(name, _)| name == &command) {
+            Some((_, interpret)) => interpret(cli),
+            None => {
+                let plugin = self.plugins.find(&command).unwrap().clone();
+                let external = self
+                    .external
+                    .expect("plugins must have an external subcommand");
+                Ok(external(External::new(plugin, cli.forward())))
+            }
+        }
+    }
+}
+
+impl<T, C> Default for Router<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, C> std::fmt::Debug for Router<T, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Router")
+            .field(
+                "routes",
+                &self.routes.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .field("nested", &self.nested)
+            .field("summaries", &self.summaries)
+            .field("descriptions", &self.descriptions)
+            .field("plugins", &self.plugins)
+            .finish()
+    }
+}
+
+/// A subcommand found while walking through a [Router], see [walk][Router::walk].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Node {
+    path: Vec<String>,
+    summary: Option<String>,
+    description: Option<String>,
+}
+
+impl Node {
+    /// References the names of the subcommands leading to and including this
+    /// subcommand, relative to the [Router].
+    pub fn path(&self) -> &[String] {
+        &self.path
+    }
+
+    /// References the name of the subcommand.
+    pub fn name(&self) -> &str {
+        self.path.last().unwrap()
+    }
+
+    /// Returns the number of subcommands between the [Router] and this subcommand,
+    /// where a route has a depth of 0.
+    pub fn depth(&self) -> usize {
+        self.path.len() - 1
+    }
+
+    /// References the one-line summary of the subcommand, if one exists.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// References the long description of the subcommand, if one exists.
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// Locks `stdout` for writing a command's output.
+///
+/// Unlike `println!`, which panics when the output is piped to a program that
+/// exits early (ex: `myapp list | head -n 1`), writing to the locked `stdout`
+/// returns a [BrokenPipe][std::io::ErrorKind::BrokenPipe] error. When such an
+/// error is returned from execution, [go][super::Cli::go] exits successfully
+/// without reporting it, following the Unix convention.
+#[cfg(feature = "runner")]
+pub fn stdout() -> std::io::StdoutLock<'static> {
+    std::io::stdout().lock()
+}
+
+/// Writes the `text` followed by a newline to `stdout`, ignoring a closed pipe.
+#[cfg(feature = "runner")]
+pub(crate) fn write_out<T: std::fmt::Display>(text: T) -> () {
+    let _ = writeln!(std::io::stdout(), "{}", text);
+}
+
+/// Writes the `text` followed by a newline to `stderr`, ignoring a closed pipe.
+#[cfg(feature = "runner")]
+pub(crate) fn write_err<T: std::fmt::Display>(text: T) -> () {
+    let _ = writeln!(std::io::stderr(), "{}", text);
+}
+
+/// Returns the width of the terminal in columns, as set by the shell through the
+/// `COLUMNS` variable.
+#[cfg(feature = "runner")]
+pub(crate) fn columns() -> Option<usize> {
+    std::env::var("COLUMNS")
+        .ok()
+        .and_then(|c| c.parse::<usize>().ok())
+        .filter(|c| *c > 0)
+}
+
+/// Checks if the error `err`, or any error that caused it, is from writing to
+/// a closed pipe.
+#[cfg(feature = "runner")]
+pub(crate) fn is_broken_pipe(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut cur = Some(err);
+    while let Some(e) = cur {
+        cur = match e.downcast_ref::<std::io::Error>() {
+            Some(io) if io.kind() == std::io::ErrorKind::BrokenPipe => return true,
+            // an io error skips over the error it wraps when asked for its source
+            Some(io) => match io.get_ref() {
+                Some(inner) => Some(inner as &(dyn std::error::Error + 'static)),
+                None => e.source(),
+            },
+            None => e.source(),
+        };
+    }
+    false
+}
+
+/// Runs the current program again with the arguments it was invoked with, as
+/// rewritten by `modify` (ex: after elevating privileges or fixing up the
+/// environment).
+///
+/// The arguments given to `modify` are the [raw_args][Cli::raw_args] captured by
+/// `cli`, beginning with the program as it was invoked. The first of the
+/// returned arguments is the name the program is run as, while the program
+/// itself is always the current executable.
+///
+/// On Unix platforms, the current process is replaced by the new one. On other
+/// platforms, the new program is run as a child process and the current process
+/// exits with its exit code once it finishes. In either case, this function only
+/// returns if the new program could not be started.
+#[cfg(feature = "runner")]
+pub fn reexec<F: FnOnce(Vec<String>) -> Vec<String>>(
+    cli: &Cli<Memory>,
+    modify: F,
+) -> std::io::Error {
+    let mut command = match reexec_command(cli.raw_args().to_vec(), modify) {
+        Ok(c) => c,
+        Err(e) => return e,
+    };
+    #[cfg(unix)]
+    {
+        std::os::unix::process::CommandExt::exec(&mut command)
+    }
+    #[cfg(not(unix))]
+    {
+        match command.status() {
+            Ok(status) => std::process::exit(Halt::from(status).code() as i32),
+            Err(e) => e,
+        }
+    }
+}
+
+/// Creates the process that runs the current executable with the `args` as
+/// rewritten by `modify`, see [reexec].
+#[cfg(feature = "runner")]
+fn reexec_command<F: FnOnce(Vec<String>) -> Vec<String>>(
+    args: Vec<String>,
+    modify: F,
+) -> std::io::Result<std::process::Command> {
+    let exe = std::env::current_exe()?;
+    let mut args = modify(args).into_iter();
+    let mut command = std::process::Command::new(&exe);
+    // preserve the name the program is run as, which may select its behavior
+    #[cfg(unix)]
+    if let Some(name) = args.next() {
+        std::os::unix::process::CommandExt::arg0(&mut command, name);
+    }
+    #[cfg(not(unix))]
+    args.next();
+    command.args(args);
+    Ok(command)
+}
+
+/// The function that runs a [Command] registered to a [Multicall] binary.
+#[cfg(feature = "runner")]
+type Runner = fn(Cli<Memory>) -> ExitCode;
+
+/// An entry point for a single binary that provides multiple commands, selected
+/// by the name the executable was invoked with (such as through a symbolic link).
+///
+/// When the executable's name does not match any command (such as the generic
+/// name of the binary), the command is selected by the first positional argument
+/// instead.
+#[cfg(feature = "runner")]
+#[derive(Debug)]
+pub struct Multicall {
+    commands: Vec<(String, Runner)>,
+    help: Option<Help>,
+}
+
+#[cfg(feature = "runner")]
+impl Multicall {
+    /// Creates a new entry point with no commands.
+    pub fn new() -> Self {
+        Self {
+            commands: Vec::new(),
+            help: None,
+        }
+    }
+
+    /// Adds the command `T` to be run when the executable is invoked as `name`.
+    pub fn command<T: Command>(mut self, name: &str) -> Self {
+        self.commands
+            .push((name.to_string(), Cli::<Memory>::run::<T>));
+        self
+    }
+
+    /// Sets the [Help] information to display when the command is selected by the
+    /// first positional argument.
+    pub fn help(mut self, help: Help) -> Self {
+        self.help = Some(help);
+        self
+    }
+
+    /// Runs the command selected by the executable's name, or by the first
+    /// positional argument when the name does not match a command.
+    ///
+    /// The file extension of the executable's name is ignored. The command is
+    /// processed the same as in [go][Cli::go].
+    pub fn go(&self, cli: Cli<Ready>) -> ExitCode {
+        let mut cli = cli.save();
+        let invoked = cli
+            .invoked_name()
+            .and_then(|n| Path::new(n).file_stem())
+            .map(|n| n.to_string_lossy().into_owned());
+        if let Some(run) = invoked.and_then(|n| self.find(&n)) {
+            return run(cli);
+        }
+        match self.dispatch(&mut cli) {
+            Ok(run) => {
+                cli.unset_help();
+                run(cli)
+            }
+            Err(err) => cli.report(err),
+        }
+    }
+
+    /// Selects the command from the first positional argument.
+    fn dispatch(&self, cli: &mut Cli<Memory>) -> cli::Result<Runner> {
+        if let Some(help) = &self.help {
+            cli.help(help.clone())?;
+        }
+        let bank: Vec<&str> = self
+            .commands
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        let command = cli.select_required(Arg::subcommand("command"), &bank)?;
+        Ok(self.find(&command).unwrap())
+    }
+
+    /// Finds the command registered as `name`.
+    fn find(&self, name: &str) -> Option<Runner> {
+        self.commands
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, run)| *run)
+    }
+}
+
+#[cfg(feature = "runner")]
+impl Default for Multicall {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::cli::{ErrorContext, ErrorKind};
+    use crate::plugin::Plugin;
     use crate::{arg::*, help::Help};
 
     /// Helper test fn to write vec of &str as iterator for Cli parameter.
@@ -254,6 +907,1162 @@ mod test {
         let _ = Op::interpret(&mut cli);
     }
 
+    /// Tests a nested subcommand cli structure dispatched by a [Router].
+    #[derive(Debug, PartialEq)]
+    struct Calc {
+        command: Option<CalcSubcommand>,
+    }
+
+    impl Command for Calc {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Calc {
+                command: cli.nest(Arg::subcommand("command"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            match self.command {
+                Some(command) => command.execute(&()),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Neg {
+        value: i32,
+    }
+
+    impl Subcommand<()> for Neg {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Neg {
+                value: cli.require(Arg::positional("value"))?,
+            })
+        }
+
+        fn execute(self, _: &()) -> Result {
+            println!("{}", -self.value);
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum CalcSubcommand {
+        Add(Add),
+        Neg(Neg),
+    }
+
+    impl From<Add> for CalcSubcommand {
+        fn from(value: Add) -> Self {
+            Self::Add(value)
+        }
+    }
+
+    impl From<Neg> for CalcSubcommand {
+        fn from(value: Neg) -> Self {
+            Self::Neg(value)
+        }
+    }
+
+    impl Subcommand<()> for CalcSubcommand {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Router::new()
+                .route::<Add>("add")
+                .route::<Neg>("neg")
+                .dispatch(cli)
+        }
+
+        fn execute(self, c: &()) -> Result {
+            match self {
+                CalcSubcommand::Add(op) => op.execute(c),
+                CalcSubcommand::Neg(op) => op.execute(c),
+            }
+        }
+    }
+
+    #[test]
+    fn routed_commands() {
+        let mut cli = Cli::new().parse(args(vec!["calc", "neg", "4"])).save();
+        assert_eq!(
+            Calc::interpret(&mut cli).unwrap(),
+            Calc {
+                command: Some(CalcSubcommand::Neg(Neg { value: 4 }))
+            }
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["calc", "add", "9", "10"]))
+            .save();
+        assert_eq!(
+            Calc::interpret(&mut cli).unwrap(),
+            Calc {
+                command: Some(CalcSubcommand::Add(Add {
+                    lhs: 9,
+                    rhs: 10,
+                    force: false,
+                    verbose: false,
+                }))
+            }
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["calc"])).save();
+        assert_eq!(Calc::interpret(&mut cli).unwrap(), Calc { command: None });
+
+        // the bank is built from the routes to offer suggestions
+        let mut cli = Cli::new()
+            .threshold(2)
+            .parse(args(vec!["calc", "nag", "4"]))
+            .save();
+        assert_eq!(
+            Calc::interpret(&mut cli).unwrap_err().kind(),
+            ErrorKind::SuggestSubcommand
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["calc", "mult", "4"])).save();
+        assert_eq!(
+            Calc::interpret(&mut cli).unwrap_err().kind(),
+            ErrorKind::UnknownSubcommand
+        );
+    }
+
+    #[test]
+    fn nested_route_suggestions() {
+        #[derive(Debug)]
+        struct App;
+
+        impl Subcommand<()> for App {
+            fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+                Router::<CalcSubcommand, ()>::new()
+                    .route::<Add>("add")
+                    .route::<Neg>("deps")
+                    .nested(&["install", "cache clear"])
+                    .dispatch(cli)
+                    .map(|_| App)
+            }
+
+            fn execute(self, _: &()) -> Result {
+                Ok(())
+            }
+        }
+        let dispatch =
+            |cli: &mut Cli<Memory>| cli.nest::<App, ()>(Arg::subcommand("command")).unwrap_err();
+
+        let mut cli = Cli::new()
+            .threshold(2)
+            .parse(args(vec!["myapp", "install-deps"]))
+            .save();
+        let err = dispatch(&mut cli);
+        assert_eq!(err.kind(), ErrorKind::SuggestSubcommand);
+        assert_eq!(
+            err.to_string(),
+            "invalid subcommand \"install-deps\"\n\nDid you mean \"myapp deps install\"?"
+        );
+
+        let mut cli = Cli::new()
+            .threshold(2)
+            .parse(args(vec!["myapp", "deps-cache-cler"]))
+            .save();
+        match dispatch(&mut cli).context() {
+            ErrorContext::SuggestWord(_, s) => assert_eq!(s, &vec!["myapp deps cache clear"]),
+            _ => panic!("expected a suggestion"),
+        }
+
+        // suggestions at the same level are kept when they are closer
+        let mut cli = Cli::new()
+            .threshold(2)
+            .parse(args(vec!["myapp", "dep"]))
+            .save();
+        match dispatch(&mut cli).context() {
+            ErrorContext::SuggestWord(_, s) => assert_eq!(s, &vec!["deps"]),
+            _ => panic!("expected a suggestion"),
+        }
+    }
+
+    #[test]
+    fn walk_routes() {
+        let router = Router::<CalcSubcommand, ()>::new()
+            .route::<Add>("add")
+            .summary("Add two numbers")
+            .description("Add two numbers together.\n\nThe sum must fit in 16 bits.")
+            .route::<Neg>("deps")
+            .nested(&["install", "cache clear"]);
+        let nodes = router.walk();
+        assert_eq!(
+            nodes
+                .iter()
+                .map(|n| (n.path().join(" "), n.depth(), n.summary()))
+                .collect::<Vec<_>>(),
+            vec![
+                (String::from("add"), 0, Some("Add two numbers")),
+                (String::from("deps"), 0, None),
+                (String::from("deps install"), 1, None),
+                (String::from("deps cache clear"), 2, None),
+            ]
+        );
+        assert_eq!(nodes[3].name(), "clear");
+        assert_eq!(
+            nodes[0].description(),
+            Some("Add two numbers together.\n\nThe sum must fit in 16 bits.")
+        );
+        assert_eq!(nodes[1].description(), None);
+    }
+
+    /// Tests a [Router] that dispatches to plugins alongside its routes.
+    #[derive(Debug, PartialEq)]
+    struct Launch {
+        verbose: bool,
+        command: Option<LaunchSubcommand>,
+    }
+
+    impl Command for Launch {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            cli.help(Help::with("launch [options] <command>"))?;
+            Ok(Launch {
+                verbose: cli.check(Arg::flag("verbose").switch('v'))?,
+                command: cli.nest(Arg::subcommand("command"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum LaunchSubcommand {
+        Neg(Neg),
+        Plugin(External),
+    }
+
+    impl From<Neg> for LaunchSubcommand {
+        fn from(value: Neg) -> Self {
+            Self::Neg(value)
+        }
+    }
+
+    impl From<External> for LaunchSubcommand {
+        fn from(value: External) -> Self {
+            Self::Plugin(value)
+        }
+    }
+
+    impl Subcommand<()> for LaunchSubcommand {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Router::new()
+                .route::<Neg>("neg")
+                .plugins(
+                    Plugins::new()
+                        .plugin(Plugin::new("deploy", "bin/deploy").with_summary("Ship it"))
+                        .plugin(Plugin::new("neg", "bin/neg")),
+                )
+                .dispatch(cli)
+        }
+
+        fn execute(self, _: &()) -> Result {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn plugin_commands() {
+        let external = |words: Vec<&str>| -> (bool, External) {
+            let mut cli = Cli::new().parse(args(words)).save();
+            let tool = Launch::interpret(&mut cli).unwrap();
+            assert!(cli.empty().is_ok());
+            match tool.command {
+                Some(LaunchSubcommand::Plugin(e)) => (tool.verbose, e),
+                _ => panic!("expected a plugin"),
+            }
+        };
+        // the arguments after the plugin's name are forwarded
+        let (verbose, e) = external(vec![
+            "launch",
+            "deploy",
+            "--to=prod",
+            "-vq",
+            "-n=2",
+            "site",
+            "--",
+            "-y",
+        ]);
+        assert_eq!(verbose, true);
+        assert_eq!(e.plugin().name(), "deploy");
+        assert_eq!(e.args(), &["--to=prod", "-q", "-n=2", "site", "--", "-y"]);
+        assert_eq!(e.plugin().entry(), std::path::Path::new("bin/deploy"));
+
+        // help is forwarded to the plugin instead of being displayed
+        let (_, e) = external(vec!["launch", "deploy", "--help"]);
+        assert_eq!(e.args(), &["--help"]);
+
+        // routes take priority over plugins
+        let mut cli = Cli::new().parse(args(vec!["launch", "neg", "4"])).save();
+        assert_eq!(
+            Launch::interpret(&mut cli).unwrap().command,
+            Some(LaunchSubcommand::Neg(Neg { value: 4 }))
+        );
+
+        // plugins are a part of the bank to offer suggestions
+        let mut cli = Cli::new()
+            .threshold(3)
+            .parse(args(vec!["launch", "deplyo"]))
+            .save();
+        match Launch::interpret(&mut cli).unwrap_err().context() {
+            ErrorContext::SuggestWord(_, s) => assert_eq!(s, &vec!["deploy"]),
+            _ => panic!("expected a suggestion"),
+        }
+    }
+
+    #[test]
+    fn walk_plugins() {
+        let router = Router::<LaunchSubcommand, ()>::new()
+            .route::<Neg>("neg")
+            .plugins(
+                Plugins::new().plugin(Plugin::new("deploy", "bin/deploy").with_summary("Ship it")),
+            );
+        assert_eq!(
+            router
+                .walk()
+                .iter()
+                .map(|n| (n.name(), n.summary()))
+                .collect::<Vec<_>>(),
+            vec![("neg", None), ("deploy", Some("Ship it"))]
+        );
+    }
+
+    /// Tests dynamic completions provided while interpreting a command.
+    #[derive(Debug, PartialEq)]
+    struct Vcs {
+        verbose: bool,
+        command: Option<VcsSubcommand>,
+    }
+
+    impl Command for Vcs {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Vcs {
+                verbose: cli.check(Arg::flag("verbose").switch('v'))?,
+                command: cli.nest(Arg::subcommand("command"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Checkout {
+        force: bool,
+        remote: Option<String>,
+        branch: Option<String>,
+    }
+
+    impl Subcommand<()> for Checkout {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            let branches = |_: &str| vec![String::from("main"), String::from("master")];
+            Ok(Checkout {
+                force: cli.check(Arg::flag("force"))?,
+                remote: cli
+                    .get(Arg::option("remote").completer(|_: &str| vec![String::from("origin")]))?,
+                branch: cli.get(Arg::positional("branch").completer(branches))?,
+            })
+        }
+
+        fn execute(self, _: &()) -> Result {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    enum VcsSubcommand {
+        Checkout(Checkout),
+        Neg(Neg),
+    }
+
+    impl From<Checkout> for VcsSubcommand {
+        fn from(value: Checkout) -> Self {
+            Self::Checkout(value)
+        }
+    }
+
+    impl From<Neg> for VcsSubcommand {
+        fn from(value: Neg) -> Self {
+            Self::Neg(value)
+        }
+    }
+
+    impl Subcommand<()> for VcsSubcommand {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Router::new()
+                .route::<Checkout>("checkout")
+                .route::<Neg>("clean")
+                .dispatch(cli)
+        }
+
+        fn execute(self, _: &()) -> Result {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn dynamic_completion() {
+        let complete = |words: Vec<&str>| -> Vec<String> {
+            let mut cli = Cli::new().parse(args(words)).save();
+            let _ = cli.construct::<Vcs>();
+            cli.completions().unwrap()
+        };
+        // subcommands
+        assert_eq!(
+            complete(vec!["vcs", "__complete", ""]),
+            vec!["checkout", "clean"]
+        );
+        assert_eq!(
+            complete(vec!["vcs", "__complete", "-v", "che"]),
+            vec!["checkout"]
+        );
+        // flags at each level
+        assert_eq!(
+            complete(vec!["vcs", "__complete", "-"]),
+            vec!["--verbose", "-v"]
+        );
+        // flags of the parent are still recognized after the subcommand
+        assert_eq!(
+            complete(vec!["vcs", "__complete", "checkout", "--"]),
+            vec!["--verbose", "--force", "--remote"]
+        );
+        // values from the argument's completer
+        assert_eq!(
+            complete(vec!["vcs", "__complete", "checkout", "ma"]),
+            vec!["main", "master"]
+        );
+        assert_eq!(
+            complete(vec!["vcs", "__complete", "checkout", "--remote", ""]),
+            vec!["origin"]
+        );
+        assert_eq!(
+            complete(vec!["vcs", "__complete", "checkout", "main", ""]),
+            Vec::<String>::new()
+        );
+
+        // completion is only active through the hidden entry point
+        let cli = Cli::new().parse(args(vec!["vcs", "checkout"])).save();
+        assert_eq!(cli.completions(), None);
+    }
+
+    #[test]
+    fn list_route_commands() {
+        let router = Router::<CalcSubcommand, ()>::new()
+            .route::<Add>("add")
+            .summary("Add \"two\" numbers")
+            .route::<Neg>("neg")
+            .nested(&["abs"]);
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["calc", "--list-commands"]))
+            .save();
+        let err = router.list_commands(&mut cli).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Eager);
+        assert_eq!(err.to_string(), "add\nneg\nneg abs");
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["calc", "--list-commands=json"]))
+            .save();
+        assert_eq!(
+            router.list_commands(&mut cli).unwrap_err().to_string(),
+            "[{\"path\":\"add\",\"summary\":\"Add \\\"two\\\" numbers\"},{\"path\":\"neg\",\"summary\":null},{\"path\":\"neg abs\",\"summary\":null}]"
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["calc", "--list-commands=xml"]))
+            .save();
+        assert_eq!(
+            router.list_commands(&mut cli).unwrap_err().kind(),
+            ErrorKind::UnexpectedValue
+        );
+
+        // the flag is not raised
+        let mut cli = Cli::new().parse(args(vec!["calc", "add", "1", "2"])).save();
+        assert!(router.list_commands(&mut cli).is_ok());
+        assert_eq!(
+            router.dispatch(&mut cli).unwrap(),
+            CalcSubcommand::Add(Add {
+                lhs: 1,
+                rhs: 2,
+                force: false,
+                verbose: false
+            })
+        );
+    }
+
+    /// Tests a subcommand that is entered by default.
+    #[derive(Debug, PartialEq)]
+    struct Tool {
+        command: Status,
+    }
+
+    impl Command for Tool {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            cli.help(Help::with("Usage: tool [command]"))?;
+            Ok(Tool {
+                command: cli.nest_or(Arg::subcommand("command"), "status")?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            self.command.execute(&())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Status {
+        short: bool,
+    }
+
+    impl Subcommand<()> for Status {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            cli.select(&["status"])?;
+            Ok(Status {
+                short: cli.check(Arg::flag("short"))?,
+            })
+        }
+
+        fn execute(self, _: &()) -> Result {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn default_subcommand() {
+        let mut cli = Cli::new().parse(args(vec!["tool"])).save();
+        assert_eq!(
+            Tool::interpret(&mut cli).unwrap(),
+            Tool {
+                command: Status { short: false }
+            }
+        );
+        assert_eq!(cli.command_path(), &["tool", "status"]);
+
+        // remaining arguments are given to the default subcommand
+        let mut cli = Cli::new().parse(args(vec!["tool", "--short"])).save();
+        assert_eq!(
+            Tool::interpret(&mut cli).unwrap(),
+            Tool {
+                command: Status { short: true }
+            }
+        );
+        cli.empty().unwrap();
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["tool", "status", "--short"]))
+            .save();
+        assert_eq!(
+            Tool::interpret(&mut cli).unwrap(),
+            Tool {
+                command: Status { short: true }
+            }
+        );
+
+        // help is raised for the current command
+        let mut cli = Cli::new().parse(args(vec!["tool", "--help"])).save();
+        let err = Tool::interpret(&mut cli).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Help);
+        assert_eq!(err.to_string(), "Usage: tool [command]");
+
+        let mut cli = Cli::new().parse(args(vec!["tool", "stats"])).save();
+        assert!(Tool::interpret(&mut cli).is_err());
+    }
+
+    /// Tests options that are repeated before the subcommand.
+    #[derive(Debug, PartialEq)]
+    struct Git {
+        dirs: Vec<String>,
+        command: Option<Log>,
+    }
+
+    impl Command for Git {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Git {
+                dirs: cli
+                    .get_all(Arg::option("dir").switch('C').before_subcommand())?
+                    .unwrap_or_default(),
+                command: cli.nest(Arg::subcommand("command"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Log {
+        oneline: bool,
+        count: Option<usize>,
+    }
+
+    impl Subcommand<()> for Log {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            cli.select(&["log"])?;
+            Ok(Log {
+                oneline: cli.check(Arg::flag("oneline"))?,
+                count: cli.get(Arg::option("count").switch('C'))?,
+            })
+        }
+
+        fn execute(self, _: &()) -> Result {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn options_before_subcommand() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "git", "-C", "a", "--dir=b", "-C", "c", "log", "-C", "3",
+            ]))
+            .save();
+        assert_eq!(
+            Git::interpret(&mut cli)
+                .and_then(|g| cli.empty().map(|_| g))
+                .unwrap(),
+            Git {
+                dirs: vec![String::from("a"), String::from("b"), String::from("c")],
+                command: Some(Log {
+                    oneline: false,
+                    count: Some(3)
+                }),
+            }
+        );
+
+        // the value of an option is not mistaken for the subcommand
+        let mut cli = Cli::new().parse(args(vec!["git", "-C", "log"])).save();
+        assert_eq!(
+            Git::interpret(&mut cli)
+                .and_then(|g| cli.empty().map(|_| g))
+                .unwrap(),
+            Git {
+                dirs: vec![String::from("log")],
+                command: None,
+            }
+        );
+
+        // flags of the subcommand are still out of context before its name
+        let mut cli = Cli::new()
+            .parse(args(vec!["git", "-C", "a", "--oneline", "log"]))
+            .save();
+        let err = Git::interpret(&mut cli)
+            .and_then(|g| cli.empty().map(|_| g))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::OutOfContextArgSuggest);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--oneline\" is unknown or invalid in the current context\n\nMaybe move it after \"log\"?"
+        );
+    }
+
+    /// Tests arguments that are only valid with certain subcommands.
+    #[derive(Debug, PartialEq)]
+    struct Cargo {
+        release: bool,
+        target: Option<String>,
+        command: Option<Step>,
+    }
+
+    impl Command for Cargo {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Cargo {
+                release: cli.check(Arg::flag("release").switch('r').only_with("build"))?,
+                target: cli.get(Arg::option("target").only_with("build").only_with("test"))?,
+                command: cli.nest(Arg::subcommand("command"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Step(String);
+
+    impl Subcommand<()> for Step {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Step(cli.select(&["build", "test", "clean"])?))
+        }
+
+        fn execute(self, _: &()) -> Result {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn args_only_with_subcommand() {
+        let mut cli = Cli::new()
+            .parse(args(vec!["cargo", "build", "--release", "--target=x86"]))
+            .save();
+        assert_eq!(
+            Cargo::interpret(&mut cli).unwrap(),
+            Cargo {
+                release: true,
+                target: Some(String::from("x86")),
+                command: Some(Step(String::from("build"))),
+            }
+        );
+
+        let mut cli = Cli::new()
+            .parse(args(vec!["cargo", "--target", "x86", "test"]))
+            .save();
+        assert!(Cargo::interpret(&mut cli).is_ok());
+
+        let mut cli = Cli::new().parse(args(vec!["cargo", "test", "-r"])).save();
+        let err = Cargo::interpret(&mut cli).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::RestrictedArg);
+        assert_eq!(
+            err.to_string(),
+            "argument \"--release\" is only valid with \"build\""
+        );
+
+        // supplying no subcommand is also invalid
+        let mut cli = Cli::new().parse(args(vec!["cargo", "--target=x86"])).save();
+        assert_eq!(
+            Cargo::interpret(&mut cli).unwrap_err().to_string(),
+            "argument \"--target\" is only valid with \"build\" or \"test\""
+        );
+
+        let mut cli = Cli::new().parse(args(vec!["cargo", "clean"])).save();
+        assert!(Cargo::interpret(&mut cli).is_ok());
+    }
+
+    /// Tests data passed from interpretation to execution.
+    #[cfg(feature = "runner")]
+    #[derive(Debug, PartialEq)]
+    struct Count {
+        tally: Tally,
+    }
+
+    #[cfg(feature = "runner")]
+    impl Command for Count {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            let words: String = cli.require(Arg::positional("words"))?;
+            cli.stash(words.split(',').map(String::from).collect::<Vec<String>>());
+            Ok(Count {
+                tally: cli.nest(Arg::subcommand("tally"))?.unwrap(),
+            })
+        }
+
+        fn execute(self) -> Result {
+            self.tally.execute(&())
+        }
+    }
+
+    /// Tests data passed from interpretation to a nested subcommand's execution.
+    #[cfg(feature = "runner")]
+    #[derive(Debug, PartialEq)]
+    enum Tally {
+        Count,
+        Other,
+    }
+
+    #[cfg(feature = "runner")]
+    impl Subcommand<()> for Tally {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            match cli.select(&["count", "other"])?.as_ref() {
+                "count" => Ok(Tally::Count),
+                _ => Ok(Tally::Other),
+            }
+        }
+
+        fn execute(self, _: &()) -> Result {
+            let words = stash::take::<Vec<String>>().unwrap();
+            // values are taken only once
+            assert_eq!(stash::take::<Vec<String>>(), None);
+            match self {
+                Tally::Count => Err(Exit::Custom(words.len() as u8))?,
+                Tally::Other => Err(Exit::Custom(255))?,
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn execute_with_stash() {
+        let code = Cli::new()
+            .parse(args(vec!["app", "a,b,c", "count"]))
+            .go::<Count>();
+        assert_eq!(code, ExitCode::from(3));
+
+        let code = Cli::new()
+            .parse(args(vec!["app", "a,b,c", "other"]))
+            .go::<Count>();
+        assert_eq!(code, ExitCode::from(255));
+
+        // nothing is left over once the command finishes
+        assert_eq!(stash::take::<Vec<String>>(), None);
+    }
+
+    /// Command whose output is closed by its reader.
+    #[cfg(feature = "runner")]
+    struct Piped;
+
+    #[cfg(feature = "runner")]
+    impl Command for Piped {
+        fn interpret(_: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Piped)
+        }
+
+        fn execute(self) -> Result {
+            Err(Box::new(std::io::Error::from(
+                std::io::ErrorKind::BrokenPipe,
+            )))
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn broken_pipe_exits_successfully() {
+        let code = Cli::new().parse(args(vec!["app"])).go::<Piped>();
+        assert_eq!(code, ExitCode::from(0));
+
+        // the closed pipe is found within the chain of errors
+        let err = std::io::Error::other(std::io::Error::from(std::io::ErrorKind::BrokenPipe));
+        assert_eq!(is_broken_pipe(&err), true);
+        assert_eq!(is_broken_pipe(&Halt::new(Exit::Custom(1))), false);
+    }
+
+    /// Command that succeeds when executed.
+    #[cfg(feature = "runner")]
+    struct True;
+
+    #[cfg(feature = "runner")]
+    impl Command for True {
+        fn interpret(_: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(True)
+        }
+
+        fn execute(self) -> Result {
+            Ok(())
+        }
+    }
+
+    /// Command that fails when executed.
+    #[cfg(feature = "runner")]
+    struct False;
+
+    #[cfg(feature = "runner")]
+    impl Command for False {
+        fn interpret(_: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(False)
+        }
+
+        fn execute(self) -> Result {
+            Err("failed")?
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn multicall() {
+        let app = Multicall::new()
+            .command::<True>("true")
+            .command::<False>("false")
+            .help(Help::with("Usage: box <command>"));
+        let go = |argv: Vec<&str>| app.go(Cli::new().parse(args(argv)));
+
+        // select by the executable name
+        assert_eq!(go(vec!["/usr/bin/true"]), ExitCode::from(0));
+        assert_eq!(go(vec!["false.exe"]), ExitCode::from(101));
+
+        // select by the first positional under the generic name
+        assert_eq!(go(vec!["box", "true"]), ExitCode::from(0));
+        assert_eq!(go(vec!["box", "false"]), ExitCode::from(101));
+        assert_eq!(go(vec!["box", "ture"]), ExitCode::from(101));
+        assert_eq!(go(vec!["box"]), ExitCode::from(101));
+        assert_eq!(go(vec!["box", "--help"]), ExitCode::from(0));
+
+        // the remaining arguments are given to the command
+        assert_eq!(go(vec!["true", "extra"]), ExitCode::from(101));
+    }
+
+    /// Command that passes through a given exit code.
+    #[cfg(feature = "runner")]
+    struct Wrap {
+        code: u8,
+    }
+
+    #[cfg(feature = "runner")]
+    impl Command for Wrap {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Wrap {
+                code: cli.require(Arg::positional("code"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            Err(Exit::Custom(self.code))?
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn reexec_with_modified_args() {
+        let cli = Cli::new().parse(args(vec!["app", "install"])).save();
+        let command = reexec_command(cli.raw_args().to_vec(), |mut args| {
+            args.push(String::from("--elevated"));
+            args
+        })
+        .unwrap();
+        assert_eq!(command.get_program(), std::env::current_exe().unwrap());
+        assert_eq!(
+            command.get_args().collect::<Vec<&std::ffi::OsStr>>(),
+            vec!["install", "--elevated"]
+        );
+        // the program is still run when every argument is removed
+        let command = reexec_command(vec![String::from("app")], |_| Vec::new()).unwrap();
+        assert_eq!(command.get_args().count(), 0);
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn exit_with_code() {
+        let go = |argv: Vec<&str>| Cli::new().parse(args(argv)).go::<Wrap>();
+        assert_eq!(go(vec!["wrap", "0"]), ExitCode::from(0));
+        assert_eq!(go(vec!["wrap", "3"]), ExitCode::from(3));
+        assert_eq!(go(vec!["wrap", "255"]), ExitCode::from(255));
+
+        let halt = Halt::with_message(Exit::Custom(2), "child process failed");
+        assert_eq!(halt.code(), 2);
+        assert_eq!(halt.to_string(), "child process failed");
+        assert_eq!(Halt::new(Exit::Custom(4)).message(), None);
+    }
+
+    #[test]
+    #[cfg(all(unix, feature = "runner"))]
+    fn exit_from_status() {
+        use std::os::unix::process::ExitStatusExt;
+        use std::process::ExitStatus;
+
+        assert_eq!(
+            Halt::from(ExitStatus::from_raw(2 << 8)).exit(),
+            Exit::Custom(2)
+        );
+        // terminated by a signal
+        assert_eq!(
+            Halt::from(ExitStatus::from_raw(9)).exit(),
+            Exit::RuntimeError
+        );
+    }
+
+    /// Command that ends the program with a named exit.
+    #[cfg(feature = "runner")]
+    struct Stop {
+        interrupt: bool,
+    }
+
+    #[cfg(feature = "runner")]
+    impl Command for Stop {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(Stop {
+                interrupt: cli.check(Arg::flag("interrupt"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            match self.interrupt {
+                true => Err(Exit::Interrupted)?,
+                false => Err(Exit::Custom(7))?,
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "runner")]
+    fn exit_with_semantics() {
+        let go = |argv: Vec<&str>| Cli::new().parse(args(argv)).go::<Stop>();
+        assert_eq!(go(vec!["stop", "--interrupt"]), ExitCode::from(130));
+        assert_eq!(go(vec!["stop"]), ExitCode::from(7));
+        assert_eq!(go(vec!["stop", "extra"]), Exit::UsageError.into());
+
+        assert_eq!(Exit::Success.is_success(), true);
+        assert_eq!(Exit::Custom(0).is_success(), true);
+        assert_eq!(Exit::RuntimeError.code(), 101);
+        assert_eq!(Halt::from(Exit::Interrupted).code(), 130);
+    }
+
+    /// Context created by the top-level command.
+    #[derive(Debug, PartialEq)]
+    struct Config {
+        profile: String,
+    }
+
+    /// Enriched context created for subcommands of [Db].
+    #[derive(Debug, PartialEq)]
+    struct Session {
+        profile: String,
+        table: String,
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct App {
+        profile: String,
+        db: Option<Db>,
+    }
+
+    impl Command for App {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            Ok(App {
+                profile: cli.require(Arg::option("profile"))?,
+                db: cli.nest(Arg::subcommand("command"))?,
+            })
+        }
+
+        fn execute(self) -> Result {
+            let config = Config {
+                profile: self.profile,
+            };
+            match self.db {
+                Some(db) => db.execute(&config),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Db {
+        query: Option<Nested<Query, Config, Session>>,
+    }
+
+    impl Subcommand<Config> for Db {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            cli.select(&["db"])?;
+            let table: String = cli.require(Arg::option("table"))?;
+            Ok(Db {
+                query: cli.nest_with(Arg::subcommand("command"), move |c: &Config| Session {
+                    profile: c.profile.clone(),
+                    table: table,
+                })?,
+            })
+        }
+
+        fn execute(self, context: &Config) -> Result {
+            match self.query {
+                Some(query) => query.execute(context),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Query {
+        key: String,
+    }
+
+    impl Subcommand<Session> for Query {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            cli.select(&["query"])?;
+            Ok(Query {
+                key: cli.require(Arg::positional("key"))?,
+            })
+        }
+
+        fn execute(self, context: &Session) -> Result {
+            assert_eq!(context.profile, "dev");
+            assert_eq!(context.table, "users");
+            assert_eq!(self.key, "alice");
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn nested_with_context() {
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "app",
+                "--profile",
+                "dev",
+                "db",
+                "--table",
+                "users",
+                "query",
+                "alice",
+            ]))
+            .save();
+        let app = App::interpret(&mut cli).unwrap();
+        assert_eq!(
+            app.db.as_ref().unwrap().query.as_ref().unwrap().get(),
+            &Query {
+                key: String::from("alice")
+            }
+        );
+        assert!(app.execute().is_ok());
+
+        let mut cli = Cli::new()
+            .parse(args(vec![
+                "app",
+                "--profile",
+                "dev",
+                "db",
+                "--table",
+                "users",
+            ]))
+            .save();
+        let app = App::interpret(&mut cli).unwrap();
+        assert_eq!(app.db, Some(Db { query: None }));
+    }
+
+    /// Command that falls back to the profile from its context.
+    #[derive(Debug, PartialEq)]
+    struct Deploy {
+        profile: String,
+    }
+
+    impl CommandFrom<Config> for Deploy {
+        fn interpret(cli: &mut Cli<Memory>, context: &Config) -> cli::Result<Self> {
+            Ok(Deploy {
+                profile: cli
+                    .get(Arg::option("profile"))?
+                    .unwrap_or_else(|| context.profile.clone()),
+            })
+        }
+
+        fn execute(self, context: &Config) -> Result {
+            match self.profile == context.profile {
+                true => Ok(()),
+                false => Err(Exit::Custom(3))?,
+            }
+        }
+    }
+
+    #[test]
+    fn command_from_context() {
+        let config = || Config {
+            profile: String::from("dev"),
+        };
+        let build = |argv: Vec<&str>| {
+            Cli::new()
+                .parse(args(argv))
+                .build_with::<Deploy, _>(&config())
+        };
+        assert_eq!(
+            build(vec!["deploy"]).unwrap(),
+            Deploy {
+                profile: String::from("dev")
+            }
+        );
+        assert_eq!(
+            build(vec!["deploy", "--profile", "prod"]).unwrap(),
+            Deploy {
+                profile: String::from("prod")
+            }
+        );
+        assert!(build(vec!["deploy", "extra"]).is_err());
+
+        #[cfg(feature = "runner")]
+        {
+            let go = |argv: Vec<&str>| Cli::new().parse(args(argv)).go_with::<Deploy, _>(config());
+            assert_eq!(go(vec!["deploy"]), ExitCode::from(0));
+            assert_eq!(go(vec!["deploy", "--profile", "prod"]), ExitCode::from(3));
+        }
+    }
+
     #[test]
     fn reuse_collected_arg() {
         let mut cli = Cli::new()
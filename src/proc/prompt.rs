@@ -0,0 +1,148 @@
+//! Interactive questions asked by a command before it performs an operation
+//! that cannot be undone.
+//!
+//! A [Confirm] is taken from the command-line processor along with the common
+//! `--yes` (`-y`) flag, see [check_yes][crate::Cli::check_yes]. Raising the flag
+//! answers every question with "yes", so the command can also be scripted.
+
+use std::fmt::Display;
+use std::io::{BufRead, IsTerminal, Write};
+
+mod symbol {
+    pub const YES: [&str; 2] = ["y", "yes"];
+    pub const NO: [&str; 3] = ["", "n", "no"];
+}
+
+/// Asks the user to confirm an operation, unless it was already confirmed on
+/// the command-line.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Confirm {
+    assumed: bool,
+}
+
+impl Confirm {
+    /// Creates a confirmation that answers "yes" without asking when `assumed`
+    /// is enabled.
+    pub fn new(assumed: bool) -> Self {
+        Self { assumed: assumed }
+    }
+
+    /// Checks if every question is answered with "yes" without asking.
+    pub fn is_assumed(&self) -> bool {
+        self.assumed
+    }
+
+    /// Asks the `question` on `stderr` and reads the answer from `stdin`.
+    ///
+    /// Returns `true` without asking if the answer is assumed. Otherwise, the
+    /// question is repeated until answered with "y" or "n", where no answer
+    /// means "n".
+    ///
+    /// This function errors if the answer is not assumed and `stdin` or `stderr`
+    /// is not a terminal, as no one is there to answer.
+    pub fn ask<T: AsRef<str>>(&self, question: T) -> Result<bool, PromptError> {
+        if self.assumed == true {
+            return Ok(true);
+        }
+        let question = question.as_ref();
+        if std::io::stdin().is_terminal() == false || std::io::stderr().is_terminal() == false {
+            return Err(PromptError::NotInteractive(String::from(question)));
+        }
+        Self::read_answer(question, std::io::stdin().lock(), std::io::stderr())
+            .map_err(PromptError::Io)
+    }
+
+    fn read_answer<R: BufRead, W: Write>(
+        question: &str,
+        mut input: R,
+        mut output: W,
+    ) -> std::io::Result<bool> {
+        loop {
+            write!(output, "{} [y/N] ", question)?;
+            output.flush()?;
+            let mut line = String::new();
+            // no one is left to answer once the input is closed
+            if input.read_line(&mut line)? == 0 {
+                writeln!(output)?;
+                return Ok(false);
+            }
+            let answer = line.trim().to_lowercase();
+            if symbol::YES.contains(&answer.as_str()) == true {
+                return Ok(true);
+            } else if symbol::NO.contains(&answer.as_str()) == true {
+                return Ok(false);
+            }
+        }
+    }
+}
+
+/// The reasons a question can fail to be answered.
+#[derive(Debug)]
+pub enum PromptError {
+    /// The question needs an answer but nothing is connected to a terminal.
+    NotInteractive(String),
+    Io(std::io::Error),
+}
+
+impl Display for PromptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotInteractive(q) => write!(
+                f,
+                "cannot ask \"{}\" without a terminal; use \"--yes\" to answer \"yes\"",
+                q
+            ),
+            Self::Io(e) => write!(f, "failed to read answer: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PromptError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn answer(input: &str) -> (bool, String) {
+        let mut output = Vec::new();
+        let ok = Confirm::read_answer("Delete 42 files?", input.as_bytes(), &mut output).unwrap();
+        (ok, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn read_answers() {
+        assert_eq!(
+            answer("y\n"),
+            (true, String::from("Delete 42 files? [y/N] "))
+        );
+        assert_eq!(answer("YES\n").0, true);
+        assert_eq!(answer("\n").0, false);
+        assert_eq!(answer("no\n").0, false);
+        // an unknown answer asks again
+        assert_eq!(
+            answer("maybe\ny\n"),
+            (
+                true,
+                String::from("Delete 42 files? [y/N] Delete 42 files? [y/N] ")
+            )
+        );
+        // a closed input declines
+        assert_eq!(answer("").0, false);
+    }
+
+    #[test]
+    fn assumed_answer() {
+        assert_eq!(Confirm::new(true).ask("Delete 42 files?").unwrap(), true);
+        assert_eq!(
+            PromptError::NotInteractive(String::from("Delete?")).to_string(),
+            "cannot ask \"Delete?\" without a terminal; use \"--yes\" to answer \"yes\""
+        );
+    }
+}
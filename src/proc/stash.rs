@@ -0,0 +1,138 @@
+//! Data passed from a command's interpretation to its execution.
+//!
+//! Some values are derived while the command-line is interpreted but do not
+//! belong in the command's struct (ex: an opened file or a resolved
+//! configuration), since they would force the struct to give up comparisons
+//! and debug printing. Such values are stashed with [stash][crate::Cli::stash]
+//! and taken with [take] while the command executes, including from any of its
+//! subcommands:
+//!
+//! ```text
+//! interpret: cli.stash(File::open(&path)?)
+//! execute:   let file = stash::take::<File>().unwrap()
+//! ```
+
+use std::any::{Any, TypeId};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+thread_local! {
+    /// The values stashed for the command that is currently executing.
+    static CURRENT: RefCell<Stash> = RefCell::new(Stash::new());
+}
+
+/// Removes and returns the value of type `T` that was stashed while the
+/// command-line was interpreted, if one exists.
+///
+/// The values are available while the command is executed by
+/// [go][crate::Cli::go], and any values that are not taken are dropped once it
+/// finishes.
+pub fn take<T: 'static>() -> Option<T> {
+    CURRENT.with(|s| s.borrow_mut().take::<T>())
+}
+
+/// Makes the values in `stash` available to [take] while `f` runs.
+#[cfg(any(feature = "runner", test))]
+pub(crate) fn provide<R, F: FnOnce() -> R>(stash: Stash, f: F) -> R {
+    let outer = CURRENT.with(|s| std::mem::replace(&mut *s.borrow_mut(), stash));
+    let result = f();
+    // values are dropped outside of the borrow in case dropping them takes values
+    let inner = CURRENT.with(|s| std::mem::replace(&mut *s.borrow_mut(), outer));
+    std::mem::drop(inner);
+    result
+}
+
+/// A collection holding at most one value of each type.
+#[derive(Default)]
+pub struct Stash {
+    values: HashMap<TypeId, Box<dyn Any>>,
+}
+
+impl Stash {
+    /// Creates a new stash with no values.
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+
+    /// Stores the `value`, returning the previous value of the same type if one
+    /// was stored.
+    pub fn insert<T: 'static>(&mut self, value: T) -> Option<T> {
+        self.values
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .map(|v| *v.downcast::<T>().unwrap())
+    }
+
+    /// References the stored value of type `T`, if one exists.
+    pub fn get<T: 'static>(&self) -> Option<&T> {
+        self.values
+            .get(&TypeId::of::<T>())
+            .map(|v| v.downcast_ref::<T>().unwrap())
+    }
+
+    /// Removes and returns the stored value of type `T`, if one exists.
+    pub fn take<T: 'static>(&mut self) -> Option<T> {
+        self.values
+            .remove(&TypeId::of::<T>())
+            .map(|v| *v.downcast::<T>().unwrap())
+    }
+
+    /// Returns the number of stored values.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Checks if there are no stored values.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Stash {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Stash({})", self.values.len())
+    }
+}
+
+/// Stashes are compared by the types of the values they store, since the values
+/// themselves may not be comparable.
+impl PartialEq for Stash {
+    fn eq(&self, other: &Self) -> bool {
+        self.values.len() == other.values.len()
+            && self.values.keys().all(|k| other.values.contains_key(k))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn store_by_type() {
+        let mut stash = Stash::new();
+        assert_eq!(stash.insert(String::from("config.toml")), None);
+        assert_eq!(stash.insert(8_u16), None);
+        assert_eq!(stash.insert(9_u16), Some(8));
+        assert_eq!(stash.len(), 2);
+
+        assert_eq!(stash.get::<String>().unwrap(), "config.toml");
+        assert_eq!(stash.get::<u32>(), None);
+        assert_eq!(stash.take::<u16>(), Some(9));
+        assert_eq!(stash.take::<u16>(), None);
+        assert_ne!(stash, Stash::new());
+    }
+
+    #[test]
+    fn take_while_provided() {
+        let mut stash = Stash::new();
+        stash.insert(String::from("config.toml"));
+        let name = provide(stash, || {
+            // an inner stash hides the outer one until it finishes
+            assert_eq!(provide(Stash::new(), take::<String>), None);
+            take::<String>()
+        });
+        assert_eq!(name, Some(String::from("config.toml")));
+        assert_eq!(take::<String>(), None);
+    }
+}
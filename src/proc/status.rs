@@ -0,0 +1,258 @@
+//! Status messages written to `stderr` while a command executes.
+//!
+//! A [Status] is taken from the command-line processor once its verbosity and
+//! coloring are known, so every command of an application reports its progress
+//! the same way:
+//!
+//! ```text
+//!    Compiling cliproc v2.1.1
+//!     Finished release in 4.2s
+//! ```
+//!
+//! Nothing is written when the verbosity is [Quiet][Verbosity::Quiet], and a
+//! [Spinner] is only drawn when `stderr` is a terminal.
+
+use crate::cli::Verbosity;
+use crate::error::Paint;
+use std::fmt::Display;
+use std::io::{IsTerminal, Write};
+
+/// The width that step labels are right-aligned to.
+const LABEL_WIDTH: usize = 12;
+
+/// The frames a [Spinner] cycles through on each tick.
+const FRAMES: [char; 4] = ['|', '/', '-', '\\'];
+
+/// Writes status messages to `stderr` according to the verbosity and coloring
+/// requested on the command-line, see [status][crate::Cli::status].
+#[derive(Debug, PartialEq, Clone)]
+pub struct Status {
+    verbosity: Verbosity,
+    color: bool,
+    terminal: bool,
+}
+
+impl Status {
+    /// Creates a status writer that writes at the `verbosity` level, applying
+    /// colors if `color` is enabled.
+    pub fn new(verbosity: Verbosity, color: bool) -> Self {
+        Self {
+            verbosity: verbosity,
+            color: color,
+            terminal: std::io::stderr().is_terminal(),
+        }
+    }
+
+    /// Checks if no status messages are written.
+    pub fn is_quiet(&self) -> bool {
+        self.verbosity == Verbosity::Quiet
+    }
+
+    /// Checks if additional status messages are written, see [note][Status::note].
+    pub fn is_verbose(&self) -> bool {
+        self.verbosity >= Verbosity::Verbose
+    }
+
+    /// Writes a step with an action `label` (ex: `Compiling`) followed by its
+    /// `message`.
+    pub fn step<T: AsRef<str>, U: Display>(&self, label: T, message: U) -> () {
+        if let Some(line) = self.format_step(label.as_ref(), message) {
+            Self::write(&line);
+        }
+    }
+
+    /// Writes a `message` that is only of interest when more output is requested.
+    pub fn note<T: Display>(&self, message: T) -> () {
+        if let Some(line) = self.format_note(message) {
+            Self::write(&line);
+        }
+    }
+
+    /// Writes a warning `message`.
+    pub fn warn<T: Display>(&self, message: T) -> () {
+        if let Some(line) = self.format_warn(message) {
+            Self::write(&line);
+        }
+    }
+
+    /// Creates a spinner that shows `message` while work is in progress.
+    pub fn spinner<T: AsRef<str>>(&self, message: T) -> Spinner<'_> {
+        Spinner {
+            status: self,
+            message: String::from(message.as_ref()),
+            frame: 0,
+            drawn: false,
+        }
+    }
+
+    fn format_step<U: Display>(&self, label: &str, message: U) -> Option<String> {
+        match self.is_quiet() {
+            true => None,
+            false => Some(format!(
+                "{} {}",
+                Paint::new(self.color).bold_green(format!("{:>LABEL_WIDTH$}", label)),
+                message
+            )),
+        }
+    }
+
+    fn format_note<T: Display>(&self, message: T) -> Option<String> {
+        match self.is_verbose() {
+            true => Some(format!(
+                "{} {}",
+                Paint::new(self.color).blue("note:"),
+                message
+            )),
+            false => None,
+        }
+    }
+
+    fn format_warn<T: Display>(&self, message: T) -> Option<String> {
+        match self.is_quiet() {
+            true => None,
+            false => Some(format!(
+                "{} {}",
+                Paint::new(self.color).yellow("warning:"),
+                message
+            )),
+        }
+    }
+
+    /// Checks if the status is able to redraw the current line.
+    fn is_animated(&self) -> bool {
+        self.terminal == true && self.is_quiet() == false
+    }
+
+    fn write(line: &str) -> () {
+        let _ = writeln!(std::io::stderr(), "{}", line);
+    }
+}
+
+/// A message shown with an animated frame that advances on each
+/// [tick][Spinner::tick].
+///
+/// The spinner is only drawn when `stderr` is a terminal, and is cleared once
+/// it is finished or dropped.
+#[derive(Debug)]
+pub struct Spinner<'a> {
+    status: &'a Status,
+    message: String,
+    frame: usize,
+    drawn: bool,
+}
+
+impl<'a> Spinner<'a> {
+    /// Advances the spinner to its next frame.
+    pub fn tick(&mut self) -> () {
+        if self.status.is_animated() == true {
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K{}", self.frame_line());
+            let _ = stderr.flush();
+            self.drawn = true;
+        }
+        self.frame = (self.frame + 1) % FRAMES.len();
+    }
+
+    /// Replaces the message shown next to the spinner.
+    pub fn set_message<T: AsRef<str>>(&mut self, message: T) -> () {
+        self.message = String::from(message.as_ref());
+    }
+
+    /// Clears the spinner and writes a step with the `label` and `message` in
+    /// its place, see [step][Status::step].
+    pub fn finish<T: AsRef<str>, U: Display>(mut self, label: T, message: U) -> () {
+        self.clear();
+        self.status.step(label, message);
+    }
+
+    fn frame_line(&self) -> String {
+        format!(
+            "{} {}",
+            Paint::new(self.status.color)
+                .bold_green(format!("{:>LABEL_WIDTH$}", FRAMES[self.frame])),
+            self.message
+        )
+    }
+
+    fn clear(&mut self) -> () {
+        if self.drawn == true {
+            let mut stderr = std::io::stderr().lock();
+            let _ = write!(stderr, "\r\x1b[2K");
+            let _ = stderr.flush();
+            self.drawn = false;
+        }
+    }
+}
+
+impl<'a> Drop for Spinner<'a> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn status(verbosity: Verbosity) -> Status {
+        Status {
+            verbosity: verbosity,
+            color: false,
+            terminal: true,
+        }
+    }
+
+    #[test]
+    fn format_messages() {
+        let s = status(Verbosity::Normal);
+        assert_eq!(
+            s.format_step("Compiling", "cliproc v2.1.1"),
+            Some(String::from("   Compiling cliproc v2.1.1"))
+        );
+        assert_eq!(s.format_note("using cache"), None);
+        assert_eq!(
+            s.format_warn("unused manifest key"),
+            Some(String::from("warning: unused manifest key"))
+        );
+        assert_eq!(
+            status(Verbosity::Verbose).format_note("using cache"),
+            Some(String::from("note: using cache"))
+        );
+
+        // quiet writes nothing and never animates
+        let s = status(Verbosity::Quiet);
+        assert_eq!(s.format_step("Compiling", "cliproc"), None);
+        assert_eq!(s.format_warn("unused"), None);
+        assert_eq!(s.is_animated(), false);
+    }
+
+    #[test]
+    fn spinner_frames() {
+        let s = Status {
+            verbosity: Verbosity::Normal,
+            color: false,
+            terminal: false,
+        };
+        let mut spinner = s.spinner("Fetching");
+        assert_eq!(spinner.frame_line(), format!("{:>12} Fetching", '|'));
+        spinner.tick();
+        spinner.set_message("Fetching index");
+        assert_eq!(spinner.frame_line(), format!("{:>12} Fetching index", '/'));
+        // nothing is drawn when stderr is not a terminal
+        assert_eq!(spinner.drawn, false);
+
+        // the frame is aligned before it is colored
+        let s = Status {
+            verbosity: Verbosity::Normal,
+            color: true,
+            terminal: false,
+        };
+        assert_eq!(
+            s.spinner("Fetching").frame_line(),
+            format!(
+                "{} Fetching",
+                Paint::new(true).bold_green(format!("{:>12}", '|'))
+            )
+        );
+    }
+}
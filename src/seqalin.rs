@@ -57,24 +57,97 @@ fn sequence_alignment(s1: &str, s2: &str, gap_penalty: Cost, mismatch_penalty: C
     lut[s1.len()][s2.len()]
 }
 
-/// Given a word `s` and a known set of words `bank`, determine which word has
-/// the minimum edit distance to the given word while being below the `threshold`.
+/// Computes the edit distance between `s1` and `s2` with unit gap and mismatch
+/// penalties only if it is below the `bound`.
 ///
-/// The `gap_penalty` and `mismatch penalty` for sequence alignment are internally set.
-pub fn sel_min_edit_str<'a, T: AsRef<str>>(
+/// A cell further than `bound` from the diagonal cannot lead to a cost below
+/// `bound`, so only the band around the diagonal is filled (Ukkonen's cutoff).
+/// The computation stops early once every cell of a row reaches the `bound`.
+///
+/// __time complexity__: O(n * bound)   
+/// __space complexity__: O(m)
+fn bounded_alignment(s1: &str, s2: &str, bound: Cost) -> Option<Cost> {
+    let s1: Vec<char> = s1.chars().collect();
+    let s2: Vec<char> = s2.chars().collect();
+    // the difference in lengths alone requires at least that many gaps
+    if bound == 0 || s1.len().abs_diff(s2.len()) >= bound {
+        return None;
+    }
+    // cells outside of the band are considered unreachable at the bound
+    let mut prev: Vec<Cost> = (0..=s2.len()).map(|j| j.min(bound)).collect();
+    let mut cur: Vec<Cost> = vec![bound; s2.len() + 1];
+    for i in 1..=s1.len() {
+        let lo = i.saturating_sub(bound - 1).max(1);
+        let hi = (i + bound - 1).min(s2.len());
+        cur[lo - 1] = match lo {
+            1 => i.min(bound),
+            _ => bound,
+        };
+        let mut row_min = cur[lo - 1];
+        for j in lo..=hi {
+            cur[j] = ((s1[i - 1] != s2[j - 1]) as Cost + prev[j - 1])
+                .min(1 + prev[j])
+                .min(1 + cur[j - 1])
+                .min(bound);
+            row_min = row_min.min(cur[j]);
+        }
+        if hi < s2.len() {
+            cur[hi + 1] = bound;
+        }
+        if row_min >= bound {
+            return None;
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    match prev[s2.len()] < bound {
+        true => Some(prev[s2.len()]),
+        false => None,
+    }
+}
+
+/// Given a word `s` and a known set of words `bank`, rank the words with an
+/// edit distance below the `threshold` in order of increasing cost.
+///
+/// Words of equal cost keep their order from `bank`.
+pub fn rank_edit_strs<'a, T: AsRef<str>>(s: &str, bank: &'a [T], threshold: Cost) -> Vec<&'a str> {
+    rank_within(s, bank, |_| threshold)
+}
+
+/// Given a word `s` and a known set of words `bank`, rank the words within a
+/// threshold scaled by the length of each candidate in order of increasing cost.
+///
+/// Words of equal cost keep their order from `bank`.
+pub fn rank_edit_strs_scaled<'a, T: AsRef<str>>(s: &str, bank: &'a [T]) -> Vec<&'a str> {
+    rank_within(s, bank, scaled_threshold)
+}
+
+fn rank_within<'a, T: AsRef<str>>(
     s: &str,
     bank: &'a [T],
-    threshold: Cost,
-) -> Option<&'a str> {
-    let (w, c) = bank
+    threshold: impl Fn(&str) -> Cost,
+) -> Vec<&'a str> {
+    let mut ranks: Vec<(&str, Cost)> = bank
         .iter()
-        .map(|f| (f, sequence_alignment(s, f.as_ref(), 1, 1)))
-        .min_by(|x, y| x.1.cmp(&y.1))?;
-    if c < threshold {
-        Some(w.as_ref())
-    } else {
-        None
-    }
+        .filter_map(|f| {
+            bounded_alignment(s, f.as_ref(), threshold(f.as_ref())).map(|c| (f.as_ref(), c))
+        })
+        .collect();
+    // a stable sort keeps ties in their original order
+    ranks.sort_by_key(|x| x.1);
+    ranks.into_iter().map(|(w, _)| w).collect()
+}
+
+/// Computes the threshold for the word `s` such that the allowed edit distance
+/// is a third of its length, allowing at least one edit.
+pub fn scaled_threshold(s: &str) -> Cost {
+    (s.chars().count() / 3).max(1) + 1
+}
+
+/// Finds the word in `bank` closest to `s` along with its edit cost.
+pub fn min_edit_str<'a, T: AsRef<str>>(s: &str, bank: &'a [T]) -> Option<(&'a str, Cost)> {
+    bank.iter()
+        .map(|f| (f.as_ref(), sequence_alignment(s, f.as_ref(), 1, 1)))
+        .min_by(|x, y| x.1.cmp(&y.1))
 }
 
 #[cfg(test)]
@@ -96,17 +169,89 @@ mod test {
         assert_eq!(sequence_alignment("ALPHA", "alpha", 2, 1), 5);
     }
 
+    #[test]
+    fn bounded_matches_full_alignment() {
+        let words = [
+            "",
+            "a",
+            "go",
+            "stop",
+            "tops",
+            "build",
+            "buif",
+            "palate",
+            "palette",
+            "identity",
+            "similarity",
+            "ocurrance",
+            "occurrence",
+            "--verbose",
+            "--verbsoe",
+            "--version",
+        ];
+        for s1 in words {
+            for s2 in words {
+                let cost = sequence_alignment(s1, s2, 1, 1);
+                for bound in 0..12 {
+                    let expected = match cost < bound {
+                        true => Some(cost),
+                        false => None,
+                    };
+                    assert_eq!(bounded_alignment(s1, s2, bound), expected);
+                }
+            }
+        }
+    }
+
     #[test]
     fn get_closest_word() {
         let bank: Vec<&str> = vec![];
-        assert_eq!(sel_min_edit_str("word", &bank, 3), None);
+        assert_eq!(rank_edit_strs("word", &bank, 3).first(), None);
 
         let bank: Vec<&str> = vec!["run", "check", "build", "plan", "config", "play", "digit"];
 
-        assert_eq!(sel_min_edit_str("buif", &bank, 3), Some("build"));
-        assert_eq!(sel_min_edit_str("word", &bank, 3), None);
-        assert_eq!(sel_min_edit_str("plug", &bank, 3), Some("plan"));
-        assert_eq!(sel_min_edit_str("cck", &bank, 3), Some("check"));
-        assert_eq!(sel_min_edit_str("digt", &bank, 3), Some("digit"));
+        assert_eq!(rank_edit_strs("buif", &bank, 3).first(), Some(&"build"));
+        assert_eq!(rank_edit_strs("word", &bank, 3).first(), None);
+        assert_eq!(rank_edit_strs("plug", &bank, 3).first(), Some(&"plan"));
+        assert_eq!(rank_edit_strs("cck", &bank, 3).first(), Some(&"check"));
+        assert_eq!(rank_edit_strs("digt", &bank, 3).first(), Some(&"digit"));
+    }
+
+    #[test]
+    fn rank_closest_words() {
+        let bank: Vec<&str> = vec!["--version", "--verbose", "--lib", "--verb"];
+        assert_eq!(
+            rank_edit_strs("--verbsoe", &bank, 4),
+            vec!["--verbose", "--version", "--verb"]
+        );
+        assert_eq!(rank_edit_strs("--verbsoe", &bank, 3), vec!["--verbose"]);
+        // ties keep their order from the bank
+        assert_eq!(
+            rank_edit_strs("--vers", &bank, 4),
+            vec!["--verb", "--version", "--verbose"]
+        );
+        assert_eq!(rank_edit_strs("--vers", &bank, 0), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn get_closest_word_scaled() {
+        assert_eq!(scaled_threshold("go"), 2);
+        assert_eq!(scaled_threshold("verbose"), 3);
+        assert_eq!(scaled_threshold("no-default-features"), 7);
+
+        let bank: Vec<&str> = vec!["run", "go", "verbose", "no-default-features"];
+        // short words only allow a single edit
+        assert_eq!(rank_edit_strs_scaled("rnx", &bank).first(), None);
+        assert_eq!(rank_edit_strs_scaled("rn", &bank).first(), Some(&"run"));
+        // long words allow more edits
+        assert_eq!(rank_edit_strs_scaled("vrebsoe", &bank).first(), None);
+        assert_eq!(
+            rank_edit_strs_scaled("verbsoe", &bank).first(),
+            Some(&"verbose")
+        );
+        assert_eq!(
+            rank_edit_strs_scaled("no-defualt-feature", &bank).first(),
+            Some(&"no-default-features")
+        );
     }
 }
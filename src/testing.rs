@@ -0,0 +1,344 @@
+//! Table-driven tests for the behavior of an entire command-line interface.
+//!
+//! A [Corpus] is a table of invocations, each paired with what is expected to
+//! come out of processing it: the constructed command, the kind of error, a
+//! part of the error's message, or the full text of the error (or help) as
+//! stored in a golden file. The whole table is run as a single test:
+//!
+//! ```
+//! use cliproc::{cli, proc, Arg, Cli, Command};
+//! use cliproc::cli::ErrorKind;
+//! use cliproc::stage::Memory;
+//! use cliproc::testing::Corpus;
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct Add {
+//!     lhs: u8,
+//!     rhs: u8,
+//! }
+//!
+//! impl Command for Add {
+//!     fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+//!         Ok(Self {
+//!             lhs: cli.require(Arg::positional("lhs"))?,
+//!             rhs: cli.require(Arg::positional("rhs"))?,
+//!         })
+//!     }
+//!
+//!     fn execute(self) -> proc::Result {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! Corpus::<Add>::new()
+//!     .ok(["add", "1", "2"], Add { lhs: 1, rhs: 2 })
+//!     .err(["add", "1"], ErrorKind::MissingPositional)
+//!     .err_contains(["add", "1", "x"], "failed to process value \"x\"")
+//!     .run();
+//! ```
+//!
+//! Golden files are compared against the error rendered without any styling.
+//! When the `CLIPROC_BLESS` environment variable is set, the golden files are
+//! written with the current output instead of being compared.
+
+use crate::cli::{stage::Build, Cli, ErrorKind, ErrorStyle};
+use crate::Command;
+use std::fmt::{Debug, Display};
+use std::path::{Path, PathBuf};
+
+/// The environment variable that requests golden files to be rewritten.
+const BLESS: &str = "CLIPROC_BLESS";
+
+/// The outcome expected from processing a command-line.
+#[derive(Debug, PartialEq)]
+pub enum Expect<T> {
+    /// The command is constructed equal to the value.
+    Parsed(T),
+    /// An error of the kind is returned.
+    Kind(ErrorKind),
+    /// An error is returned whose message contains the text.
+    Message(String),
+    /// An error is returned whose message equals the contents of the file.
+    Golden(PathBuf),
+}
+
+/// An invocation that did not match its expectation.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Failure {
+    args: Vec<String>,
+    reason: String,
+}
+
+impl Failure {
+    /// References the command-line of the failed invocation.
+    pub fn args(&self) -> &[String] {
+        &self.args
+    }
+
+    /// References the reason the invocation failed.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"{}\": {}", self.args.join(" "), self.reason)
+    }
+}
+
+/// A table of invocations of the command `T` and their expected outcomes.
+pub struct Corpus<T> {
+    cli: Box<dyn Fn() -> Cli<Build>>,
+    cases: Vec<(Vec<String>, Expect<T>)>,
+    bless: bool,
+}
+
+impl<T: Command + PartialEq + Debug> Corpus<T> {
+    /// Creates an empty table where each invocation is processed by a default [Cli].
+    pub fn new() -> Self {
+        Self {
+            cli: Box::new(Cli::new),
+            cases: Vec::new(),
+            bless: std::env::var_os(BLESS).is_some(),
+        }
+    }
+
+    /// Processes each invocation with the [Cli] created by `cli`, such as to
+    /// share the builder settings of the application.
+    pub fn with_cli<F: Fn() -> Cli<Build> + 'static>(mut self, cli: F) -> Self {
+        self.cli = Box::new(cli);
+        self
+    }
+
+    /// Writes the golden files with the current output instead of comparing
+    /// against them when `enabled`.
+    ///
+    /// By default, this is enabled when the `CLIPROC_BLESS` environment variable
+    /// is set.
+    pub fn bless(mut self, enabled: bool) -> Self {
+        self.bless = enabled;
+        self
+    }
+
+    /// Adds the invocation `args` with the `expect`ed outcome.
+    ///
+    /// The `args` are the full command-line given to [parse][Cli::parse],
+    /// beginning with the program name.
+    pub fn case<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        mut self,
+        args: I,
+        expect: Expect<T>,
+    ) -> Self {
+        self.cases.push((
+            args.into_iter().map(|s| String::from(s.as_ref())).collect(),
+            expect,
+        ));
+        self
+    }
+
+    /// Adds the invocation `args` that is expected to construct `command`.
+    pub fn ok<I: IntoIterator<Item = S>, S: AsRef<str>>(self, args: I, command: T) -> Self {
+        self.case(args, Expect::Parsed(command))
+    }
+
+    /// Adds the invocation `args` that is expected to error with `kind`.
+    pub fn err<I: IntoIterator<Item = S>, S: AsRef<str>>(self, args: I, kind: ErrorKind) -> Self {
+        self.case(args, Expect::Kind(kind))
+    }
+
+    /// Adds the invocation `args` that is expected to error with a message
+    /// containing `text`.
+    pub fn err_contains<I: IntoIterator<Item = S>, S: AsRef<str>, U: AsRef<str>>(
+        self,
+        args: I,
+        text: U,
+    ) -> Self {
+        self.case(args, Expect::Message(String::from(text.as_ref())))
+    }
+
+    /// Adds the invocation `args` that is expected to error with the message
+    /// stored in the golden file at `path`, such as for help text.
+    pub fn golden<I: IntoIterator<Item = S>, S: AsRef<str>, P: AsRef<Path>>(
+        self,
+        args: I,
+        path: P,
+    ) -> Self {
+        self.case(args, Expect::Golden(path.as_ref().to_path_buf()))
+    }
+
+    /// Processes every invocation and collects the ones that did not match
+    /// their expectation.
+    pub fn check(self) -> Vec<Failure> {
+        let mut failures = Vec::new();
+        for (args, expect) in self.cases {
+            let result = (self.cli)().parse(args.clone().into_iter()).build::<T>();
+            let reason = match (result, expect) {
+                (Ok(command), Expect::Parsed(expected)) => match command == expected {
+                    true => None,
+                    false => Some(format!("expected {:?} but got {:?}", expected, command)),
+                },
+                (Ok(command), _) => Some(format!("expected an error but got {:?}", command)),
+                (Err(err), Expect::Parsed(_)) => {
+                    Some(format!("expected a command but got error: {}", err))
+                }
+                (Err(err), Expect::Kind(kind)) => match err.kind() == kind {
+                    true => None,
+                    false => Some(format!(
+                        "expected error {:?} but got {:?}: {}",
+                        kind,
+                        err.kind(),
+                        err
+                    )),
+                },
+                (Err(err), Expect::Message(text)) => match err.to_string().contains(&text) {
+                    true => None,
+                    false => Some(format!(
+                        "expected error containing \"{}\" but got: {}",
+                        text, err
+                    )),
+                },
+                (Err(err), Expect::Golden(path)) => {
+                    Self::compare(&path, &err.render(&ErrorStyle::new()), self.bless)
+                }
+            };
+            if let Some(reason) = reason {
+                failures.push(Failure {
+                    args: args,
+                    reason: reason,
+                });
+            }
+        }
+        failures
+    }
+
+    /// Processes every invocation, panicking with every one that did not match
+    /// its expectation.
+    pub fn run(self) -> () {
+        let total = self.cases.len();
+        let failures = self.check();
+        if failures.is_empty() == false {
+            panic!(
+                "{} of {} cases failed:\n{}",
+                failures.len(),
+                total,
+                failures
+                    .iter()
+                    .map(|f| format!("  {}", f))
+                    .collect::<Vec<String>>()
+                    .join("\n")
+            );
+        }
+    }
+
+    /// Compares the `text` against the golden file at `path`, or writes it to the
+    /// file when blessing.
+    fn compare(path: &Path, text: &str, bless: bool) -> Option<String> {
+        if bless == true {
+            let written = match path.parent() {
+                Some(dir) => std::fs::create_dir_all(dir).and_then(|_| std::fs::write(path, text)),
+                None => std::fs::write(path, text),
+            };
+            return written
+                .err()
+                .map(|e| format!("failed to write \"{}\": {}", path.display(), e));
+        }
+        match std::fs::read_to_string(path) {
+            Ok(golden) => match golden == text {
+                true => None,
+                false => Some(format!(
+                    "output does not match \"{}\":\n--- expected\n{}\n--- actual\n{}",
+                    path.display(),
+                    golden,
+                    text
+                )),
+            },
+            Err(e) => Some(format!(
+                "failed to read \"{}\" ({}); set {} to create it",
+                path.display(),
+                e,
+                BLESS
+            )),
+        }
+    }
+}
+
+impl<T: Command + PartialEq + Debug> Default for Corpus<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::cli::stage::Memory;
+    use crate::{cli, proc, Arg, Help};
+
+    #[derive(Debug, PartialEq)]
+    struct Greet {
+        name: String,
+        loud: bool,
+    }
+
+    impl Command for Greet {
+        fn interpret(cli: &mut Cli<Memory>) -> cli::Result<Self> {
+            cli.help(Help::with("usage: greet [--loud] <name>"))?;
+            Ok(Self {
+                loud: cli.check(Arg::flag("loud"))?,
+                name: cli.require(Arg::positional("name"))?,
+            })
+        }
+
+        fn execute(self) -> proc::Result {
+            Ok(())
+        }
+    }
+
+    fn greet(name: &str, loud: bool) -> Greet {
+        Greet {
+            name: String::from(name),
+            loud: loud,
+        }
+    }
+
+    #[test]
+    fn check_cases() {
+        let failures = Corpus::<Greet>::new()
+            .ok(["greet", "--loud", "ada"], greet("ada", true))
+            .ok(["greet", "ada"], greet("ada", true))
+            .err(["greet"], ErrorKind::MissingPositional)
+            .err(["greet", "ada", "--quiet"], ErrorKind::MissingPositional)
+            .err_contains(["greet", "ada", "bob"], "invalid argument \"bob\"")
+            .check();
+        assert_eq!(failures.len(), 2);
+        assert_eq!(failures[0].args(), ["greet", "ada"]);
+        assert_eq!(
+            failures[0].reason(),
+            "expected Greet { name: \"ada\", loud: true } but got Greet { name: \"ada\", loud: false }"
+        );
+        assert!(failures[1]
+            .reason()
+            .starts_with("expected error MissingPositional but got "));
+    }
+
+    #[test]
+    fn bless_golden_files() {
+        let dir = std::env::temp_dir().join(format!("cliproc-golden-{}", std::process::id()));
+        let path = dir.join("help.txt");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let corpus = || Corpus::<Greet>::new().golden(["greet", "--help"], &path);
+        assert_eq!(corpus().bless(false).check().len(), 1);
+        assert_eq!(corpus().bless(true).check(), vec![]);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "usage: greet [--loud] <name>"
+        );
+        corpus().bless(false).run();
+
+        std::fs::write(&path, "usage: greet <name>").unwrap();
+        assert_eq!(corpus().bless(false).check().len(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
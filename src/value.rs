@@ -0,0 +1,355 @@
+//! Common value types that can be requested from the command-line.
+
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+#[cfg(feature = "url")]
+pub use url::Url;
+
+mod symbol {
+    pub const SEPARATOR: char = ',';
+    pub const SPAN: char = '-';
+}
+
+/// A set of indices written with the comma grammar (ex: `1-5,8,10-12`).
+///
+/// The indices are normalized into ascending order with overlapping and
+/// adjacent spans merged together.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Selection {
+    spans: Vec<RangeInclusive<usize>>,
+}
+
+impl Selection {
+    /// Checks if the index `i` is a part of the selection.
+    pub fn contains(&self, i: usize) -> bool {
+        self.spans.iter().any(|s| s.contains(&i))
+    }
+
+    /// Returns the number of indices in the selection.
+    ///
+    /// The length saturates at [usize::MAX] when the selection covers every index.
+    pub fn len(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|s| (s.end() - s.start()).saturating_add(1))
+            .fold(0, |sum, n| sum.saturating_add(n))
+    }
+
+    /// Checks if the selection contains no indices.
+    ///
+    /// A selection parsed from the command-line is never empty.
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// References the normalized list of inclusive spans.
+    pub fn spans(&self) -> &[RangeInclusive<usize>] {
+        &self.spans
+    }
+
+    /// Iterates through every index in the selection in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.spans.iter().flat_map(|s| s.clone())
+    }
+}
+
+impl FromStr for Selection {
+    type Err = SelectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut spans = Vec::<RangeInclusive<usize>>::new();
+        for part in s.split(symbol::SEPARATOR).map(|p| p.trim()) {
+            if part.is_empty() == true {
+                return Err(SelectionError::Empty);
+            }
+            let span = match part.split_once(symbol::SPAN) {
+                Some((lo, hi)) => {
+                    let (lo, hi) = (parse_index(lo)?, parse_index(hi)?);
+                    if lo > hi {
+                        return Err(SelectionError::Descending(lo, hi));
+                    }
+                    lo..=hi
+                }
+                None => {
+                    let i = parse_index(part)?;
+                    i..=i
+                }
+            };
+            spans.push(span);
+        }
+        // merge the overlapping and adjacent spans
+        spans.sort_by_key(|s| *s.start());
+        let mut merged = Vec::<RangeInclusive<usize>>::with_capacity(spans.len());
+        for span in spans {
+            match merged.last_mut() {
+                Some(last) if *span.start() <= last.end().saturating_add(1) => {
+                    if span.end() > last.end() {
+                        *last = *last.start()..=*span.end();
+                    }
+                }
+                _ => merged.push(span),
+            }
+        }
+        Ok(Self { spans: merged })
+    }
+}
+
+impl Display for Selection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = self
+            .spans
+            .iter()
+            .map(|s| match s.start() == s.end() {
+                true => s.start().to_string(),
+                false => format!("{}{}{}", s.start(), symbol::SPAN, s.end()),
+            })
+            .collect();
+        write!(f, "{}", parts.join(&symbol::SEPARATOR.to_string()))
+    }
+}
+
+fn parse_index(s: &str) -> Result<usize, SelectionError> {
+    s.trim()
+        .parse::<usize>()
+        .map_err(|_| SelectionError::NotIndex(s.trim().to_string()))
+}
+
+/// The reasons a [Selection] can fail to be parsed.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SelectionError {
+    Empty,
+    NotIndex(String),
+    Descending(usize, usize),
+}
+
+impl Display for SelectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "selection contains an empty entry"),
+            Self::NotIndex(s) => write!(f, "\"{}\" is not a valid index", s),
+            Self::Descending(lo, hi) => write!(
+                f,
+                "range \"{}{}{}\" must be written in ascending order",
+                lo,
+                symbol::SPAN,
+                hi
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SelectionError {}
+
+/// The formats a command can write its output in, as requested on the
+/// command-line through the common `--output <format>` option, see
+/// [check_output][super::Cli::check_output].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum OutputFormat {
+    /// A human-readable table.
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+impl OutputFormat {
+    /// Every format, in the order they are listed in errors.
+    pub const ALL: [OutputFormat; 3] = [Self::Table, Self::Json, Self::Yaml];
+
+    /// Finds the format written as `name` (ex: `json`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.name() == name)
+    }
+
+    /// Returns the name the format is written as on the command-line.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Table => "table",
+            Self::Json => "json",
+            Self::Yaml => "yaml",
+        }
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Output of a command that can be written in multiple [OutputFormat]s.
+pub trait Render {
+    /// Formats the output in the requested `format`.
+    ///
+    /// The `format` is always one of the supported [formats][Render::formats].
+    fn render(&self, format: OutputFormat) -> String;
+
+    /// Lists the formats the output can be written in.
+    ///
+    /// By default, every format is supported.
+    fn formats() -> &'static [OutputFormat]
+    where
+        Self: Sized,
+    {
+        &OutputFormat::ALL
+    }
+}
+
+/// Formats the `value` as pretty-printed JSON, such as for rendering
+/// [OutputFormat::Json] in [Render::render].
+///
+/// A value that cannot be represented as JSON (such as a map with non-string
+/// keys) is considered a programmer's error and will result in a panic!.
+#[cfg(feature = "serde")]
+pub fn to_json<T: serde::Serialize + ?Sized>(value: &T) -> String {
+    serde_json::to_string_pretty(value).expect("value cannot be represented as JSON")
+}
+
+/// How a path value is resolved against its base directory, see
+/// [relative_to][super::Arg::relative_to].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub enum PathResolution {
+    /// A relative path is joined onto the base as it was written.
+    #[default]
+    Join,
+    /// The joined path has its `.` and `..` components removed without
+    /// accessing the file system, so symbolic links are kept as written.
+    Normalize,
+    /// The joined path is made absolute with all symbolic links followed,
+    /// which requires the path to exist.
+    Canonicalize,
+}
+
+impl PathResolution {
+    /// Resolves the `path` against the directory `base` under this resolution.
+    ///
+    /// An absolute `path` is not joined onto `base`, but is still normalized or
+    /// canonicalized.
+    pub fn resolve<P: AsRef<Path>, B: AsRef<Path>>(
+        &self,
+        path: P,
+        base: B,
+    ) -> std::io::Result<PathBuf> {
+        let joined = base.as_ref().join(path);
+        match self {
+            Self::Join => Ok(joined),
+            Self::Normalize => Ok(normalize(&joined)),
+            Self::Canonicalize => joined.canonicalize(),
+        }
+    }
+}
+
+/// Lexically removes the `.` and `..` components from the `path`.
+///
+/// A `..` that cannot remove a previous component is kept, except at the root.
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for c in path.components() {
+        match c {
+            Component::CurDir => (),
+            Component::ParentDir => match result.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    result.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => (),
+                _ => result.push(c),
+            },
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn selection_from_str() {
+        let sel = Selection::from_str("1-5,8,10-12").unwrap();
+        assert_eq!(sel.spans(), &[1..=5, 8..=8, 10..=12]);
+        assert_eq!(sel.len(), 9);
+        assert_eq!(sel.contains(4), true);
+        assert_eq!(sel.contains(9), false);
+        assert_eq!(
+            sel.iter().collect::<Vec<usize>>(),
+            vec![1, 2, 3, 4, 5, 8, 10, 11, 12]
+        );
+
+        // normalizes overlapping and adjacent spans
+        let sel = Selection::from_str("8, 3-5,1-2,4-6").unwrap();
+        assert_eq!(sel.spans(), &[1..=6, 8..=8]);
+        assert_eq!(sel.to_string(), "1-6,8");
+
+        // the length does not overflow when every index is selected
+        let sel = Selection::from_str(&format!("0-{}", usize::MAX)).unwrap();
+        assert_eq!(sel.len(), usize::MAX);
+    }
+
+    #[test]
+    fn selection_errors() {
+        assert_eq!(Selection::from_str(""), Err(SelectionError::Empty));
+        assert_eq!(Selection::from_str("1,,2"), Err(SelectionError::Empty));
+        assert_eq!(
+            Selection::from_str("1-x"),
+            Err(SelectionError::NotIndex(String::from("x")))
+        );
+        assert_eq!(
+            Selection::from_str("-3"),
+            Err(SelectionError::NotIndex(String::new()))
+        );
+        assert_eq!(
+            Selection::from_str("9-2"),
+            Err(SelectionError::Descending(9, 2))
+        );
+    }
+
+    #[test]
+    fn resolve_paths() {
+        let join = PathResolution::Join;
+        assert_eq!(
+            join.resolve("src/../lib.rs", "/work/proj").unwrap(),
+            PathBuf::from("/work/proj/src/../lib.rs")
+        );
+        assert_eq!(
+            join.resolve("/etc/hosts", "/work/proj").unwrap(),
+            PathBuf::from("/etc/hosts")
+        );
+
+        let norm = PathResolution::Normalize;
+        assert_eq!(
+            norm.resolve("./src/../lib.rs", "/work/proj").unwrap(),
+            PathBuf::from("/work/proj/lib.rs")
+        );
+        assert_eq!(
+            norm.resolve("../../../x", "/work/proj").unwrap(),
+            PathBuf::from("/x")
+        );
+        assert_eq!(
+            norm.resolve("../x", "../proj").unwrap(),
+            PathBuf::from("../x")
+        );
+
+        // canonicalizing requires the path to exist
+        assert!(PathResolution::Canonicalize
+            .resolve("missing.txt", std::env::temp_dir())
+            .is_err());
+    }
+
+    #[test]
+    fn output_formats() {
+        assert_eq!(OutputFormat::from_name("yaml"), Some(OutputFormat::Yaml));
+        assert_eq!(OutputFormat::from_name("YAML"), None);
+        assert_eq!(OutputFormat::Json.to_string(), "json");
+        assert_eq!(OutputFormat::default(), OutputFormat::Table);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn output_to_json() {
+        assert_eq!(to_json(&vec!["a", "b"]), "[\n  \"a\",\n  \"b\"\n]");
+    }
+}